@@ -0,0 +1,90 @@
+//! Arguments shared by the subcommands
+
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum DelimArg {
+    Comma,
+    Tab,
+    Pipe,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ModeArg {
+    Off,
+    Safe,
+}
+
+/// Every field of `toon::Options`.
+#[derive(Args, Debug, Clone)]
+pub struct OptionArgs {
+    /// Delimiter for inline arrays and table rows
+    #[arg(long, value_enum, default_value_t = DelimArg::Comma)]
+    pub delimiter: DelimArg,
+
+    /// Spaces per indentation level
+    #[arg(long, default_value_t = 2)]
+    pub indent: usize,
+
+    /// Fold chains of single-key objects into dotted keys (`a.b: 1`)
+    #[arg(long, value_enum, default_value_t = ModeArg::Off)]
+    pub key_folding: ModeArg,
+
+    /// Most segments a folded key may have (default: no limit)
+    #[arg(long)]
+    pub flatten_depth: Option<usize>,
+
+    /// Expand dotted keys into nested objects when decoding
+    #[arg(long, value_enum, default_value_t = ModeArg::Off)]
+    pub expand_paths: ModeArg,
+
+    /// Reject anything the spec's strict mode rejects (the default)
+    #[arg(long, overrides_with = "no_strict")]
+    pub strict: bool,
+
+    /// Accept documents that only strict mode rejects
+    #[arg(long)]
+    pub no_strict: bool,
+}
+
+impl OptionArgs {
+    pub fn options(&self) -> toon::Options {
+        toon::Options {
+            delimiter: match self.delimiter {
+                DelimArg::Comma => toon::Delimiter::Comma,
+                DelimArg::Tab => toon::Delimiter::Tab,
+                DelimArg::Pipe => toon::Delimiter::Pipe,
+            },
+            strict: !self.no_strict,
+            indent: self.indent,
+            key_folding: match self.key_folding {
+                ModeArg::Off => toon::KeyFolding::Off,
+                ModeArg::Safe => toon::KeyFolding::Safe,
+            },
+            flatten_depth: self.flatten_depth,
+            expand_paths: match self.expand_paths {
+                ModeArg::Off => toon::ExpandPaths::Off,
+                ModeArg::Safe => toon::ExpandPaths::Safe,
+            },
+        }
+    }
+}
+
+impl OptionArgs {
+    pub fn format_options(&self) -> toon::FormatOptions {
+        toon::FormatOptions::from(&self.options())
+    }
+}
+
+/// Where to read from and write to.
+#[derive(Args, Debug, Clone)]
+pub struct IoArgs {
+    /// Input file (defaults to stdin)
+    pub input: Option<PathBuf>,
+
+    /// Output file (defaults to stdout)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
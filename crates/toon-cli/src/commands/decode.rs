@@ -0,0 +1,49 @@
+//! `toon-cli decode`: TOON to JSON, YAML, TOML or CSV
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use super::read_value;
+use crate::args::{IoArgs, OptionArgs};
+use crate::format::{self, Format};
+use crate::io::{read_input, write_output};
+use crate::ndjson;
+
+#[derive(Args, Debug)]
+pub struct DecodeArgs {
+    #[command(flatten)]
+    pub io: IoArgs,
+
+    #[command(flatten)]
+    pub options: OptionArgs,
+
+    /// Pretty-print the JSON
+    #[arg(long)]
+    pub pretty: bool,
+
+    /// Output format (default: from the output file's extension, else JSON)
+    #[arg(long, value_enum)]
+    pub to: Option<Format>,
+
+    /// Write each row of a root array as a line of JSON, streaming a root
+    /// table row by row
+    #[arg(long, conflicts_with_all = ["to", "pretty"])]
+    pub ndjson: bool,
+}
+
+pub fn run(args: &DecodeArgs) -> Result<()> {
+    let path = args.io.input.as_deref();
+    if args.ndjson {
+        return ndjson::decode(path, args.io.output.as_deref(), &args.options.options());
+    }
+    let input = read_input(path)?;
+    let options = args.options.options();
+    let value = read_value(path, &input, Format::Toon, &options)?;
+    let to = args
+        .to
+        .or_else(|| args.io.output.as_deref().and_then(Format::from_path))
+        .unwrap_or(Format::Json);
+    let out = format::write(&value, to, &options, args.pretty)
+        .with_context(|| format!("cannot write the document as {}", to))?;
+    write_output(args.io.output.as_deref(), &out)
+}
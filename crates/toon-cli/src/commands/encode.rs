@@ -0,0 +1,53 @@
+//! `toon-cli encode`: JSON, YAML, TOML or CSV to TOON
+
+use anyhow::Result;
+use clap::Args;
+
+use super::read_value;
+use crate::Reported;
+use crate::args::{IoArgs, OptionArgs};
+use crate::format::Format;
+use crate::io::{input_name, read_input, write_output};
+use crate::ndjson;
+
+#[derive(Args, Debug)]
+pub struct EncodeArgs {
+    #[command(flatten)]
+    pub io: IoArgs,
+
+    #[command(flatten)]
+    pub options: OptionArgs,
+
+    /// Input format (default: from the file extension, else the content, else JSON)
+    #[arg(long, value_enum)]
+    pub from: Option<Format>,
+
+    /// Read one JSON record per line and write them as a single array,
+    /// streaming instead of holding the input in memory
+    #[arg(long, conflicts_with = "from")]
+    pub ndjson: bool,
+
+    /// Decode the output again and compare it with the input; on a
+    /// difference, name the first path that differs and write nothing
+    #[arg(long, visible_alias = "check-roundtrip", conflicts_with = "ndjson")]
+    pub verify: bool,
+}
+
+pub fn run(args: &EncodeArgs) -> Result<()> {
+    let path = args.io.input.as_deref();
+    if args.ndjson {
+        return ndjson::encode(path, args.io.output.as_deref(), &args.options.options());
+    }
+    let input = read_input(path)?;
+    let options = args.options.options();
+    let format = Format::detect(args.from, path, &input, Format::Json);
+    let value = read_value(path, &input, format, &options)?;
+    let out = toon::encode_to_string(&value, &options)?;
+    if args.verify {
+        if let Err(e) = toon::verify_roundtrip(&value, &out, &options) {
+            eprintln!("error: {}: round trip failed: {}", input_name(path), e);
+            return Err(Reported.into());
+        }
+    }
+    write_output(args.io.output.as_deref(), &out)
+}
@@ -0,0 +1,75 @@
+//! `toon-cli fmt`: rewrite TOON documents in canonical layout
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use super::{report, usage};
+use crate::Reported;
+use crate::args::OptionArgs;
+use crate::io::{input_name, read_input, write_output};
+
+#[derive(Args, Debug)]
+pub struct FmtArgs {
+    /// Files to format (defaults to stdin)
+    pub inputs: Vec<PathBuf>,
+
+    /// Output file (defaults to stdout); needs a single input
+    #[arg(short, long, conflicts_with_all = ["check", "write"])]
+    pub output: Option<PathBuf>,
+
+    /// Only report inputs that are not formatted, and exit with 1 if any
+    #[arg(long)]
+    pub check: bool,
+
+    /// Rewrite the input files in place
+    #[arg(short, long, conflicts_with = "check")]
+    pub write: bool,
+
+    #[command(flatten)]
+    pub options: OptionArgs,
+}
+
+pub fn run(args: &FmtArgs) -> Result<()> {
+    let inputs = if args.inputs.is_empty() {
+        if args.write {
+            return Err(usage("--write needs input files"));
+        }
+        vec![None]
+    } else {
+        args.inputs.iter().map(|p| Some(p.as_path())).collect()
+    };
+    if inputs.len() > 1 && !args.check && !args.write {
+        return Err(usage("formatting several files needs --check or --write"));
+    }
+    let options = args.options.format_options();
+    let mut unformatted = false;
+    for path in inputs {
+        let input = read_input(path)?;
+        let name = input_name(path);
+        let out = toon::fmt(&input, &options).map_err(|e| report(&name, &input, &e))?;
+        let formatted = input.strip_suffix('\n').unwrap_or(&input) == out;
+        match path {
+            _ if args.check => {
+                if !formatted {
+                    println!("{}: not formatted", name);
+                    unformatted = true;
+                }
+            }
+            Some(path) if args.write => {
+                if !formatted {
+                    fs::write(path, format!("{}\n", out))
+                        .with_context(|| format!("cannot write {}", path.display()))?;
+                }
+            }
+            _ => write_output(args.output.as_deref(), &out)?,
+        }
+    }
+    if unformatted {
+        Err(Reported.into())
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+//! One module per subcommand
+
+pub mod decode;
+pub mod encode;
+pub mod fmt;
+pub mod stats;
+pub mod validate;
+
+use std::path::Path;
+
+use crate::Reported;
+use crate::format::{self, Format};
+use crate::io::input_name;
+
+/// Print a decode error for the document `name` with the offending line, and
+/// return the error that makes `main` exit with the "invalid input" code.
+/// Streamed input has no `source` to quote; pass `""`.
+pub fn report(name: &str, source: &str, err: &toon::Error) -> anyhow::Error {
+    eprintln!("error: {}: {}", name, err);
+    if let Some(snippet) = err
+        .snippet(source)
+        .filter(|s| !source.is_empty() && !s.is_empty())
+    {
+        eprintln!("{}", snippet);
+    }
+    Reported.into()
+}
+
+/// Parse the input read from `path` as `format`, reporting TOON errors with
+/// [`report`].
+pub fn read_value(
+    path: Option<&Path>,
+    input: &str,
+    format: Format,
+    options: &toon::Options,
+) -> anyhow::Result<toon::Value> {
+    let name = input_name(path);
+    format::read(input, format, options).map_err(|e| match e.downcast::<toon::Error>() {
+        Ok(e) => report(&name, input, &e),
+        Err(e) => e.context(format!("{} is not valid {}", name, format)),
+    })
+}
+
+/// A command-line usage error, which `main` reports like clap's own.
+pub fn usage(message: &str) -> anyhow::Error {
+    clap::Error::raw(
+        clap::error::ErrorKind::ArgumentConflict,
+        format!("{}\n", message),
+    )
+    .into()
+}
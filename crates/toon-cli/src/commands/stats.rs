@@ -0,0 +1,106 @@
+//! `toon-cli stats`: compare the size of a document as JSON and as TOON, in
+//! bytes and in (estimated) tokens
+
+use anyhow::{Context, Result};
+use clap::Args;
+
+use toon::stats::{ApproxTokenizer, Comparison, Size, Tokenizer};
+
+use super::report;
+use crate::args::{IoArgs, OptionArgs};
+use crate::io::{input_name, read_input, write_output};
+
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+    #[command(flatten)]
+    pub io: IoArgs,
+
+    #[command(flatten)]
+    pub options: OptionArgs,
+}
+
+pub fn run(args: &StatsArgs) -> Result<()> {
+    let input = read_input(args.io.input.as_deref())?;
+    let options = args.options.options();
+    // JSON if it parses as JSON, TOON otherwise
+    let value: toon::Value = match serde_json::from_str(&input) {
+        Ok(value) => value,
+        Err(_) => toon::decode_from_str(&input, &options)
+            .map_err(|e| report(&input_name(args.io.input.as_deref()), &input, &e))?,
+    };
+    let report =
+        toon::stats::report(&value, &options, &ApproxTokenizer).context("cannot encode as TOON")?;
+    let pretty = serde_json::to_string_pretty(&value)?;
+    let toon = toon::encode_to_string(&value, &options).context("cannot encode as TOON")?;
+
+    let total = report.total;
+    let saved = 100.0 * (1.0 - total.toon.bytes as f64 / total.json.bytes.max(1) as f64);
+    let rows = [
+        ("JSON (compact)", total.json, 1),
+        (
+            "JSON (pretty)",
+            Size {
+                bytes: pretty.len(),
+                tokens: ApproxTokenizer.count_tokens(&pretty),
+            },
+            pretty.lines().count(),
+        ),
+        ("TOON", total.toon, toon.lines().count()),
+    ];
+    let mut out = String::new();
+    for (name, size, lines) in rows {
+        out.push_str(&format!(
+            "{:<15} {:>10} bytes {:>8} tokens {:>7} lines\n",
+            name, size.bytes, size.tokens, lines
+        ));
+    }
+    out.push_str(&format!(
+        "TOON is {:.1}% smaller than compact JSON, with {} fewer tokens",
+        saved,
+        percent(&total)
+    ));
+
+    // Token counts JSON -> TOON for each part of the document
+    let width = report
+        .subtrees
+        .iter()
+        .map(|s| s.path.len())
+        .chain(report.tables.iter().map(|t| t.path.len().max(6)))
+        .max()
+        .unwrap_or(0);
+    if !report.subtrees.is_empty() {
+        out.push_str("\n\nTokens by subtree (JSON -> TOON):");
+        for subtree in &report.subtrees {
+            out.push_str(&line(&subtree.path, width, &subtree.size, ""));
+        }
+    }
+    if !report.tables.is_empty() {
+        out.push_str("\n\nTokens by table (JSON -> TOON):");
+        for table in &report.tables {
+            let path = if table.path.is_empty() {
+                "(root)"
+            } else {
+                &table.path
+            };
+            let shape = format!("  {} rows x {} fields", table.rows, table.fields.len());
+            out.push_str(&line(path, width, &table.size, &shape));
+        }
+    }
+    write_output(args.io.output.as_deref(), &out)
+}
+
+fn line(path: &str, width: usize, size: &Comparison, suffix: &str) -> String {
+    format!(
+        "\n  {:<width$} {:>8} -> {:>8}  {:>7}{}",
+        path,
+        size.json.tokens,
+        size.toon.tokens,
+        percent(size),
+        suffix,
+    )
+}
+
+/// The token savings as a percentage, such as `41.2%`.
+fn percent(size: &Comparison) -> String {
+    format!("{:.1}%", 100.0 * size.token_savings())
+}
@@ -0,0 +1,232 @@
+//! `toon-cli validate`: report every problem in TOON documents
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use serde_json::{Value, json};
+use toon::{Diagnostic, ErrorKind, Severity, Span};
+
+use crate::args::OptionArgs;
+use crate::io::{input_name, read_input};
+use crate::{Reported, ReportedIo};
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// Files to check (defaults to stdin)
+    pub inputs: Vec<PathBuf>,
+
+    /// How to print the diagnostics
+    #[arg(long, value_enum, default_value_t = ReportFormat::Text)]
+    pub format: ReportFormat,
+
+    #[command(flatten)]
+    pub options: OptionArgs,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    /// `file:line:column: severity: message` and the offending line
+    Text,
+    /// A JSON array with one object per diagnostic
+    Json,
+    /// A SARIF 2.1.0 log, for code-scanning dashboards
+    Sarif,
+}
+
+pub fn run(args: &ValidateArgs) -> Result<()> {
+    let inputs = if args.inputs.is_empty() {
+        vec![None]
+    } else {
+        args.inputs.iter().map(|p| Some(p.as_path())).collect()
+    };
+    let options = args.options.options();
+    let (mut failed, mut unreadable) = (false, false);
+    let mut found = Vec::new();
+    for path in inputs {
+        let name = input_name(path);
+        // A file that cannot be read is reported like any other problem, so
+        // the rest are still checked
+        let (input, diagnostics) = match read_input(path) {
+            Ok(input) => {
+                let diagnostics = toon::validate(&input, &options);
+                (input, diagnostics)
+            }
+            Err(e) => {
+                unreadable = true;
+                (String::new(), vec![io_error(&e)])
+            }
+        };
+        for d in diagnostics {
+            failed |= d.is_error();
+            if let ReportFormat::Text = args.format {
+                print_text(&name, &input, &d);
+            } else {
+                found.push(Found {
+                    uri: path.map(uri),
+                    width: width(&input, &d),
+                    name: name.clone(),
+                    d,
+                });
+            }
+        }
+    }
+    match args.format {
+        ReportFormat::Text => {}
+        ReportFormat::Json => {
+            let list: Vec<Value> = found.iter().map(to_json).collect();
+            println!("{}", serde_json::to_string_pretty(&list)?);
+        }
+        ReportFormat::Sarif => println!("{}", serde_json::to_string_pretty(&to_sarif(&found))?),
+    }
+    if unreadable {
+        Err(ReportedIo.into())
+    } else if failed {
+        Err(Reported.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// A diagnostic and where it was found.
+struct Found {
+    name: String,
+    /// URI reference of the file; `None` for stdin
+    uri: Option<String>,
+    /// Characters covered by the span
+    width: usize,
+    d: Diagnostic,
+}
+
+/// The diagnostic for a file that could not be read, which has no line.
+fn io_error(e: &anyhow::Error) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Error,
+        kind: ErrorKind::Io,
+        message: format!("{:#}", e),
+        span: Span::default(),
+    }
+}
+
+/// Characters of `input` covered by the span of `d`.
+fn width(input: &str, d: &Diagnostic) -> usize {
+    input
+        .get(d.span.offset..d.span.offset + d.span.len)
+        .map_or(d.span.len, |s| s.chars().count())
+}
+
+/// `path` as a URI reference: relative paths stay relative, absolute ones
+/// become `file:` URIs, and bytes outside the unreserved set are escaped.
+fn uri(path: &Path) -> String {
+    let text = path.display().to_string().replace('\\', "/");
+    let mut out = String::new();
+    if path.is_absolute() {
+        out.push_str(if text.starts_with('/') {
+            "file://"
+        } else {
+            "file:///"
+        });
+    }
+    for b in text.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                out.push(b as char)
+            }
+            b':' if path.is_absolute() => out.push(':'),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+fn print_text(name: &str, input: &str, d: &Diagnostic) {
+    let at = match (d.span.line, d.span.column) {
+        (0, _) => name.to_string(),
+        (line, 0) => format!("{}:{}", name, line),
+        (line, column) => format!("{}:{}:{}", name, line, column),
+    };
+    println!("{}: {}: {}", at, severity(d), d.message);
+    let snippet = d.snippet(input);
+    if !snippet.is_empty() {
+        println!("{}", snippet);
+    }
+}
+
+fn severity(d: &Diagnostic) -> &'static str {
+    match d.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+/// Column 0 means the position on the line is unknown.
+fn column(d: &Diagnostic) -> Option<usize> {
+    (d.span.column > 0).then_some(d.span.column)
+}
+
+/// Line 0 means the problem is with the file as a whole.
+fn line(d: &Diagnostic) -> Option<usize> {
+    (d.span.line > 0).then_some(d.span.line)
+}
+
+fn to_json(Found { name, d, .. }: &Found) -> Value {
+    json!({
+        "file": name,
+        "line": line(d),
+        "column": column(d),
+        "severity": severity(d),
+        "rule": d.kind.as_str(),
+        "message": d.message,
+    })
+}
+
+fn to_sarif(found: &[Found]) -> Value {
+    let mut rules: Vec<&str> = Vec::new();
+    let results: Vec<Value> = found
+        .iter()
+        .map(|Found { uri, width, d, .. }| {
+            let rule = d.kind.as_str();
+            let index = rules.iter().position(|r| *r == rule).unwrap_or_else(|| {
+                rules.push(rule);
+                rules.len() - 1
+            });
+            // Stdin has no URI, so its location is only described
+            let mut location = json!({
+                "artifactLocation": match uri {
+                    Some(uri) => json!({ "uri": uri }),
+                    None => json!({ "description": { "text": "standard input" } }),
+                },
+            });
+            if let Some(line) = line(d) {
+                let mut region = json!({ "startLine": line });
+                if let Some(column) = column(d) {
+                    region["startColumn"] = json!(column);
+                    region["endColumn"] = json!(column + (*width).max(1));
+                }
+                location["region"] = region;
+            }
+            json!({
+                "ruleId": rule,
+                "ruleIndex": index,
+                "level": severity(d),
+                "message": { "text": d.message },
+                "locations": [{ "physicalLocation": location }],
+            })
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "toon-cli",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://toonformat.dev",
+                    "rules": rules.iter().map(|id| json!({ "id": id })).collect::<Vec<_>>(),
+                }
+            },
+            "results": results,
+        }]
+    })
+}
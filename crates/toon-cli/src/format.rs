@@ -0,0 +1,206 @@
+//! The formats `encode` reads and `decode` writes
+//!
+//! Every conversion goes through a [`toon::Value`]: other formats are read
+//! and written with their serde implementations, CSV and TSV with
+//! `toon::csv`, and TOON with the crate's own `decode_from_str` and
+//! `encode_to_string`.
+
+use std::fmt;
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use clap::ValueEnum;
+use serde::de::IgnoredAny;
+use toon::csv::CsvOptions;
+use toon::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+    Csv,
+    Tsv,
+    Toon,
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::Json => "JSON",
+            Format::Yaml => "YAML",
+            Format::Toml => "TOML",
+            Format::Csv => "CSV",
+            Format::Tsv => "TSV",
+            Format::Toon => "TOON",
+        })
+    }
+}
+
+impl Format {
+    /// The format a file name's extension stands for.
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        Some(match ext.as_str() {
+            "json" => Format::Json,
+            "yaml" | "yml" => Format::Yaml,
+            "toml" => Format::Toml,
+            "csv" => Format::Csv,
+            "tsv" => Format::Tsv,
+            "toon" => Format::Toon,
+            _ => return None,
+        })
+    }
+
+    /// The format of an input: the one asked for, else the one its extension
+    /// names, else whatever its content gives away, else `default`.
+    pub fn detect(
+        explicit: Option<Format>,
+        path: Option<&Path>,
+        text: &str,
+        default: Format,
+    ) -> Format {
+        explicit
+            .or_else(|| path.and_then(Format::from_path))
+            .or_else(|| sniff(text))
+            .unwrap_or(default)
+    }
+}
+
+/// Guess a format from the first lines that only one format would write.
+fn sniff(text: &str) -> Option<Format> {
+    let start = text.trim_start();
+    if start.starts_with(['{', '[']) && serde_json::from_str::<IgnoredAny>(start).is_ok() {
+        return Some(Format::Json);
+    }
+    for line in text.lines().map(str::trim_end) {
+        if line.trim_start().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line == "---" || line.starts_with("%YAML") || line.starts_with("- ") {
+            return Some(Format::Yaml);
+        }
+        if is_toml_line(line) {
+            return Some(Format::Toml);
+        }
+        if is_toon_header(line) {
+            return Some(Format::Toon);
+        }
+    }
+    for format in [Format::Tsv, Format::Csv] {
+        if is_table(text, format) {
+            return Some(format);
+        }
+    }
+    // Malformed JSON is still best reported as JSON
+    start.starts_with(['{', '[']).then_some(Format::Json)
+}
+
+/// `[table]`, `[[array]]` or `key = value`.
+fn is_toml_line(line: &str) -> bool {
+    let bare = |key: &str| {
+        let key = key.trim();
+        !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '"' | ' '))
+    };
+    if let Some(inner) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+        let inner = inner
+            .strip_prefix('[')
+            .and_then(|l| l.strip_suffix(']'))
+            .unwrap_or(inner);
+        return bare(inner) && !inner.trim().starts_with(|c: char| c.is_ascii_digit());
+    }
+    line.split_once('=').is_some_and(|(key, _)| bare(key))
+}
+
+/// A TOON array header such as `users[2]{id,name}:` or `[3]: 1,2,3`.
+fn is_toon_header(line: &str) -> bool {
+    let Some((key, _)) = line.split_once(':') else {
+        return false;
+    };
+    key.find('[').is_some_and(|open| {
+        key[open + 1..].starts_with(|c: char| c.is_ascii_digit())
+            && (key.ends_with(']') || key.ends_with('}'))
+    })
+}
+
+/// A header row of at least two names and records that all match it.
+fn is_table(text: &str, format: Format) -> bool {
+    let Ok(Value::Array(rows)) = toon::csv::from_csv(text, &csv_options(format)) else {
+        return false;
+    };
+    rows.first()
+        .and_then(Value::as_object)
+        .is_some_and(|row| row.len() >= 2 && row.keys().all(|k| !k.contains(':')))
+}
+
+fn csv_options(format: Format) -> CsvOptions {
+    match format {
+        Format::Tsv => CsvOptions::tsv(),
+        _ => CsvOptions::default(),
+    }
+}
+
+/// Parse `text` as `format`.
+pub fn read(text: &str, format: Format, options: &toon::Options) -> Result<Value> {
+    Ok(match format {
+        Format::Json => serde_json::from_str(text)?,
+        Format::Yaml => serde_yaml_ng::from_str(text)?,
+        Format::Toml => from_toml(toml::Value::Table(toml::from_str(text)?)),
+        Format::Csv | Format::Tsv => toon::csv::from_csv(text, &csv_options(format))?,
+        Format::Toon => toon::decode_from_str(text, options)?,
+    })
+}
+
+/// Render `value` as `format`, without a final newline.
+pub fn write(
+    value: &Value,
+    format: Format,
+    options: &toon::Options,
+    pretty: bool,
+) -> Result<String> {
+    let mut text = match format {
+        Format::Json if pretty => serde_json::to_string_pretty(value)?,
+        Format::Json => serde_json::to_string(value)?,
+        Format::Yaml => serde_yaml_ng::to_string(value)?,
+        Format::Toml => toml::to_string(value)?,
+        Format::Csv | Format::Tsv => write_table(value, format)?,
+        Format::Toon => toon::encode_to_string(value, options)?,
+    };
+    text.truncate(text.trim_end_matches('\n').len());
+    Ok(text)
+}
+
+// TOML datetimes have no counterpart in the data model, so they become
+// strings instead of serde's private marker struct.
+fn from_toml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(from_toml).collect()),
+        toml::Value::Table(table) => {
+            let mut map = Map::new();
+            for (key, value) in table {
+                map.insert(key, from_toml(value));
+            }
+            Value::Object(map)
+        }
+    }
+}
+
+/// An array of flat objects, or an object holding just one, as CSV or TSV.
+fn write_table(value: &Value, format: Format) -> Result<String> {
+    let rows = match value {
+        Value::Object(map) if map.len() == 1 => map.values().next().unwrap_or(value),
+        _ => value,
+    };
+    if !rows.is_array() {
+        bail!("{} output needs an array of objects", format);
+    }
+    Ok(toon::csv::to_csv(rows, "", &csv_options(format))?)
+}
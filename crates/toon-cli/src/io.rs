@@ -0,0 +1,42 @@
+//! Reading input and writing output
+
+use std::fs;
+use std::io::{Read, Write, stdin, stdout};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+/// The contents of `path`, or all of stdin.
+pub fn read_input(path: Option<&Path>) -> Result<String> {
+    match path {
+        Some(path) => {
+            fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))
+        }
+        None => {
+            let mut buf = String::new();
+            stdin()
+                .read_to_string(&mut buf)
+                .context("cannot read stdin")?;
+            Ok(buf)
+        }
+    }
+}
+
+/// Name of the input for messages.
+pub fn input_name(path: Option<&Path>) -> String {
+    path.map_or_else(|| "<stdin>".to_string(), |p| p.display().to_string())
+}
+
+/// Write `text` and a final newline to `path`, or to stdout.
+pub fn write_output(path: Option<&Path>, text: &str) -> Result<()> {
+    match path {
+        Some(path) => fs::write(path, format!("{}\n", text))
+            .with_context(|| format!("cannot write {}", path.display())),
+        None => {
+            let mut out = stdout().lock();
+            writeln!(out, "{}", text)
+                .and_then(|()| out.flush())
+                .context("cannot write stdout")
+        }
+    }
+}
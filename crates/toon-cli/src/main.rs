@@ -1,84 +1,150 @@
-use std::fs::File;
-use std::io::{Read, stdin};
-use std::path::PathBuf;
+//! `toon-cli`: convert, format and check TOON documents
+//!
+//! Exit codes: 0 on success, 1 when the input is invalid (or a check fails),
+//! 2 for usage errors, 3 when a file cannot be read or written.
+
+mod args;
+mod commands;
+mod format;
+mod io;
+mod ndjson;
+
+use std::process::ExitCode;
 
 use anyhow::Result;
-use clap::{Parser, ValueEnum};
+use clap::{Args, Parser, Subcommand};
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
-enum DelimArg {
-    Comma,
-    Tab,
-    Pipe,
-}
+use crate::args::{IoArgs, OptionArgs};
+use crate::commands::{decode, encode, fmt, stats, validate};
+
+const EXIT_INVALID: u8 = 1;
+const EXIT_IO: u8 = 3;
 
 #[derive(Parser, Debug)]
 #[command(
     name = "toon-cli",
-    about = "CLI for JSON ↔ TOON conversion (WIP)",
-    version
+    about = "Convert, format and check TOON documents",
+    version,
+    args_conflicts_with_subcommands = true
 )]
-struct Args {
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Without a subcommand, behaves like `encode`, or `decode` with `--decode`
+    #[command(flatten)]
+    legacy: LegacyArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Encode JSON, YAML, TOML or CSV as TOON
+    Encode(encode::EncodeArgs),
+    /// Decode TOON to JSON, YAML, TOML or CSV
+    Decode(decode::DecodeArgs),
+    /// Rewrite TOON in canonical layout
+    Fmt(fmt::FmtArgs),
+    /// Report every problem in TOON documents
+    Validate(validate::ValidateArgs),
+    /// Compare the size of a document as JSON and as TOON
+    Stats(stats::StatsArgs),
+}
+
+#[derive(Args, Debug)]
+struct LegacyArgs {
     /// Decode TOON to JSON (default encodes JSON to TOON)
     #[arg(short, long)]
     decode: bool,
 
-    /// Delimiter for TOON arrays (when applicable)
-    #[arg(long, value_enum, default_value_t = DelimArg::Comma)]
-    delimiter: DelimArg,
-
-    /// Strict mode validation (enabled by default per spec)
-    #[arg(long, default_value_t = true)]
-    strict: bool,
-
     /// Pretty-print JSON on output (when decoding)
-    #[arg(long, default_value_t = false)]
+    #[arg(long)]
     pretty: bool,
 
-    /// Input file (defaults to stdin)
-    input: Option<PathBuf>,
+    #[command(flatten)]
+    io: IoArgs,
+
+    #[command(flatten)]
+    options: OptionArgs,
 }
 
-fn main() -> Result<()> {
-    let args = Args::parse();
+/// A failure the command has already described on stderr or stdout.
+#[derive(Debug)]
+pub struct Reported;
 
-    let mut buf = String::new();
-    match &args.input {
-        Some(path) => {
-            let mut f = File::open(path)?;
-            f.read_to_string(&mut buf)?;
-        }
-        None => {
-            stdin().read_to_string(&mut buf)?;
-        }
+impl std::fmt::Display for Reported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid input")
     }
+}
 
-    let delimiter = match args.delimiter {
-        DelimArg::Comma => toon::Delimiter::Comma,
-        DelimArg::Tab => toon::Delimiter::Tab,
-        DelimArg::Pipe => toon::Delimiter::Pipe,
-    };
-    let options = toon::Options {
-        delimiter,
-        strict: args.strict,
-        indent: 2,
-        key_folding: toon::KeyFolding::Off,
-        flatten_depth: None,
-        expand_paths: toon::ExpandPaths::Off,
-    };
+impl std::error::Error for Reported {}
+
+/// Like [`Reported`], for a file that could not be read or written.
+#[derive(Debug)]
+pub struct ReportedIo;
+
+impl std::fmt::Display for ReportedIo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("cannot read or write a file")
+    }
+}
 
-    if args.decode {
-        let value: serde_json::Value = toon::decode_from_str(&buf, &options)?;
-        if args.pretty {
-            println!("{}", serde_json::to_string_pretty(&value)?);
-        } else {
-            println!("{}", serde_json::to_string(&value)?);
+impl std::error::Error for ReportedIo {}
+
+fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        Some(Command::Encode(args)) => encode::run(&args),
+        Some(Command::Decode(args)) => decode::run(&args),
+        Some(Command::Fmt(args)) => fmt::run(&args),
+        Some(Command::Validate(args)) => validate::run(&args),
+        Some(Command::Stats(args)) => stats::run(&args),
+        None => {
+            let LegacyArgs {
+                decode,
+                pretty,
+                io,
+                options,
+            } = cli.legacy;
+            if decode {
+                decode::run(&decode::DecodeArgs {
+                    io,
+                    options,
+                    pretty,
+                    to: None,
+                    ndjson: false,
+                })
+            } else {
+                encode::run(&encode::EncodeArgs {
+                    io,
+                    options,
+                    from: None,
+                    ndjson: false,
+                    verify: false,
+                })
+            }
         }
-    } else {
-        let value: serde_json::Value = serde_json::from_str(&buf)?;
-        let out = toon::encode_to_string(&value, &options)?;
-        println!("{}", out);
     }
+}
 
-    Ok(())
+fn main() -> ExitCode {
+    let Err(e) = run(Cli::parse()) else {
+        return ExitCode::SUCCESS;
+    };
+    if e.is::<Reported>() {
+        return ExitCode::from(EXIT_INVALID);
+    }
+    if e.is::<ReportedIo>() {
+        return ExitCode::from(EXIT_IO);
+    }
+    if let Some(usage) = e.downcast_ref::<clap::Error>() {
+        usage.exit();
+    }
+    eprintln!("error: {:#}", e);
+    let io = e.chain().any(|cause| {
+        cause.is::<std::io::Error>()
+            || cause
+                .downcast_ref::<toon::Error>()
+                .is_some_and(|e| e.kind() == toon::ErrorKind::Io)
+    });
+    ExitCode::from(if io { EXIT_IO } else { EXIT_INVALID })
 }
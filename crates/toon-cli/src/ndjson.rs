@@ -0,0 +1,273 @@
+//! Streaming NDJSON (JSON Lines) conversion
+//!
+//! `encode --ndjson` turns one JSON record per line into a single root array
+//! in two passes: the first learns the length `N` and whether the records
+//! share a key set of primitive values, the second writes them as table rows
+//! (or list items, or inline values). Only one record is held at a time; stdin
+//! is spilled to a temporary file so it can be read twice.
+//!
+//! `decode --ndjson` writes a root table row by row with
+//! [`toon::TableReader`], which fails if the document goes on after the
+//! table. Any other array is decoded whole first.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Cursor, Read, Write, stdin, stdout};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, bail};
+use toon::encode::primitives;
+use toon::{Delimiter, Options, TableReader, Value};
+
+use crate::commands::report;
+use crate::io::input_name;
+
+/// Encode the NDJSON records of `input` as one TOON array.
+pub fn encode(input: Option<&Path>, output: Option<&Path>, options: &Options) -> Result<()> {
+    let name = input_name(input);
+    let spill;
+    let path = match input {
+        Some(path) => path,
+        None => {
+            spill = Spill::from_stdin()?;
+            &spill.path
+        }
+    };
+
+    let mut shape = Shape::default();
+    for_each_record(path, &name, |value| {
+        shape.add(&value);
+        Ok(())
+    })?;
+
+    let mut out = Output::create(output)?;
+    let delimiter = options.delimiter;
+    match shape.fields.filter(|_| shape.tabular) {
+        Some(fields) => {
+            let mut table = toon::TableWriter::new(&mut out, "", &fields, shape.len, options)?;
+            for_each_record(path, &name, |value| Ok(table.write_row(&value)?))?;
+            table.finish()?;
+        }
+        None if shape.primitive && shape.len > 0 => {
+            let separator = primitives::delimiter_char(delimiter).to_string();
+            write!(
+                out,
+                "{}",
+                primitives::format_inline_array_header(shape.len, delimiter)
+            )?;
+            let mut first = true;
+            for_each_record(path, &name, |value| {
+                if !first {
+                    out.write_all(separator.as_bytes())?;
+                }
+                first = false;
+                Ok(out.write_all(token(&value, delimiter).as_bytes())?)
+            })?;
+        }
+        None => {
+            write!(
+                out,
+                "{}",
+                primitives::format_expanded_array_header(shape.len, delimiter)
+            )?;
+            for_each_record(path, &name, |value| {
+                for line in toon::ser::list_item_lines(&value, options)? {
+                    write!(out, "\n{}", line)?;
+                }
+                Ok(())
+            })?;
+        }
+    }
+    out.write_all(b"\n")?;
+    out.finish()
+}
+
+/// Write each row of the TOON array in `input` as a line of JSON.
+pub fn decode(input: Option<&Path>, output: Option<&Path>, options: &Options) -> Result<()> {
+    let name = input_name(input);
+    let mut reader: Box<dyn BufRead> = match input {
+        Some(path) => Box::new(BufReader::new(
+            File::open(path).with_context(|| format!("cannot read {}", path.display()))?,
+        )),
+        None => Box::new(BufReader::new(stdin())),
+    };
+    // Everything up to the first line with content, which says whether the
+    // document is a root table
+    let mut head = String::new();
+    while head.trim().is_empty() {
+        if reader
+            .read_line(&mut head)
+            .with_context(|| format!("cannot read {}", name))?
+            == 0
+        {
+            break;
+        }
+    }
+    let is_table = TableReader::<Value, _>::new(head.as_bytes(), options).is_ok();
+    let mut reader = Cursor::new(head).chain(reader);
+    let mut out = Output::create(output)?;
+
+    if is_table {
+        let rows = TableReader::<serde_json::Value, _>::new(BufReader::new(reader), options)
+            .map_err(|e| report(&name, "", &e))?;
+        for row in rows {
+            let row = row.map_err(|e| report(&name, "", &e))?;
+            serde_json::to_writer(&mut out, &row)?;
+            out.write_all(b"\n")?;
+        }
+        return out.finish();
+    }
+
+    let mut text = String::new();
+    reader
+        .read_to_string(&mut text)
+        .with_context(|| format!("cannot read {}", name))?;
+    let value: Value =
+        toon::decode_from_str(&text, options).map_err(|e| report(&name, &text, &e))?;
+    let rows = match value {
+        Value::Array(rows) => rows,
+        Value::Object(map) if map.len() == 1 && map.values().all(Value::is_array) => {
+            match map.into_iter().next() {
+                Some((_, Value::Array(rows))) => rows,
+                _ => unreachable!("checked above"),
+            }
+        }
+        _ => bail!("{} is not an array, so it has no rows to write", name),
+    };
+    for row in rows {
+        serde_json::to_writer(&mut out, &row)?;
+        out.write_all(b"\n")?;
+    }
+    out.finish()
+}
+
+/// What the first pass learns about the records.
+#[derive(Default)]
+struct Shape {
+    len: usize,
+    /// Keys of the first record, if it is an object
+    fields: Option<Vec<String>>,
+    /// Every record is an object of primitives with the first one's keys
+    tabular: bool,
+    /// Every record is a primitive
+    primitive: bool,
+}
+
+impl Shape {
+    fn add(&mut self, value: &Value) {
+        let object = value.as_object();
+        if self.len == 0 {
+            self.fields = object
+                .filter(|o| !o.is_empty())
+                .map(|o| o.keys().cloned().collect());
+            self.tabular = self.fields.is_some();
+            self.primitive = true;
+        }
+        self.len += 1;
+        self.primitive &= value.is_primitive();
+        self.tabular &= match (object, &self.fields) {
+            (Some(object), Some(fields)) => {
+                object.len() == fields.len()
+                    && fields
+                        .iter()
+                        .all(|f| object.get(f).is_some_and(Value::is_primitive))
+            }
+            _ => false,
+        };
+    }
+}
+
+/// Parse every non-blank line of `path` as JSON and hand it to `f`.
+fn for_each_record(path: &Path, name: &str, mut f: impl FnMut(Value) -> Result<()>) -> Result<()> {
+    let file = File::open(path).with_context(|| format!("cannot read {}", path.display()))?;
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.with_context(|| format!("cannot read {}", name))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}: not a JSON record", name, i + 1))?;
+        f(value)?;
+    }
+    Ok(())
+}
+
+/// A primitive as it appears inside an array.
+fn token(value: &Value, delimiter: Delimiter) -> String {
+    match value {
+        Value::String(s) => primitives::format_string(s, delimiter),
+        Value::Bool(b) => primitives::format_bool(*b).to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => primitives::format_null().to_string(),
+    }
+}
+
+/// Buffered output to a file or stdout.
+struct Output {
+    inner: BufWriter<Box<dyn Write>>,
+    name: String,
+}
+
+impl Output {
+    fn create(path: Option<&Path>) -> Result<Self> {
+        let (inner, name): (Box<dyn Write>, String) = match path {
+            Some(path) => (
+                Box::new(
+                    File::create(path)
+                        .with_context(|| format!("cannot write {}", path.display()))?,
+                ),
+                path.display().to_string(),
+            ),
+            None => (Box::new(stdout().lock()), "stdout".to_string()),
+        };
+        Ok(Output {
+            inner: BufWriter::new(inner),
+            name,
+        })
+    }
+
+    fn finish(mut self) -> Result<()> {
+        self.inner
+            .flush()
+            .with_context(|| format!("cannot write {}", self.name))
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Stdin copied to a temporary file, removed on drop.
+struct Spill {
+    path: PathBuf,
+}
+
+impl Spill {
+    fn from_stdin() -> Result<Self> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        let path =
+            std::env::temp_dir().join(format!("toon-cli-{}-{}.ndjson", std::process::id(), nanos));
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .with_context(|| format!("cannot write {}", path.display()))?;
+        let spill = Spill { path };
+        std::io::copy(&mut stdin().lock(), &mut file).context("cannot read stdin")?;
+        Ok(spill)
+    }
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,510 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use tempfile::tempdir;
+
+fn cli() -> Command {
+    Command::new(assert_cmd::cargo::cargo_bin!("toon-cli"))
+}
+
+#[test]
+fn encode_exposes_options_and_writes_output_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let input = dir.path().join("in.json");
+    let output = dir.path().join("out.toon");
+    std::fs::write(&input, r#"{"a":{"b":{"c":1}},"t":["x","y"]}"#)?;
+
+    cli()
+        .args(["encode", "--key-folding", "safe", "--delimiter", "pipe"])
+        .arg(&input)
+        .arg("-o")
+        .arg(&output)
+        .assert()
+        .success()
+        .stdout("");
+    assert_eq!(std::fs::read_to_string(&output)?, "a.b.c: 1\nt[2|]: x|y\n");
+
+    cli()
+        .args(["encode", "--indent", "4"])
+        .write_stdin(r#"{"a":{"b":1}}"#)
+        .assert()
+        .success()
+        .stdout("a:\n    b: 1\n");
+    Ok(())
+}
+
+#[test]
+fn decode_expands_paths_and_can_relax_strict_mode() {
+    cli()
+        .args(["decode", "--expand-paths", "safe"])
+        .write_stdin("a.b: 1\n")
+        .assert()
+        .success()
+        .stdout("{\"a\":{\"b\":1}}\n");
+
+    cli()
+        .arg("decode")
+        .write_stdin("a:\n   b: 1\n")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("<stdin>: syntax at line 2"));
+    cli()
+        .args(["decode", "--no-strict"])
+        .write_stdin("a:\n   b: 1\n")
+        .assert()
+        .success();
+}
+
+#[test]
+fn validate_lists_every_problem() {
+    cli()
+        .arg("validate")
+        .write_stdin("x[3]: 1,2\ny: \"oops\n")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "<stdin>:1:7: error: array length mismatch",
+        ))
+        .stdout(predicate::str::contains(
+            "<stdin>:2:4: error: unterminated string",
+        ));
+
+    cli()
+        .args(["validate", "--no-strict"])
+        .write_stdin("a:\n   b: 1\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("<stdin>:2:1: warning:"));
+}
+
+#[test]
+fn validate_emits_json_and_sarif() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let bad = dir.path().join("bad.toon");
+    std::fs::write(&bad, "x[3]: 1,2\nrows[1|]{a,b}:\n  1|2\n")?;
+
+    let out = cli()
+        .args(["validate", "--format", "json"])
+        .arg(&bad)
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    let list: serde_json::Value = serde_json::from_slice(&out)?;
+    assert_eq!(
+        list[0],
+        serde_json::json!({
+            "file": bad.display().to_string(),
+            "line": 1,
+            "column": 7,
+            "severity": "error",
+            "rule": "length-mismatch",
+            "message": list[0]["message"],
+        })
+    );
+    assert_eq!(list[1]["rule"], "delimiter-mismatch");
+    assert_eq!(list[1]["line"], 2);
+
+    let out = cli()
+        .args(["validate", "--format", "sarif"])
+        .arg(&bad)
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    let log: serde_json::Value = serde_json::from_slice(&out)?;
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "toon-cli");
+    assert_eq!(
+        run["tool"]["driver"]["rules"][1]["id"],
+        "delimiter-mismatch"
+    );
+    let result = &run["results"][1];
+    assert_eq!(result["ruleId"], "delimiter-mismatch");
+    assert_eq!(result["ruleIndex"], 1);
+    assert_eq!(result["level"], "error");
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["region"]["startLine"], 2);
+    let uri = location["artifactLocation"]["uri"]
+        .as_str()
+        .unwrap_or_default();
+    assert!(uri.starts_with("file:///") && uri.ends_with("/bad.toon"));
+
+    // A clean document is an empty list and exits 0
+    cli()
+        .args(["validate", "--format", "json"])
+        .write_stdin("a: 1\n")
+        .assert()
+        .success()
+        .stdout("[]\n");
+    Ok(())
+}
+
+#[test]
+fn validate_reports_unreadable_files_and_goes_on() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let bad = dir.path().join("bad.toon");
+    std::fs::write(&bad, "x[3]: 1,2\n")?;
+    let missing = dir.path().join("missing.toon");
+
+    cli()
+        .arg("validate")
+        .arg(&missing)
+        .arg(&bad)
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains(format!(
+            "{}: error: cannot read",
+            missing.display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "{}:1:7: error: array length mismatch",
+            bad.display()
+        )));
+
+    let out = cli()
+        .args(["validate", "--format", "json"])
+        .arg(&missing)
+        .arg(&bad)
+        .assert()
+        .code(3)
+        .get_output()
+        .stdout
+        .clone();
+    let list: serde_json::Value = serde_json::from_slice(&out)?;
+    assert_eq!(list[0]["rule"], "io");
+    assert_eq!(list[0]["line"], serde_json::Value::Null);
+    assert_eq!(list[1]["rule"], "length-mismatch");
+    Ok(())
+}
+
+#[test]
+fn validate_sarif_columns_count_characters_and_stdin_has_no_uri()
+-> Result<(), Box<dyn std::error::Error>> {
+    let out = cli()
+        .args(["validate", "--format", "sarif"])
+        .write_stdin("ü: \"éé\n")
+        .assert()
+        .code(1)
+        .get_output()
+        .stdout
+        .clone();
+    let log: serde_json::Value = serde_json::from_slice(&out)?;
+    let location = &log["runs"][0]["results"][0]["locations"][0]["physicalLocation"];
+    assert!(location["artifactLocation"].get("uri").is_none());
+    // The unterminated `"éé` is three characters, though five bytes
+    assert_eq!(location["region"]["startColumn"], 4);
+    assert_eq!(location["region"]["endColumn"], 7);
+    Ok(())
+}
+
+#[test]
+fn fmt_and_stats() {
+    cli()
+        .arg("fmt")
+        .write_stdin("users[2]:\n  - id: 1\n    name: a\n  - id: 2\n    name: b\n")
+        .assert()
+        .success()
+        .stdout("users[2]{id,name}:\n  1,a\n  2,b\n");
+
+    cli()
+        .arg("stats")
+        .write_stdin(r#"{"u":[{"x":1},{"x":2}]}"#)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("TOON"))
+        .stdout(predicate::str::contains("smaller than compact JSON"))
+        .stdout(predicate::str::contains("fewer tokens"))
+        .stdout(predicate::str::contains(
+            "Tokens by subtree (JSON -> TOON):\n  u ",
+        ))
+        .stdout(predicate::str::contains(
+            "Tokens by table (JSON -> TOON):\n  u ",
+        ))
+        .stdout(predicate::str::contains("2 rows x 1 fields"));
+}
+
+#[test]
+fn exit_codes_distinguish_usage_and_io_errors() {
+    cli().args(["decode", "--indent"]).assert().code(2);
+    cli()
+        .args(["decode", "/nonexistent/input.toon"])
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(
+            "cannot read /nonexistent/input.toon",
+        ));
+    cli()
+        .arg("encode")
+        .write_stdin("{not json")
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn fmt_check_and_write() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let tidy = dir.path().join("tidy.toon");
+    let messy = dir.path().join("messy.toon");
+    std::fs::write(&tidy, "a:\n  b: 1\n")?;
+    std::fs::write(&messy, "a:\n    b: \"1x\"\n")?;
+
+    cli()
+        .args(["fmt", "--check"])
+        .arg(&tidy)
+        .assert()
+        .success()
+        .stdout("");
+    cli()
+        .args(["fmt", "--check"])
+        .arg(&tidy)
+        .arg(&messy)
+        .assert()
+        .code(1)
+        .stdout(format!("{}: not formatted\n", messy.display()));
+
+    cli()
+        .args(["fmt", "--write"])
+        .arg(&messy)
+        .assert()
+        .success();
+    assert_eq!(std::fs::read_to_string(&messy)?, "a:\n  b: 1x\n");
+    cli()
+        .args(["fmt", "--check"])
+        .arg(&messy)
+        .assert()
+        .success();
+
+    cli()
+        .args(["fmt", "--check", "--indent", "4"])
+        .arg(&tidy)
+        .assert()
+        .code(1);
+    cli().arg("fmt").arg(&tidy).arg(&messy).assert().code(2);
+    Ok(())
+}
+
+#[test]
+fn formats_are_detected_by_extension_and_content() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let csv = dir.path().join("users.csv");
+    std::fs::write(&csv, "id,name,zip\n1,Ada,02134\n2,\"Bob, Jr\",10001\n")?;
+    cli()
+        .arg("encode")
+        .arg(&csv)
+        .assert()
+        .success()
+        .stdout("[2]{id,name,zip}:\n  1,Ada,\"02134\"\n  2,\"Bob, Jr\",\"10001\"\n");
+
+    // TSV by extension and by content
+    let tsv = dir.path().join("scores.tsv");
+    std::fs::write(&tsv, "name\tscore\nAda\t9.5\n")?;
+    cli()
+        .arg("encode")
+        .arg(&tsv)
+        .assert()
+        .success()
+        .stdout("[1]{name,score}:\n  Ada,9.5\n");
+    cli()
+        .arg("encode")
+        .write_stdin("name\tscore\nAda\t9.5\n")
+        .assert()
+        .success()
+        .stdout("[1]{name,score}:\n  Ada,9.5\n");
+
+    // Content sniffing: TOML tables, YAML documents, and JSON as before
+    for (input, expected) in [
+        ("[server]\nport = 80\n", "server:\n  port: 80\n"),
+        ("---\ntags:\n  - a\n  - b\n", "tags[2]: a,b\n"),
+        ("{\"a\": [1, 2]}", "a[2]: 1,2\n"),
+    ] {
+        cli()
+            .arg("encode")
+            .write_stdin(input)
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+
+    // Anything else is JSON, as it was before subcommands
+    cli()
+        .arg("encode")
+        .write_stdin("name: Ada\n")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("<stdin> is not valid JSON"));
+    cli()
+        .args(["encode", "--from", "yaml"])
+        .write_stdin("name: Ada\n")
+        .assert()
+        .success()
+        .stdout("name: Ada\n");
+
+    let toml = dir.path().join("out.toml");
+    cli()
+        .arg("decode")
+        .arg("-o")
+        .arg(&toml)
+        .write_stdin("server:\n  host: x\n  ports[2]: 80,443\n")
+        .assert()
+        .success();
+    assert_eq!(
+        std::fs::read_to_string(&toml)?,
+        "[server]\nhost = \"x\"\nports = [80, 443]\n"
+    );
+    cli()
+        .args(["decode", "--to", "csv"])
+        .write_stdin("users[2]{id,name}:\n  1,Ada\n  2,\"Bob, Jr\"\n")
+        .assert()
+        .success()
+        .stdout("id,name\n1,Ada\n2,\"Bob, Jr\"\n");
+    cli()
+        .args(["decode", "--to", "yaml"])
+        .write_stdin("a[2]: x,y\n")
+        .assert()
+        .success()
+        .stdout("a:\n- x\n- y\n");
+    Ok(())
+}
+
+#[test]
+fn unconvertible_input_and_output_are_invalid() {
+    cli()
+        .args(["encode", "--from", "toml"])
+        .write_stdin("a = \n")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("<stdin> is not valid TOML"));
+    cli()
+        .args(["decode", "--to", "csv"])
+        .write_stdin("a: 1\n")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "CSV output needs an array of objects",
+        ));
+    cli()
+        .args(["decode", "--to", "toml"])
+        .write_stdin("a: null\n")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "cannot write the document as TOML",
+        ));
+}
+
+#[test]
+fn ndjson_streams_records_into_one_array_and_back() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempdir()?;
+    let events = dir.path().join("events.ndjson");
+    std::fs::write(
+        &events,
+        "{\"id\":1,\"kind\":\"login\",\"ok\":true}\n\n{\"ok\":false,\"id\":2,\"kind\":\"a,b\"}\n",
+    )?;
+    let table = "[2]{id,kind,ok}:\n  1,login,true\n  2,\"a,b\",false\n";
+    cli()
+        .args(["encode", "--ndjson"])
+        .arg(&events)
+        .assert()
+        .success()
+        .stdout(table);
+    cli()
+        .args(["decode", "--ndjson"])
+        .write_stdin(table)
+        .assert()
+        .success()
+        .stdout(
+            "{\"id\":1,\"kind\":\"login\",\"ok\":true}\n{\"id\":2,\"kind\":\"a,b\",\"ok\":false}\n",
+        );
+
+    // Records that do not share a key set fall back to list items, as the
+    // encoder writes the same array
+    let mixed = "{\"id\":1}\n{\"id\":2,\"tags\":[\"x\"]}\n7\n";
+    cli()
+        .args(["encode", "--ndjson"])
+        .write_stdin(mixed)
+        .assert()
+        .success()
+        .stdout("[3]:\n  - id: 1\n  - id: 2\n    tags[1]: x\n  - 7\n");
+    cli()
+        .arg("encode")
+        .write_stdin("[{\"id\":1},{\"id\":2,\"tags\":[\"x\"]},7]")
+        .assert()
+        .success()
+        .stdout("[3]:\n  - id: 1\n  - id: 2\n    tags[1]: x\n  - 7\n");
+    cli()
+        .args(["encode", "--ndjson", "--delimiter", "pipe"])
+        .write_stdin("1\n\"a|b\"\n")
+        .assert()
+        .success()
+        .stdout("[2|]: 1|\"a|b\"\n");
+    cli()
+        .args(["decode", "--ndjson"])
+        .write_stdin("items[2]:\n  - a: 1\n  - 3\n")
+        .assert()
+        .success()
+        .stdout("{\"a\":1}\n3\n");
+    Ok(())
+}
+
+#[test]
+fn ndjson_errors_name_the_record() {
+    cli()
+        .args(["encode", "--ndjson"])
+        .write_stdin("{\"a\":1}\n{bad\n")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("<stdin>:2: not a JSON record"));
+    cli()
+        .args(["decode", "--ndjson"])
+        .write_stdin("t[3]{id}:\n  1\n  2\n")
+        .assert()
+        .code(1)
+        .stdout("{\"id\":1}\n{\"id\":2}\n")
+        .stderr(predicate::str::contains("header declares 3"));
+    // A root table followed by more keys is not an array of rows
+    cli()
+        .args(["decode", "--ndjson"])
+        .write_stdin("items[2]{a,b}:\n  1,2\n  3,4\ncount: 2")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "unexpected content after the table",
+        ));
+    cli()
+        .args(["decode", "--ndjson", "--to", "yaml"])
+        .write_stdin("a: 1\n")
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn encode_verify_refuses_output_that_does_not_decode_back() -> Result<(), Box<dyn std::error::Error>>
+{
+    let dir = tempdir()?;
+    let out = dir.path().join("out.toon");
+    cli()
+        .args(["encode", "--verify", "--from", "json"])
+        .write_stdin("{\"ids\":[1,2.5],\"s\":\"1\"}")
+        .assert()
+        .success()
+        .stdout("ids[2]: 1,2.5\ns: \"1\"\n");
+
+    // A literal dotted key reads back as a folded path when expanded
+    cli()
+        .args(["encode", "--check-roundtrip", "--key-folding", "safe", "-o"])
+        .arg(&out)
+        .write_stdin("{\"a\":{\"b\":{\"c\":1}},\"a.b\":2}")
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("<stdin>: round trip failed"));
+    assert!(!out.exists());
+    cli()
+        .args(["encode", "--verify", "--ndjson"])
+        .write_stdin("1\n")
+        .assert()
+        .code(2);
+    Ok(())
+}
@@ -0,0 +1,330 @@
+//! Encoding within a byte or token budget
+//!
+//! [`encode_within`] writes a value as TOON and, if the text is over the
+//! [`Budget`], shrinks it until it fits: arrays keep a sample of their
+//! elements and long strings are cut short and end in a marker. The encoder
+//! writes what is left, so the text is valid TOON whose `[N]` headers count
+//! the elements kept. What was dropped is listed in a [`Report`], by the path
+//! the value had before shrinking.
+//!
+//! ```
+//! use toon_rs::budget::{self, Budget};
+//! use toon_rs::stats::ApproxTokenizer;
+//! use toon_rs::{Options, toon};
+//!
+//! let rows: Vec<_> = (1..=100).map(|id| toon!({ "id": id, "ok": true })).collect();
+//! let value = toon!({ "rows": rows });
+//! let budget = Budget { max_bytes: Some(80), ..Budget::default() };
+//! let (text, report) = budget::encode_within(&value, &Options::default(), &budget, &ApproxTokenizer)?;
+//! assert_eq!(text, "rows[6]{id,ok}:\n  1,true\n  2,true\n  3,true\n  98,true\n  99,true\n  100,true");
+//! assert_eq!((report.sampled[0].path.as_str(), report.sampled[0].len), ("rows", 100));
+//! assert_eq!(report.sampled[0].kept, [0, 1, 2, 97, 98, 99]);
+//! # Ok::<(), toon_rs::Error>(())
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use serde::Serialize;
+
+use crate::error::{Error, ErrorKind, Result, path_key};
+use crate::options::Options;
+use crate::stats::Tokenizer;
+use crate::value::{Map, Value};
+
+/// Fewest elements an array is sampled down to.
+const MIN_ROWS: usize = 2;
+/// Fewest characters a string is cut down to, before the marker.
+const MIN_CHARS: usize = 8;
+
+/// Limits on the encoded text, and how to shrink a value to meet them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Budget {
+    /// Most bytes the text may have (None = no limit)
+    pub max_bytes: Option<usize>,
+    /// Most tokens the text may have, as the tokenizer counts them
+    /// (None = no limit)
+    pub max_tokens: Option<usize>,
+    /// Which elements of a long array are kept
+    pub sampling: Sampling,
+    /// Appended to a string that was cut short
+    pub marker: String,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget {
+            max_bytes: None,
+            max_tokens: None,
+            sampling: Sampling::HeadTail,
+            marker: String::from("…"),
+        }
+    }
+}
+
+/// Which elements of an array are kept when it is shrunk to `k` of them.
+/// Kept elements stay in their original order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Sampling {
+    /// The first half of `k` and the last half
+    #[default]
+    HeadTail,
+    /// `k` elements evenly spaced from the first to the last
+    Even,
+    /// Elements from each group of objects with the same value of this field,
+    /// in proportion to the size of the group and at least one per group
+    /// while `k` allows, evenly spaced within it
+    Stratified(String),
+}
+
+/// What [`encode_within`] dropped to fit the budget.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Report {
+    /// Arrays that keep only some of their elements, in document order
+    pub sampled: Vec<Sampled>,
+    /// Strings that were cut short, in document order
+    pub truncated: Vec<Truncated>,
+}
+
+impl Report {
+    /// Whether nothing was dropped.
+    pub fn is_empty(&self) -> bool {
+        self.sampled.is_empty() && self.truncated.is_empty()
+    }
+}
+
+/// An array that keeps only some of its elements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sampled {
+    /// Path of the array, such as `data.users`
+    pub path: String,
+    /// Elements the array had
+    pub len: usize,
+    /// Indices of the elements kept, ascending
+    pub kept: Vec<usize>,
+}
+
+/// A string that was cut short.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Truncated {
+    /// Path of the string, such as `users[4].bio`
+    pub path: String,
+    /// Characters the string had
+    pub len: usize,
+    /// Characters kept, before the marker
+    pub kept: usize,
+}
+
+/// Encode `value` with `options` in at most `budget.max_bytes` bytes and
+/// `budget.max_tokens` tokens as counted by `tokenizer`.
+///
+/// Text that already fits is returned as [`crate::encode_to_string`] writes
+/// it, with an empty report. Otherwise every array and string is capped at a
+/// share of the longest array and string, the same share for both, and the
+/// largest share whose text fits is used. Arrays keep at least two elements
+/// and strings eight characters; if the text is still over the budget then,
+/// the error has kind [`ErrorKind::BudgetExceeded`].
+pub fn encode_within<T: Serialize, K: Tokenizer + ?Sized>(
+    value: &T,
+    options: &Options,
+    budget: &Budget,
+    tokenizer: &K,
+) -> Result<(String, Report)> {
+    let value = crate::to_value(value)?;
+    let fits = |text: &str| {
+        budget.max_bytes.is_none_or(|max| text.len() <= max)
+            && budget
+                .max_tokens
+                .is_none_or(|max| tokenizer.count_tokens(text) <= max)
+    };
+    let text = crate::encode_to_string(&value, options)?;
+    if fits(&text) {
+        return Ok((text, Report::default()));
+    }
+
+    let (rows, chars) = extent(&value);
+    let attempt = |share: usize| -> Result<(String, Report)> {
+        let mut shrink = Shrink {
+            rows: cap(rows, share, MIN_ROWS),
+            chars: cap(chars, share, MIN_CHARS),
+            budget,
+            report: Report::default(),
+        };
+        let shrunk = shrink.value(&value, "");
+        Ok((crate::encode_to_string(&shrunk, options)?, shrink.report))
+    };
+    // The largest share, in thousandths, whose text fits
+    let mut best = attempt(0)?;
+    if !fits(&best.0) {
+        return Err(Error::data(
+            ErrorKind::BudgetExceeded,
+            format!(
+                "the value does not fit the budget even when shrunk to {} bytes",
+                best.0.len()
+            ),
+        ));
+    }
+    let (mut low, mut high) = (0, 1000);
+    while high - low > 1 {
+        let share = (low + high) / 2;
+        let shrunk = attempt(share)?;
+        if fits(&shrunk.0) {
+            low = share;
+            best = shrunk;
+        } else {
+            high = share;
+        }
+    }
+    Ok(best)
+}
+
+/// The longest array and the longest string in `value`, in elements and
+/// characters.
+fn extent(value: &Value) -> (usize, usize) {
+    match value {
+        Value::String(s) => (0, s.chars().count()),
+        Value::Array(items) => items
+            .iter()
+            .map(extent)
+            .fold((items.len(), 0), |(r, c), (rows, chars)| {
+                (r.max(rows), c.max(chars))
+            }),
+        Value::Object(map) => map
+            .values()
+            .map(extent)
+            .fold((0, 0), |(r, c), (rows, chars)| (r.max(rows), c.max(chars))),
+        _ => (0, 0),
+    }
+}
+
+/// `share` thousandths of `longest`, but no less than `min`.
+fn cap(longest: usize, share: usize, min: usize) -> usize {
+    (longest * share).div_ceil(1000).max(min)
+}
+
+struct Shrink<'a> {
+    /// Most elements an array keeps
+    rows: usize,
+    /// Most characters a string keeps
+    chars: usize,
+    budget: &'a Budget,
+    report: Report,
+}
+
+impl Shrink<'_> {
+    fn value(&mut self, value: &Value, path: &str) -> Value {
+        match value {
+            Value::String(s) => {
+                let len = s.chars().count();
+                if len <= self.chars {
+                    return value.clone();
+                }
+                self.report.truncated.push(Truncated {
+                    path: path.to_string(),
+                    len,
+                    kept: self.chars,
+                });
+                let mut cut: String = s.chars().take(self.chars).collect();
+                cut.push_str(&self.budget.marker);
+                Value::String(cut)
+            }
+            Value::Array(items) => {
+                let kept = if items.len() > self.rows {
+                    let kept = sample(items, self.rows, &self.budget.sampling);
+                    self.report.sampled.push(Sampled {
+                        path: path.to_string(),
+                        len: items.len(),
+                        kept: kept.clone(),
+                    });
+                    kept
+                } else {
+                    (0..items.len()).collect()
+                };
+                kept.into_iter()
+                    .map(|i| self.value(&items[i], &format!("{}[{}]", path, i)))
+                    .collect()
+            }
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| {
+                        let path = match path {
+                            "" => path_key(key),
+                            _ => format!("{}.{}", path, path_key(key)),
+                        };
+                        (key.clone(), self.value(value, &path))
+                    })
+                    .collect::<Map>(),
+            ),
+            _ => value.clone(),
+        }
+    }
+}
+
+/// Indices of the `k` of `items` that `sampling` keeps, ascending.
+fn sample(items: &[Value], k: usize, sampling: &Sampling) -> Vec<usize> {
+    let n = items.len();
+    match sampling {
+        Sampling::HeadTail => (0..k.div_ceil(2)).chain(n - k / 2..n).collect(),
+        Sampling::Even => even(n, k),
+        Sampling::Stratified(field) => {
+            // Groups in order of their first element
+            let mut groups: Vec<(Option<&Value>, Vec<usize>)> = Vec::new();
+            for (i, item) in items.iter().enumerate() {
+                let key = item.as_object().and_then(|o| o.get(field));
+                match groups.iter_mut().find(|(g, _)| *g == key) {
+                    Some((_, members)) => members.push(i),
+                    None => groups.push((key, Vec::from([i]))),
+                }
+            }
+            let shares = allocate(&groups.iter().map(|(_, m)| m.len()).collect::<Vec<_>>(), k);
+            let mut kept: Vec<usize> = groups
+                .iter()
+                .zip(shares)
+                .flat_map(|((_, members), share)| {
+                    even(members.len(), share).into_iter().map(|i| members[i])
+                })
+                .collect();
+            kept.sort_unstable();
+            kept
+        }
+    }
+}
+
+/// `k` of `0..n`, evenly spaced from the first to the last.
+fn even(n: usize, k: usize) -> Vec<usize> {
+    match k {
+        _ if k >= n => (0..n).collect(),
+        0 => Vec::new(),
+        1 => Vec::from([0]),
+        _ => (0..k).map(|i| i * (n - 1) / (k - 1)).collect(),
+    }
+}
+
+/// Split `k` between groups of these sizes: one for each group first while
+/// `k` lasts, and the rest in proportion to what each group has left, by
+/// largest remainder. `k` is less than the sum of the sizes.
+fn allocate(sizes: &[usize], k: usize) -> Vec<usize> {
+    let mut shares: Vec<usize> = (0..sizes.len()).map(|i| usize::from(i < k)).collect();
+    let left = k.saturating_sub(sizes.len());
+    let room: Vec<usize> = sizes.iter().zip(&shares).map(|(s, h)| s - h).collect();
+    let total: usize = room.iter().sum();
+    if left == 0 {
+        return shares;
+    }
+    let mut given = 0;
+    let mut remainders = Vec::new();
+    for (i, r) in room.iter().enumerate() {
+        shares[i] += left * r / total;
+        given += left * r / total;
+        remainders.push((left * r % total, i));
+    }
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for (_, i) in remainders.into_iter().take(left - given) {
+        shares[i] += 1;
+    }
+    shares
+}
@@ -0,0 +1,389 @@
+//! CSV and TSV interop for tabular arrays
+//!
+//! A tabular array (`key[N]{a,b,c}:`) is a header row and records, which is
+//! what CSV is too. [`to_csv`] writes the tabular array at a path of a
+//! [`Value`] as CSV, TSV or pipe-separated text, quoting cells the way
+//! RFC 4180 does. [`from_csv`] reads such text back into an array of objects,
+//! and [`to_toon`] writes it as a TOON table with [`TableWriter`], the same
+//! header and row code the encoder uses.
+//!
+//! ```
+//! use toon_rs::csv::{self, CsvOptions};
+//! use toon_rs::{Options, toon};
+//!
+//! let value = toon!({ "users": [{ "id": 1, "name": "Ada, L." }, { "id": 2, "name": "Bob" }] });
+//! let text = csv::to_csv(&value, "users", &CsvOptions::default())?;
+//! assert_eq!(text, "id,name\n1,\"Ada, L.\"\n2,Bob\n");
+//! assert_eq!(csv::from_csv(&text, &CsvOptions::default())?, value["users"]);
+//! assert_eq!(
+//!     csv::to_toon(&text, "users", &CsvOptions::default(), &Options::default())?,
+//!     "users[2]{id,name}:\n  1,\"Ada, L.\"\n  2,Bob"
+//! );
+//! # Ok::<(), toon_rs::Error>(())
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::decode::parser::{Scalar, parse_scalar_token};
+use crate::edit::{data, lookup};
+use crate::encode::primitives;
+use crate::error::{Error, ErrorKind, Result, Span};
+use crate::options::{Delimiter, Options};
+use crate::ser::TableWriter;
+use crate::value::{Map, Value};
+
+/// How CSV text is read and written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// Separator between cells: comma for CSV, tab for TSV
+    pub delimiter: Delimiter,
+    /// Read numbers, booleans and empty cells as such, column by column
+    /// (see [`from_csv`]); otherwise every cell is a string
+    pub infer_types: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: Delimiter::Comma,
+            infer_types: true,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Tab-separated values, with type inference.
+    pub fn tsv() -> Self {
+        CsvOptions {
+            delimiter: Delimiter::Tab,
+            ..CsvOptions::default()
+        }
+    }
+}
+
+/// Write the tabular array at `path` of `value` as CSV: a header row of the
+/// field names, then one record per row, each ending in `\n`.
+///
+/// The array must hold objects with the same keys and only primitive values,
+/// which is when the encoder writes it as a table. Null is an empty cell and
+/// numbers are written in canonical form. Strings that would read back as a
+/// number, boolean or null, and empty strings, are quoted, so [`from_csv`]
+/// keeps them strings. An empty array gives empty text.
+pub fn to_csv(value: &Value, path: &str, options: &CsvOptions) -> Result<String> {
+    let not_tabular = || {
+        data(
+            ErrorKind::TypeMismatch,
+            path,
+            "expected an array of objects with the same keys and primitive values",
+        )
+    };
+    let rows = lookup(value, path)?.as_array().ok_or_else(not_tabular)?;
+    let fields = table_fields(rows).ok_or_else(not_tabular)?;
+    let separator = primitives::delimiter_char(options.delimiter);
+    let mut out = String::new();
+    if rows.is_empty() {
+        return Ok(out);
+    }
+    write_record(
+        &mut out,
+        fields.iter().map(|f| (f.to_string(), false)),
+        separator,
+    );
+    for row in rows.iter().filter_map(Value::as_object) {
+        let cells = fields.iter().map(|f| match row.get(f) {
+            Some(Value::Bool(b)) => (primitives::format_bool(*b).to_string(), false),
+            Some(Value::Number(n)) => (n.to_string(), false),
+            Some(Value::String(s)) => {
+                let typed = s.is_empty() || matches!(parse_scalar_token(s), Ok(Scalar::Other(_)));
+                (s.clone(), typed)
+            }
+            _ => (String::new(), false),
+        });
+        write_record(&mut out, cells, separator);
+    }
+    Ok(out)
+}
+
+/// Read CSV with a header row into an array of objects, one per record.
+///
+/// Quoted cells may hold separators, doubled quotes and line breaks, records
+/// may end in `\r\n`, and blank lines are skipped. Every record must have as
+/// many cells as the header.
+///
+/// With [`CsvOptions::infer_types`], a column whose cells are all numbers as
+/// TOON reads them (so `007` is not one) becomes numbers, one of only `true`
+/// and `false` becomes booleans, and in such a column empty cells and `null`
+/// are null. Any other column, or a column of empty cells, keeps strings. A
+/// quoted cell, such as `"1"`, is always a string, so its column is too.
+pub fn from_csv(input: &str, options: &CsvOptions) -> Result<Value> {
+    let (fields, rows) = read_table(input, options)?;
+    Ok(rows
+        .into_iter()
+        .map(|cells| Value::Object(fields.iter().cloned().zip(cells).collect::<Map>()))
+        .collect())
+}
+
+/// Read CSV as [`from_csv`] does and encode it as the table `key[N]{fields}:`
+/// with `options`; an empty `key` gives a root table.
+pub fn to_toon(input: &str, key: &str, csv: &CsvOptions, options: &Options) -> Result<String> {
+    let (fields, rows) = read_table(input, csv)?;
+    if fields.is_empty() {
+        return Err(Error::data(ErrorKind::Other, "CSV input has no header row"));
+    }
+    let mut table = TableWriter::with_sink(String::new(), key, &fields, rows.len(), options)?;
+    table.write_rows(
+        rows.into_iter()
+            .map(|cells| fields.iter().cloned().zip(cells).collect::<Map>()),
+    )?;
+    table.finish()
+}
+
+/// Field names of a tabular array, in the order of its first row.
+pub(crate) fn table_fields(rows: &[Value]) -> Option<Vec<&str>> {
+    let first = match rows.first() {
+        Some(row) => row.as_object()?,
+        None => return Some(Vec::new()),
+    };
+    let fields: Vec<&str> = first.keys().map(String::as_str).collect();
+    for row in rows {
+        let row = row.as_object()?;
+        let same = row.len() == fields.len()
+            && fields
+                .iter()
+                .all(|f| row.get(f).is_some_and(Value::is_primitive));
+        if !same {
+            return None;
+        }
+    }
+    Some(fields)
+}
+
+/// Write one record of `(cell, quote)` pairs; a cell is quoted when `quote`
+/// is set or RFC 4180 needs it.
+fn write_record(out: &mut String, cells: impl Iterator<Item = Cell>, separator: char) {
+    let start = out.len();
+    for (i, (cell, quote)) in cells.enumerate() {
+        if i > 0 {
+            out.push(separator);
+        }
+        if quote || cell.contains([separator, '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&cell.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(&cell);
+        }
+    }
+    // A record of one empty cell would read back as a blank line
+    if out.len() == start {
+        out.push_str("\"\"");
+    }
+    out.push('\n');
+}
+
+/// The header and the typed cells of every record.
+fn read_table(input: &str, options: &CsvOptions) -> Result<(Vec<String>, Vec<Vec<Value>>)> {
+    let separator = primitives::delimiter_char(options.delimiter);
+    let mut records = Records {
+        input,
+        pos: 0,
+        separator,
+    };
+    let Some((start, fields)) = records.next_record()? else {
+        return Ok((Vec::new(), Vec::new()));
+    };
+    let fields: Vec<String> = fields.into_iter().map(|(field, _)| field).collect();
+    for (i, field) in fields.iter().enumerate() {
+        if fields[..i].contains(field) {
+            return Err(Error::syntax(
+                ErrorKind::DuplicateKey,
+                line_at(input, start),
+                format!("duplicate column `{}`", field),
+            ));
+        }
+    }
+    let mut rows = Vec::new();
+    while let Some((start, cells)) = records.next_record()? {
+        if cells.len() != fields.len() {
+            return Err(Error::syntax(
+                ErrorKind::LengthMismatch,
+                line_at(input, start),
+                format!(
+                    "record has {} cells but the header has {}",
+                    cells.len(),
+                    fields.len()
+                ),
+            ));
+        }
+        rows.push(cells);
+    }
+
+    let mut columns = vec![Column::Empty; fields.len()];
+    if options.infer_types {
+        for cells in &rows {
+            for (column, (cell, quoted)) in columns.iter_mut().zip(cells) {
+                *column = column.widen(Column::of(cell, *quoted));
+            }
+        }
+    }
+    let rows = rows
+        .into_iter()
+        .map(|cells| {
+            cells
+                .into_iter()
+                .zip(&columns)
+                .map(|((cell, _), column)| column.value(cell))
+                .collect()
+        })
+        .collect();
+    Ok((fields, rows))
+}
+
+/// What a column's cells say its type is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    /// No cell with a value yet
+    Empty,
+    Number,
+    Bool,
+    String,
+}
+
+impl Column {
+    fn of(cell: &str, quoted: bool) -> Column {
+        if quoted {
+            return Column::String;
+        }
+        if cell.is_empty() || cell == "null" {
+            return Column::Empty;
+        }
+        match parse_scalar_token(cell) {
+            Ok(Scalar::Other(Value::Number(_))) => Column::Number,
+            Ok(Scalar::Other(Value::Bool(_))) => Column::Bool,
+            _ => Column::String,
+        }
+    }
+
+    fn widen(self, other: Column) -> Column {
+        match (self, other) {
+            (Column::Empty, c) | (c, Column::Empty) => c,
+            (a, b) if a == b => a,
+            _ => Column::String,
+        }
+    }
+
+    fn value(self, cell: String) -> Value {
+        match self {
+            Column::Empty | Column::String => Value::String(cell),
+            Column::Number | Column::Bool => match parse_scalar_token(&cell) {
+                Ok(Scalar::Other(value)) if !cell.is_empty() => value,
+                _ => Value::Null,
+            },
+        }
+    }
+}
+
+/// A cell's text and whether it was quoted.
+type Cell = (String, bool);
+
+/// RFC 4180 records, with the offset each starts at.
+struct Records<'a> {
+    input: &'a str,
+    pos: usize,
+    separator: char,
+}
+
+impl Records<'_> {
+    fn next_record(&mut self) -> Result<Option<(usize, Vec<Cell>)>> {
+        // Blank lines hold no record
+        while self.pos < self.input.len() {
+            let rest = &self.input[self.pos..];
+            let blank = rest.find('\n').map_or(rest, |end| &rest[..end]);
+            if !blank.trim_end_matches('\r').is_empty() {
+                break;
+            }
+            self.pos += (blank.len() + 1).min(rest.len());
+        }
+        if self.pos >= self.input.len() {
+            return Ok(None);
+        }
+        let start = self.pos;
+        let mut cells = Vec::new();
+        loop {
+            let quoted = self.input[self.pos..].starts_with('"');
+            let (cell, end) = self.cell()?;
+            cells.push((cell, quoted));
+            match end {
+                Some(c) if c == self.separator => continue,
+                _ => return Ok(Some((start, cells))),
+            }
+        }
+    }
+
+    /// One cell and what ended it: the separator, a line break, or the input.
+    fn cell(&mut self) -> Result<(String, Option<char>)> {
+        let input = self.input;
+        let start = self.pos;
+        let mut cell = String::new();
+        let mut chars = input[start..].char_indices().map(|(i, c)| (start + i, c));
+        if input[start..].starts_with('"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some((i, '"')) if input[i + 1..].starts_with('"') => {
+                        cell.push('"');
+                        chars.next();
+                    }
+                    Some((_, '"')) => break,
+                    Some((_, c)) => cell.push(c),
+                    None => {
+                        return Err(Error::syntax(
+                            ErrorKind::UnterminatedString,
+                            Span::locate(input, start, 1),
+                            "unterminated quoted cell",
+                        ));
+                    }
+                }
+            }
+        }
+        let quoted = input[start..].starts_with('"');
+        for (i, c) in chars {
+            if c == self.separator || c == '\n' {
+                self.pos = i + c.len_utf8();
+                if c == '\n' && cell.ends_with('\r') && !quoted {
+                    cell.pop();
+                }
+                return Ok((cell, Some(c)));
+            }
+            if quoted && c != '\r' {
+                return Err(Error::syntax(
+                    ErrorKind::Syntax,
+                    Span::locate(input, i, c.len_utf8()),
+                    "unexpected character after a quoted cell",
+                ));
+            }
+            if !quoted || c != '\r' {
+                cell.push(c);
+            }
+        }
+        self.pos = input.len();
+        if !quoted && cell.ends_with('\r') {
+            cell.pop();
+        }
+        Ok((cell, None))
+    }
+}
+
+/// The whole line that starts at `offset`.
+fn line_at(input: &str, offset: usize) -> Span {
+    let len = input[offset..]
+        .find(['\r', '\n'])
+        .unwrap_or(input.len() - offset);
+    Span::locate(input, offset, len)
+}
@@ -12,28 +12,63 @@ use std::{
     vec::Vec,
 };
 
-use serde::de::{self, DeserializeOwned, IntoDeserializer, MapAccess, SeqAccess};
+use serde::de::{self, IntoDeserializer, MapAccess, SeqAccess};
 
 use crate::decode::scanner::{LineKind, ParsedLine, scan};
-use crate::{Result, error::Error as ToONError, options::Options};
+use crate::spanned::{self, Known, SpannedAccess};
+use crate::{
+    Result,
+    error::{Error as ToONError, ErrorKind, Span},
+    options::Options,
+};
 
+/// Error type used while driving serde; wraps the crate [`Error`](ToONError)
+/// so the document path can be prefixed as it bubbles up.
 #[derive(Debug)]
-pub struct DeError {
-    msg: String,
+pub struct DeError(ToONError);
+
+impl DeError {
+    pub fn kind(&self) -> ErrorKind {
+        self.0.kind()
+    }
+
+    fn in_key(self, key: &str) -> Self {
+        DeError(self.0.in_key(key))
+    }
+
+    fn in_index(self, index: usize) -> Self {
+        DeError(self.0.in_index(index))
+    }
+
+    /// Point the error at `part` (a slice of `input`) unless it already has a span.
+    fn at(self, input: &str, part: &str) -> Self {
+        match Span::of(input, part) {
+            Some(span) => DeError(self.0.at(span)),
+            None => self,
+        }
+    }
+}
+
+impl From<ToONError> for DeError {
+    fn from(e: ToONError) -> Self {
+        DeError(e)
+    }
 }
 impl core::fmt::Display for DeError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.write_str(&self.msg)
+        core::fmt::Display::fmt(&self.0, f)
     }
 }
 impl de::Error for DeError {
     fn custom<T: core::fmt::Display>(t: T) -> Self {
-        DeError { msg: t.to_string() }
+        DeError(ToONError::Message(t.to_string()))
     }
+
+    crate::error::forward_de_error_kinds!();
 }
 impl core::error::Error for DeError {}
 
-pub fn from_str<T: DeserializeOwned>(s: &str, options: &Options) -> Result<T> {
+pub fn from_str<'de, T: de::Deserialize<'de>>(s: &'de str, options: &Options) -> Result<T> {
     let lines = scan(s);
     if options.strict {
         // Collect raw lines for tab detection
@@ -43,21 +78,24 @@ pub fn from_str<T: DeserializeOwned>(s: &str, options: &Options) -> Result<T> {
             &raw_lines,
             options.indent,
         ) {
-            return Err(ToONError::Syntax {
-                line: e.line,
-                message: e.message,
-            });
+            let offset = raw_lines
+                .get(e.line - 1)
+                .and_then(|l| Span::of(s, l))
+                .map_or(0, |at| at.offset);
+            return Err(e.into_error(offset));
         }
     }
     let mut de = DirectDeserializer {
+        input: s,
         lines,
         idx: 0,
         strict: options.strict,
     };
-    T::deserialize(&mut de).map_err(|e: DeError| ToONError::Message(e.msg))
+    T::deserialize(&mut de).map_err(|e: DeError| e.0)
 }
 
 struct DirectDeserializer<'a> {
+    input: &'a str,
     lines: Vec<ParsedLine<'a>>,
     idx: usize,
     strict: bool,
@@ -140,7 +178,11 @@ impl<'de, 'a> de::Deserializer<'de> for &mut DirectDeserializer<'a> {
         match &pl.kind {
             LineKind::ListItem { .. } => {
                 // Root is an array
-                let mut sa = SeqDe { de: self, indent };
+                let mut sa = SeqDe {
+                    de: self,
+                    indent,
+                    index: 0,
+                };
                 visitor.visit_seq(&mut sa)
             }
             LineKind::KeyValue { .. } | LineKind::KeyOnly { .. } => {
@@ -152,16 +194,38 @@ impl<'de, 'a> de::Deserializer<'de> for &mut DirectDeserializer<'a> {
                 visitor.visit_map(&mut ma)
             }
             LineKind::Scalar(s) => {
+                let s = *s;
                 let tok = DirectDeserializer::classify_primitive(s);
-                PrimDe(tok).deserialize_any(visitor)
+                PrimDe(tok)
+                    .deserialize_any(visitor)
+                    .map_err(|e| e.at(self.input, s))
             }
             LineKind::Blank => visitor.visit_unit(),
         }
     }
 
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> core::result::Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        if spanned::is_spanned(name, fields) {
+            let span = Span::of(self.input, self.input.trim()).unwrap_or_default();
+            return visitor.visit_map(SpannedAccess::new(Known {
+                value: Some(self),
+                span,
+            }));
+        }
+        self.deserialize_any(visitor)
+    }
+
     serde::forward_to_deserialize_any! {
         bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf
-        option unit unit_struct newtype_struct seq tuple tuple_struct map struct
+        option unit unit_struct newtype_struct seq tuple tuple_struct map
         enum identifier ignored_any
     }
 }
@@ -200,6 +264,7 @@ impl<'de> de::Deserializer<'de> for PrimDe {
 struct SeqDe<'a, 'b> {
     de: &'b mut DirectDeserializer<'a>,
     indent: usize,
+    index: usize,
 }
 impl<'de, 'a, 'b> SeqAccess<'de> for SeqDe<'a, 'b> {
     type Error = DeError;
@@ -224,18 +289,41 @@ impl<'de, 'a, 'b> SeqAccess<'de> for SeqDe<'a, 'b> {
             return Ok(None);
         };
         self.de.next();
+        let index = self.index;
+        self.index += 1;
+        let input = self.de.input;
+        self.item(seed, val_opt).map_err(|e| {
+            let e = match val_opt {
+                Some(vs) => e.at(input, vs),
+                None => e,
+            };
+            e.in_index(index)
+        })
+    }
+}
+
+impl<'a, 'b> SeqDe<'a, 'b> {
+    fn item<'de, T>(
+        &mut self,
+        seed: T,
+        val_opt: Option<&'a str>,
+    ) -> core::result::Result<Option<T::Value>, DeError>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        let input = self.de.input;
         if let Some(vs) = val_opt {
             // 1) Inline primitive array item: "[N<delim?>]: v1<delim>..."
             if vs.starts_with('[') {
                 if let Some((_n, dch, values_str)) = parse_inline_array_header(vs) {
                     let toks = split_delim_aware(values_str, dch);
                     let mut ia = InlineArraySeq {
+                        input,
                         tokens: toks,
                         idx: 0,
                     };
-                    return seed
-                        .deserialize(de::value::SeqAccessDeserializer::new(&mut ia))
-                        .map(Some);
+                    let ia = de::value::SeqAccessDeserializer::new(&mut ia);
+                    return seed.deserialize(At::token(input, vs, ia)).map(Some);
                 }
             }
             // 2) Object on hyphen line: variants
@@ -252,9 +340,12 @@ impl<'de, 'a, 'b> SeqAccess<'de> for SeqDe<'a, 'b> {
                             first_key: Some(key),
                             first_val: Some(HyphenFirstValue::TabularHeader { dch, header }),
                             siblings_indent,
+                            key: String::new(),
+                            raw: kraw,
                         };
+                        let obj = de::value::MapAccessDeserializer::new(&mut obj);
                         return seed
-                            .deserialize(de::value::MapAccessDeserializer::new(&mut obj))
+                            .deserialize(At::block(input, vs, self.indent, obj))
                             .map(Some);
                     }
                 }
@@ -279,15 +370,18 @@ impl<'de, 'a, 'b> SeqAccess<'de> for SeqDe<'a, 'b> {
                     first_key: Some(key),
                     first_val: Some(first_val),
                     siblings_indent,
+                    key: String::new(),
+                    raw: kraw,
                 };
+                let obj = de::value::MapAccessDeserializer::new(&mut obj);
                 return seed
-                    .deserialize(de::value::MapAccessDeserializer::new(&mut obj))
+                    .deserialize(At::block(input, vs, self.indent, obj))
                     .map(Some);
             }
             // 3) Fallback primitive value
             let tok = DirectDeserializer::classify_primitive(vs);
             let de = PrimDe(tok);
-            seed.deserialize(de).map(Some)
+            seed.deserialize(At::token(input, vs, de)).map(Some)
         } else {
             // "-" as empty object
             let mut ma = MapDe {
@@ -295,8 +389,8 @@ impl<'de, 'a, 'b> SeqAccess<'de> for SeqDe<'a, 'b> {
                 indent: self.indent + 2,
                 pending: None,
             };
-            seed.deserialize(de::value::MapAccessDeserializer::new(&mut ma))
-                .map(Some)
+            let ma = de::value::MapAccessDeserializer::new(&mut ma);
+            seed.deserialize(At::token(input, "", ma)).map(Some)
         }
     }
 }
@@ -304,7 +398,8 @@ impl<'de, 'a, 'b> SeqAccess<'de> for SeqDe<'a, 'b> {
 struct MapDe<'a, 'b> {
     de: &'b mut DirectDeserializer<'a>,
     indent: usize,
-    pending: Option<(String, ValueKind<'a>)>,
+    /// Decoded key, its raw text and the value that follows it
+    pending: Option<(String, &'a str, ValueKind<'a>)>,
 }
 
 enum ValueKind<'a> {
@@ -357,6 +452,7 @@ impl<'de, 'a, 'b> MapAccess<'de> for MapDe<'a, 'b> {
                     if let Some((_n, dch, values_str)) = parse_inline_array_header(vref) {
                         self.pending = Some((
                             k.clone(),
+                            kref,
                             ValueKind::InlinePrimitiveArray { dch, values_str },
                         ));
                         return seed.deserialize(k.into_deserializer()).map(Some);
@@ -365,6 +461,7 @@ impl<'de, 'a, 'b> MapAccess<'de> for MapDe<'a, 'b> {
                     let dch = bracket_delim_from_key(kref).unwrap_or(',');
                     self.pending = Some((
                         k.clone(),
+                        kref,
                         ValueKind::InlinePrimitiveArray {
                             dch,
                             values_str: vref,
@@ -372,7 +469,7 @@ impl<'de, 'a, 'b> MapAccess<'de> for MapDe<'a, 'b> {
                     ));
                     return seed.deserialize(k.into_deserializer()).map(Some);
                 }
-                self.pending = Some((k.clone(), ValueKind::Scalar(vref)));
+                self.pending = Some((k.clone(), kref, ValueKind::Scalar(vref)));
                 seed.deserialize(k.into_deserializer()).map(Some)
             }
             (Some(kref), None) => {
@@ -423,7 +520,7 @@ impl<'de, 'a, 'b> MapAccess<'de> for MapDe<'a, 'b> {
                         _ => ValueKind::NestedObject { child_indent },
                     }
                 };
-                self.pending = Some((k.clone(), vkind));
+                self.pending = Some((k.clone(), kref, vkind));
                 seed.deserialize(k.into_deserializer()).map(Some)
             }
             _ => Ok(None),
@@ -434,20 +531,29 @@ impl<'de, 'a, 'b> MapAccess<'de> for MapDe<'a, 'b> {
     where
         V: de::DeserializeSeed<'de>,
     {
-        let (_k, vk) = self.pending.take().ok_or_else(|| DeError {
-            msg: "value requested without key".into(),
-        })?;
-        match vk {
-            ValueKind::Scalar(s) => {
-                seed.deserialize(PrimDe(DirectDeserializer::classify_primitive(s)))
-            }
+        let (k, kraw, vk) = self
+            .pending
+            .take()
+            .ok_or_else(|| <DeError as de::Error>::custom("value requested without key"))?;
+        let input = self.de.input;
+        let indent = self.indent;
+        let result = match vk {
+            ValueKind::Scalar(s) => seed
+                .deserialize(At::token(
+                    input,
+                    s,
+                    PrimDe(DirectDeserializer::classify_primitive(s)),
+                ))
+                .map_err(|e| e.at(input, s)),
             ValueKind::InlinePrimitiveArray { dch, values_str } => {
                 let toks = split_delim_aware(values_str, dch);
                 let mut ia = InlineArraySeq {
+                    input,
                     tokens: toks,
                     idx: 0,
                 };
-                seed.deserialize(de::value::SeqAccessDeserializer::new(&mut ia))
+                let ia = de::value::SeqAccessDeserializer::new(&mut ia);
+                seed.deserialize(At::token(input, values_str, ia))
             }
             ValueKind::NestedObject { child_indent } => {
                 let mut ma = MapDe {
@@ -455,14 +561,17 @@ impl<'de, 'a, 'b> MapAccess<'de> for MapDe<'a, 'b> {
                     indent: child_indent,
                     pending: None,
                 };
-                seed.deserialize(de::value::MapAccessDeserializer::new(&mut ma))
+                let ma = de::value::MapAccessDeserializer::new(&mut ma);
+                seed.deserialize(At::below(input, kraw, indent, ma))
             }
             ValueKind::Array { child_indent } => {
                 let mut sa = SeqDe {
                     de: self.de,
                     indent: child_indent,
+                    index: 0,
                 };
-                seed.deserialize(de::value::SeqAccessDeserializer::new(&mut sa))
+                let sa = de::value::SeqAccessDeserializer::new(&mut sa);
+                seed.deserialize(At::block(input, kraw, indent, sa))
             }
             ValueKind::Tabular {
                 dch,
@@ -474,10 +583,13 @@ impl<'de, 'a, 'b> MapAccess<'de> for MapDe<'a, 'b> {
                     indent: child_indent,
                     dch,
                     header,
+                    index: 0,
                 };
-                seed.deserialize(de::value::SeqAccessDeserializer::new(&mut ta))
+                let ta = de::value::SeqAccessDeserializer::new(&mut ta);
+                seed.deserialize(At::block(input, kraw, indent, ta))
             }
-        }
+        };
+        result.map_err(|e| e.at(input, kraw).in_key(&k))
     }
 }
 
@@ -486,9 +598,11 @@ struct TabularSeqDe<'a, 'b> {
     indent: usize,
     dch: char,
     header: Vec<String>,
+    index: usize,
 }
 
 struct InlineArraySeq<'a> {
+    input: &'a str,
     tokens: Vec<&'a str>,
     idx: usize,
 }
@@ -504,10 +618,13 @@ impl<'de, 'a> SeqAccess<'de> for InlineArraySeq<'a> {
         if self.idx >= self.tokens.len() {
             return Ok(None);
         }
-        let s = self.tokens[self.idx];
+        let index = self.idx;
+        let s = self.tokens[index];
         self.idx += 1;
         let de = PrimDe(DirectDeserializer::classify_primitive(s));
-        seed.deserialize(de).map(Some)
+        seed.deserialize(At::token(self.input, s, de))
+            .map(Some)
+            .map_err(|e| e.at(self.input, s).in_index(index))
     }
 }
 
@@ -516,6 +633,10 @@ struct HyphenObjectDe<'a, 'b> {
     first_key: Option<String>,
     first_val: Option<HyphenFirstValue<'a>>,
     siblings_indent: usize,
+    /// Key of the field being deserialized, for error paths
+    key: String,
+    /// Its text in the input
+    raw: &'a str,
 }
 
 enum HyphenFirstValue<'a> {
@@ -533,6 +654,7 @@ impl<'de, 'a, 'b> MapAccess<'de> for HyphenObjectDe<'a, 'b> {
         K: de::DeserializeSeed<'de>,
     {
         if let Some(k) = self.first_key.take() {
+            self.key = k.clone();
             return seed.deserialize(k.into_deserializer()).map(Some);
         }
         // After the first field, parse siblings at siblings_indent
@@ -602,6 +724,8 @@ impl<'de, 'a, 'b> MapAccess<'de> for HyphenObjectDe<'a, 'b> {
             }
         };
         self.first_val = Some(pending);
+        self.key = k.clone();
+        self.raw = kref;
         seed.deserialize(k.into_deserializer()).map(Some)
     }
     fn next_value_seed<V>(&mut self, seed: V) -> core::result::Result<V::Value, Self::Error>
@@ -609,17 +733,25 @@ impl<'de, 'a, 'b> MapAccess<'de> for HyphenObjectDe<'a, 'b> {
         V: de::DeserializeSeed<'de>,
     {
         if let Some(fv) = self.first_val.take() {
-            match fv {
-                HyphenFirstValue::Scalar(s) => {
-                    seed.deserialize(PrimDe(DirectDeserializer::classify_primitive(s)))
-                }
+            let input = self.de.input;
+            let (raw, indent) = (self.raw, self.siblings_indent);
+            let result = match fv {
+                HyphenFirstValue::Scalar(s) => seed
+                    .deserialize(At::token(
+                        input,
+                        s,
+                        PrimDe(DirectDeserializer::classify_primitive(s)),
+                    ))
+                    .map_err(|e| e.at(input, s)),
                 HyphenFirstValue::InlineArray { dch, values_str } => {
                     let toks = split_delim_aware(values_str, dch);
                     let mut ia = InlineArraySeq {
+                        input,
                         tokens: toks,
                         idx: 0,
                     };
-                    seed.deserialize(de::value::SeqAccessDeserializer::new(&mut ia))
+                    let ia = de::value::SeqAccessDeserializer::new(&mut ia);
+                    seed.deserialize(At::token(input, values_str, ia))
                 }
                 HyphenFirstValue::NestedObject { child_indent } => {
                     let mut ma = MapDe {
@@ -627,30 +759,34 @@ impl<'de, 'a, 'b> MapAccess<'de> for HyphenObjectDe<'a, 'b> {
                         indent: child_indent,
                         pending: None,
                     };
-                    seed.deserialize(de::value::MapAccessDeserializer::new(&mut ma))
+                    let ma = de::value::MapAccessDeserializer::new(&mut ma);
+                    seed.deserialize(At::below(input, raw, indent, ma))
                 }
                 HyphenFirstValue::Array { child_indent } => {
                     let mut sa = SeqDe {
                         de: self.de,
                         indent: child_indent,
+                        index: 0,
                     };
-                    seed.deserialize(de::value::SeqAccessDeserializer::new(&mut sa))
+                    let sa = de::value::SeqAccessDeserializer::new(&mut sa);
+                    seed.deserialize(At::block(input, raw, indent, sa))
                 }
                 HyphenFirstValue::TabularHeader { dch, header } => {
                     let mut ta = TabularSeqDe {
                         de: self.de,
-                        indent: self.siblings_indent,
+                        indent,
                         dch,
                         header,
+                        index: 0,
                     };
-                    seed.deserialize(de::value::SeqAccessDeserializer::new(&mut ta))
+                    let ta = de::value::SeqAccessDeserializer::new(&mut ta);
+                    seed.deserialize(At::block(input, raw, indent, ta))
                 }
-            }
+            };
+            result.map_err(|e| e.in_key(&self.key))
         } else {
             // Should not happen if next_key_seed is correct
-            Err(DeError {
-                msg: "value requested without pending field".into(),
-            })
+            Err(de::Error::custom("value requested without pending field"))
         }
     }
 }
@@ -681,26 +817,34 @@ impl<'de, 'a, 'b> SeqAccess<'de> for TabularSeqDe<'a, 'b> {
         let rs = rs_opt.unwrap();
         let cells = split_delim_aware(rs, self.dch);
         if self.de.strict && cells.len() != self.header.len() {
-            return Err(DeError {
-                msg: format!(
-                    "row cell count {} does not match header {} at line {}",
+            return Err(DeError(ToONError::syntax(
+                ErrorKind::LengthMismatch,
+                Span::of(self.de.input, rs).unwrap_or(Span::line(row_line)),
+                format!(
+                    "row cell count {} does not match header {}",
                     cells.len(),
-                    self.header.len(),
-                    row_line
+                    self.header.len()
                 ),
-            });
+            )));
         }
+        let index = self.index;
+        self.index += 1;
         let mut rma = RowMapDe {
+            input: self.de.input,
             header: &self.header,
             cells,
             idx: 0,
         };
-        seed.deserialize(de::value::MapAccessDeserializer::new(&mut rma))
+        let input = self.de.input;
+        let rma = de::value::MapAccessDeserializer::new(&mut rma);
+        seed.deserialize(At::token(input, rs, rma))
             .map(Some)
+            .map_err(|e| e.at(self.de.input, rs).in_index(index))
     }
 }
 
 struct RowMapDe<'h> {
+    input: &'h str,
     header: &'h [String],
     cells: Vec<&'h str>,
     idx: usize,
@@ -724,7 +868,157 @@ impl<'de, 'h> MapAccess<'de> for RowMapDe<'h> {
         let i = self.idx;
         self.idx += 1;
         let s = self.cells.get(i).copied().unwrap_or("null");
-        seed.deserialize(PrimDe(DirectDeserializer::classify_primitive(s)))
+        let de = PrimDe(DirectDeserializer::classify_primitive(s));
+        seed.deserialize(At::token(self.input, s, de))
+            .map_err(|e| e.at(self.input, s).in_key(&self.header[i]))
+    }
+}
+
+/// Deserializes `inner`, filling in a [`crate::Spanned`] from where its text
+/// lies in the input.
+struct At<'a, D> {
+    inner: D,
+    input: &'a str,
+    text: &'a str,
+    extent: Extent,
+}
+
+/// How much of the input a value covers, relative to its `text`.
+enum Extent {
+    /// Just the text
+    Token,
+    /// The text and the lines after it indented deeper than the given indent
+    Block(usize),
+    /// Only those deeper lines (the value of a `key:` line)
+    Below(usize),
+}
+
+impl<'a, D> At<'a, D> {
+    fn token(input: &'a str, text: &'a str, inner: D) -> Self {
+        At {
+            inner,
+            input,
+            text,
+            extent: Extent::Token,
+        }
+    }
+
+    fn block(input: &'a str, text: &'a str, indent: usize, inner: D) -> Self {
+        At {
+            inner,
+            input,
+            text,
+            extent: Extent::Block(indent),
+        }
+    }
+
+    fn below(input: &'a str, text: &'a str, indent: usize, inner: D) -> Self {
+        At {
+            inner,
+            input,
+            text,
+            extent: Extent::Below(indent),
+        }
+    }
+
+    fn span(&self) -> Span {
+        let Some(head) = Span::of(self.input, self.text) else {
+            return Span::default();
+        };
+        let indent = match self.extent {
+            Extent::Token => return head,
+            Extent::Block(indent) | Extent::Below(indent) => indent,
+        };
+        let head_end = head.offset + head.len;
+        let rest = &self.input[head_end..];
+        let rest = rest.find('\n').map_or("", |i| &rest[i + 1..]);
+        let mut block: Option<(Span, usize)> = None;
+        for line in rest.split('\n') {
+            let content = line.trim_start_matches(' ').trim_end();
+            if content.is_empty() {
+                continue;
+            }
+            if line.len() - line.trim_start_matches(' ').len() <= indent {
+                break;
+            }
+            if let Some(at) = Span::of(self.input, content) {
+                let first = block.map_or(at, |(first, _)| first);
+                block = Some((first, at.offset + at.len));
+            }
+        }
+        match (&self.extent, block) {
+            (Extent::Below(_), Some((first, end))) => crate::spanned::span_to(first, end),
+            // Nothing below: an empty value just past the text
+            (Extent::Below(_), None) => Span::locate(self.input, head_end, 0),
+            (_, Some((_, end))) => crate::spanned::span_to(head, end),
+            (_, None) => head,
+        }
+    }
+}
+
+/// Forward to the wrapped deserializer.
+macro_rules! delegate {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {$(
+        fn $method<V>(self, $($arg: $ty,)* visitor: V) -> core::result::Result<V::Value, DeError>
+        where
+            V: de::Visitor<'de>,
+        {
+            self.inner.$method($($arg,)* visitor)
+        }
+    )*};
+}
+
+impl<'de, D: de::Deserializer<'de, Error = DeError>> de::Deserializer<'de> for At<'_, D> {
+    type Error = DeError;
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> core::result::Result<V::Value, DeError>
+    where
+        V: de::Visitor<'de>,
+    {
+        if spanned::is_spanned(name, fields) {
+            let span = self.span();
+            return visitor.visit_map(SpannedAccess::new(Known {
+                value: Some(self.inner),
+                span,
+            }));
+        }
+        self.inner.deserialize_struct(name, fields, visitor)
+    }
+
+    delegate! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
     }
 }
 
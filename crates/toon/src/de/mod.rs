@@ -1,25 +1,47 @@
-//! serde::Deserializer implementation backed by internal Value (alloc-friendly)
+//! serde::Deserializer implementations over TOON text and [`Value`] trees
 
 #[cfg(not(feature = "std"))]
-use alloc::{format, string::String, vec::Vec};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 
-#[cfg(all(feature = "de_direct", feature = "json"))]
-use core::any::TypeId;
+use serde::de;
 
-use serde::de::{self, DeserializeOwned, IntoDeserializer, MapAccess, SeqAccess};
-
-use crate::value::{Number, Value};
-use crate::{Result, options::Options};
+use crate::error::{Error, ErrorKind};
+use crate::value::Value;
 
 #[cfg(feature = "de_direct")]
 pub mod direct;
+pub mod reader;
+#[cfg(feature = "std")]
+mod table;
 
-#[cfg(all(feature = "de_direct", feature = "json"))]
-use serde_json::Value as JsonValue;
+#[cfg(feature = "std")]
+pub use reader::from_reader;
+pub use reader::from_str;
+#[cfg(feature = "std")]
+pub use table::TableReader;
 
 #[derive(Debug)]
 pub struct DeError {
     msg: String,
+    kind: ErrorKind,
+}
+
+impl DeError {
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl From<Error> for DeError {
+    fn from(e: Error) -> Self {
+        DeError {
+            msg: e.to_string(),
+            kind: e.kind(),
+        }
+    }
 }
 
 impl core::fmt::Display for DeError {
@@ -31,11 +53,18 @@ impl de::Error for DeError {
     fn custom<T: core::fmt::Display>(t: T) -> Self {
         DeError {
             msg: format!("{}", t),
+            kind: ErrorKind::Other,
         }
     }
+
+    crate::error::forward_de_error_kinds!();
 }
 impl core::error::Error for DeError {}
 
+/// Owning [`Value`] deserializer with a standalone error type.
+///
+/// `Value` and `&Value` implement `serde::Deserializer` themselves; prefer
+/// [`crate::from_value`] unless a distinct error type is needed.
 pub struct Deserializer {
     value: Value,
 }
@@ -46,143 +75,51 @@ impl Deserializer {
     }
 }
 
-impl<'de> de::Deserializer<'de> for Deserializer {
-    type Error = DeError;
-
-    fn deserialize_any<V>(self, visitor: V) -> core::result::Result<V::Value, Self::Error>
-    where
-        V: de::Visitor<'de>,
-    {
-        match self.value {
-            Value::Null => visitor.visit_unit(),
-            Value::Bool(b) => visitor.visit_bool(b),
-            Value::Number(n) => match n {
-                Number::I64(i) => visitor.visit_i64(i),
-                Number::U64(u) => visitor.visit_u64(u),
-                Number::F64(f) => visitor.visit_f64(f),
-            },
-            Value::String(s) => visitor.visit_string(s),
-            Value::Array(arr) => {
-                struct SA {
-                    elems: Vec<Value>,
-                    idx: usize,
-                }
-                impl<'de> SeqAccess<'de> for SA {
-                    type Error = DeError;
-                    fn next_element_seed<T>(
-                        &mut self,
-                        seed: T,
-                    ) -> core::result::Result<Option<T::Value>, Self::Error>
-                    where
-                        T: de::DeserializeSeed<'de>,
-                    {
-                        if self.idx >= self.elems.len() {
-                            return Ok(None);
-                        }
-                        let v = core::mem::replace(&mut self.elems[self.idx], Value::Null);
-                        self.idx += 1;
-                        let de = Deserializer { value: v };
-                        seed.deserialize(de).map(Some)
-                    }
-                }
-                visitor.visit_seq(SA { elems: arr, idx: 0 })
-            }
-            Value::Object(obj) => {
-                struct MA {
-                    entries: Vec<(String, Value)>,
-                    idx: usize,
-                    next_val: Option<Value>,
-                }
-                impl<'de> MapAccess<'de> for MA {
-                    type Error = DeError;
-                    fn next_key_seed<K>(
-                        &mut self,
-                        seed: K,
-                    ) -> core::result::Result<Option<K::Value>, Self::Error>
-                    where
-                        K: de::DeserializeSeed<'de>,
-                    {
-                        if self.idx >= self.entries.len() {
-                            return Ok(None);
-                        }
-                        let (ref key, ref val) = self.entries[self.idx];
-                        let de_key = key.clone().into_deserializer();
-                        self.next_val = Some(val.clone());
-                        seed.deserialize(de_key).map(Some)
-                    }
-                    fn next_value_seed<VV>(
-                        &mut self,
-                        seed: VV,
-                    ) -> core::result::Result<VV::Value, Self::Error>
-                    where
-                        VV: de::DeserializeSeed<'de>,
-                    {
-                        let v = self.next_val.take().unwrap_or(Value::Null);
-                        self.idx += 1;
-                        let de = Deserializer { value: v };
-                        seed.deserialize(de)
-                    }
-                }
-                visitor.visit_map(MA {
-                    entries: obj,
-                    idx: 0,
-                    next_val: None,
-                })
-            }
-        }
-    }
-
-    serde::forward_to_deserialize_any! {
-        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf
-        option unit unit_struct newtype_struct seq tuple tuple_struct map struct
-        enum identifier ignored_any
-    }
-}
-
-pub fn from_str<T: DeserializeOwned + 'static>(s: &str, options: &Options) -> Result<T> {
-    #[cfg(feature = "de_direct")]
-    {
-        #[cfg(feature = "json")]
+/// Forward to the `Value` deserializer, converting the error type.
+macro_rules! delegate {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {$(
+        fn $method<V>(self, $($arg: $ty,)* visitor: V) -> core::result::Result<V::Value, DeError>
+        where
+            V: de::Visitor<'de>,
         {
-            if TypeId::of::<T>() == TypeId::of::<JsonValue>() {
-                return from_str_via_internal_value(s, options);
-            }
+            de::Deserializer::$method(self.value, $($arg,)* visitor)
+                .map_err(DeError::from)
         }
-        crate::de::direct::from_str(s, options)
-    }
-
-    #[cfg(not(feature = "de_direct"))]
-    {
-        from_str_via_internal_value(s, options)
-    }
+    )*};
 }
 
-#[cfg_attr(all(feature = "de_direct", not(feature = "json")), allow(dead_code))]
-fn from_str_via_internal_value<T: DeserializeOwned>(s: &str, options: &Options) -> Result<T> {
-    let lines = crate::decode::scanner::scan(s);
-    if options.strict {
-        // Collect raw lines for tab detection
-        let raw_lines: Vec<&str> = s.lines().collect();
-        if let Err(e) = crate::decode::validation::validate_indentation_with_size(
-            &lines,
-            &raw_lines,
-            options.indent,
-        ) {
-            return Err(crate::error::Error::Syntax {
-                line: e.line,
-                message: e.message,
-            });
-        }
-    }
-    let mut v = crate::decode::parser::parse_to_internal_value_from_lines(lines, options.strict)?;
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = DeError;
 
-    // Apply path expansion if enabled
-    if options.expand_paths == crate::options::ExpandPaths::Safe {
-        v = crate::decode::path_expand::expand_paths(v, options.strict)
-            .map_err(crate::error::Error::Message)?;
+    delegate! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
     }
-
-    let deser = Deserializer::from_value(v);
-    let t = T::deserialize(deser).map_err(|e: DeError| crate::error::Error::Message(e.msg))?;
-    Ok(t)
 }
@@ -0,0 +1,1393 @@
+//! Streaming serde::Deserializer over lines of TOON text
+//!
+//! Lines are pulled from a [`LineSource`] as the visitor asks for values, so only
+//! the current nesting path (open objects, arrays and tables) is held in memory.
+//! Strict-mode checks match [`crate::decode_from_str`]: indentation, declared
+//! array lengths, tabular row widths, delimiter mismatches and blank lines.
+//!
+//! When the source borrows from the input (a `&str`), keys and strings that need
+//! no unescaping are passed to the visitor as borrowed slices.
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    format,
+    rc::Rc,
+    string::ToString,
+    vec::{self, Vec},
+};
+#[cfg(feature = "std")]
+use std::{borrow::Cow, rc::Rc, vec};
+
+use serde::de::{
+    self, Deserialize, DeserializeSeed, EnumAccess, IgnoredAny, IntoDeserializer, MapAccess,
+    SeqAccess, VariantAccess, Visitor,
+    value::{BorrowedStrDeserializer, StrDeserializer},
+};
+
+use crate::decode::parser::{
+    ArrayHeader, Scalar, cell_token_requires_quotes, check_delimiter_mismatch,
+    is_array_header_line, is_quoted_token, parse_array_header, parse_header, parse_key_token,
+    parse_scalar_keyed_array_header, parse_scalar_token, split_delim_aware, split_kv_quote_aware,
+    token_requires_quotes,
+};
+use crate::decode::reader::{Line, LineReader, LineSource, StrLines, slice_of};
+use crate::decode::scanner::LineKind;
+use crate::error::{Error, ErrorKind, Result, Span};
+use crate::options::{ExpandPaths, Options};
+use crate::spanned::{self, SpannedAccess, SpannedSource};
+use crate::value::spanned::SpannedValue;
+use crate::value::{Number, Value};
+
+/// Deserialize a `T` from TOON text read incrementally from `reader`.
+#[cfg(feature = "std")]
+pub fn from_reader<R: std::io::BufRead, T: de::DeserializeOwned>(
+    reader: R,
+    options: &Options,
+) -> Result<T> {
+    from_source(crate::decode::reader::IoLines::new(reader), options)
+}
+
+/// Deserialize a `T` from `s`, borrowing unescaped keys and strings from it.
+pub fn from_str<'de, T: Deserialize<'de>>(s: &'de str, options: &Options) -> Result<T> {
+    from_source(StrLines::new(s), options)
+}
+
+fn from_source<'de, S: LineSource<'de>, T: Deserialize<'de>>(
+    source: S,
+    options: &Options,
+) -> Result<T> {
+    let mut de = Deserializer::new(source, options);
+    if options.expand_paths == ExpandPaths::Safe {
+        // Path expansion needs the whole document, so strings are owned;
+        // spans are kept for errors and `Spanned` fields
+        let v: SpannedValue = de.deserialize()?;
+        let v = crate::decode::path_expand::expand(v, options.strict)?;
+        return T::deserialize(v);
+    }
+    de.deserialize()
+}
+
+pub(crate) struct Deserializer<'de, S> {
+    lines: LineReader<'de, S>,
+    strict: bool,
+    mark_quoted_dots: bool,
+    root_indent: Option<usize>,
+}
+
+/// A value position that has not been read yet.
+enum Slot<'de> {
+    Document,
+    Token {
+        text: Cow<'de, str>,
+        at: Span,
+    },
+    /// Value on the following lines, indented at least `min_indent`
+    Node {
+        min_indent: usize,
+    },
+    /// Value of a `key:` line
+    Child {
+        parent_indent: usize,
+    },
+    /// Array whose header line has already been consumed
+    Array {
+        header: ArrayHeader,
+        values: Option<Cell<'de>>,
+        at: Span,
+        parent_indent: usize,
+    },
+    /// Value already read up to where its text starts, if known
+    Resolved {
+        shape: Shape<'de>,
+        at: Option<Span>,
+    },
+}
+
+enum Shape<'de> {
+    Null,
+    Str(Cow<'de, str>),
+    /// Non-string scalar
+    Primitive(Value),
+    Seq(Seq<'de>),
+    Map(Map<'de>),
+}
+
+/// A token together with where it appears in the input.
+type Cell<'de> = (Cow<'de, str>, Span);
+
+struct Seq<'de> {
+    kind: SeqKind<'de>,
+    declared: Option<usize>,
+    count: usize,
+    /// The array's header line, for length errors
+    header_at: Span,
+}
+
+enum SeqKind<'de> {
+    Inline {
+        cells: vec::IntoIter<Cell<'de>>,
+    },
+    List {
+        indent: usize,
+    },
+    Table {
+        indent: usize,
+        fields: Rc<[Cow<'de, str>]>,
+        delimiter: char,
+    },
+    /// Old `@<delim>` header with `- ` rows
+    LegacyTable {
+        indent: usize,
+        fields: Rc<[Cow<'de, str>]>,
+        delimiter: char,
+    },
+}
+
+enum Map<'de> {
+    Empty,
+    Fields {
+        indent: usize,
+        first: Option<(Cow<'de, str>, Box<Slot<'de>>)>,
+    },
+    Row {
+        fields: Rc<[Cow<'de, str>]>,
+        cells: vec::IntoIter<Cell<'de>>,
+        idx: usize,
+    },
+}
+
+impl<'de, S: LineSource<'de>> Deserializer<'de, S> {
+    pub(crate) fn new(source: S, options: &Options) -> Self {
+        Self {
+            lines: LineReader::new(source, options),
+            strict: options.strict,
+            mark_quoted_dots: options.expand_paths == ExpandPaths::Safe,
+            root_indent: None,
+        }
+    }
+
+    /// Deserialize the whole document and run end-of-input checks.
+    pub(crate) fn deserialize<T: Deserialize<'de>>(&mut self) -> Result<T> {
+        let t = T::deserialize(ValueDe {
+            de: self,
+            slot: Slot::Document,
+        })?;
+        self.end()?;
+        Ok(t)
+    }
+
+    fn end(&mut self) -> Result<()> {
+        let Some(indent) = self.root_indent else {
+            return Ok(());
+        };
+        if !self.strict {
+            return Ok(());
+        }
+        self.lines.fill()?;
+        if let Some(line) = self.lines.peeked() {
+            let pl = line.parsed();
+            if pl.indent == indent && matches!(pl.kind, LineKind::Scalar(_)) {
+                return Err(Error::syntax(
+                    ErrorKind::Syntax,
+                    line.content_span(),
+                    "two primitives at root depth in strict mode",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse the key `raw`, a slice of `line`.
+    fn key(&self, line: &Line<'de>, raw: &str) -> Result<Cow<'de, str>> {
+        match parse_key_token(raw, self.mark_quoted_dots) {
+            Ok(Cow::Borrowed(k)) => Ok(slice_of(&line.text, k)),
+            Ok(Cow::Owned(k)) => Ok(Cow::Owned(k)),
+            Err(e) => Err(Error::syntax(e.kind(), line.span(raw), e.message())),
+        }
+    }
+
+    /// Parse the scalar `raw`, a slice of `text`; `at` locates `raw`.
+    fn scalar(&self, text: &Cow<'de, str>, raw: &str, at: Span) -> Result<Shape<'de>> {
+        match parse_scalar_token(raw) {
+            Ok(Scalar::Str(Cow::Borrowed(s))) => Ok(Shape::Str(slice_of(text, s))),
+            Ok(Scalar::Str(Cow::Owned(s))) => Ok(Shape::Str(Cow::Owned(s))),
+            Ok(Scalar::Other(v)) => Ok(Shape::Primitive(v)),
+            Err(e) => Err(Error::syntax(e.kind(), at, e.message())),
+        }
+    }
+
+    /// Parse the key of an array header; `head` is the slice of `line` the header starts at.
+    fn header_key(&self, line: &Line<'de>, head: &str, key: &str) -> Result<Cow<'de, str>> {
+        match head.get(..key.len()) {
+            Some(raw) if raw == key => self.key(line, raw),
+            _ => self.owned_key(key, line.content_span()),
+        }
+    }
+
+    fn owned_key(&self, raw: &str, at: Span) -> Result<Cow<'de, str>> {
+        match parse_key_token(raw, self.mark_quoted_dots) {
+            Ok(k) => Ok(Cow::Owned(k.into_owned())),
+            Err(e) => Err(Error::syntax(e.kind(), at, e.message())),
+        }
+    }
+
+    /// Tabular header fields; owned, since the header has been re-parsed.
+    fn header_keys<F: AsRef<str>>(&self, fields: &[F], at: Span) -> Result<Vec<Cow<'de, str>>> {
+        fields
+            .iter()
+            .map(|f| self.owned_key(f.as_ref(), at))
+            .collect()
+    }
+
+    /// Indent of the next line if it is nested deeper than `parent_indent`.
+    fn child_indent(&mut self, parent_indent: usize) -> Result<Option<usize>> {
+        Ok(self
+            .lines
+            .peek_indent()?
+            .filter(|&indent| indent > parent_indent))
+    }
+
+    fn resolve(&mut self, slot: Slot<'de>) -> Result<Shape<'de>> {
+        match slot {
+            Slot::Document => self.resolve_document(),
+            Slot::Token { text, at } => self.scalar(&text, &text, at),
+            Slot::Node { min_indent } => self.resolve_node(min_indent),
+            Slot::Child { parent_indent } => {
+                self.lines.fill()?;
+                if let Some(line) = self.lines.peeked() {
+                    let pl = line.parsed();
+                    if pl.indent > parent_indent {
+                        if let LineKind::Scalar(s) = pl.kind {
+                            if !s.starts_with('@') && !s.starts_with('[') {
+                                return Err(Error::syntax(
+                                    ErrorKind::Syntax,
+                                    line.span(s),
+                                    "missing colon in key-value context",
+                                ));
+                            }
+                        }
+                    }
+                }
+                self.resolve_node(parent_indent + 1)
+            }
+            Slot::Array {
+                header,
+                values,
+                at,
+                parent_indent,
+            } => self.resolve_array(header, values, at, parent_indent),
+            Slot::Resolved { shape, .. } => Ok(shape),
+        }
+    }
+
+    fn resolve_document(&mut self) -> Result<Shape<'de>> {
+        self.lines.fill()?;
+        let Some(line) = self.lines.peeked() else {
+            // Empty document represents an empty object
+            return Ok(Shape::Map(Map::Empty));
+        };
+        let pl = line.parsed();
+        if pl.indent == 0 {
+            let header = match pl.kind {
+                LineKind::KeyOnly { key: "{0}" } => {
+                    self.lines.take();
+                    return Ok(Shape::Map(Map::Empty));
+                }
+                LineKind::KeyOnly { key } if is_array_header_line(key) || key.starts_with('[') => {
+                    parse_array_header(&format!("{}:", key)).map(|h| (h, ""))
+                }
+                LineKind::KeyValue { key, value } => {
+                    let combined = format!("{}: {}", key, value);
+                    if is_array_header_line(&combined) || key.starts_with('[') {
+                        parse_array_header(&combined).map(|h| (h, value))
+                    } else {
+                        None
+                    }
+                }
+                LineKind::Scalar(s) if is_array_header_line(s) || s.starts_with('[') => {
+                    parse_array_header(s).map(|h| (h, s))
+                }
+                _ => None,
+            };
+            if let Some((header, tail)) = header.filter(|(h, _)| h.key.is_none()) {
+                let values = inline_values(line, &header, tail);
+                let at = line.content_span();
+                self.lines.take();
+                return self.resolve_array(header, values, at, 0);
+            }
+        }
+        self.root_indent = Some(pl.indent);
+        let indent = pl.indent;
+        self.resolve_node(indent)
+    }
+
+    fn resolve_node(&mut self, min_indent: usize) -> Result<Shape<'de>> {
+        self.lines.fill()?;
+        let Some(line) = self.lines.peeked() else {
+            return Ok(Shape::Map(Map::Empty));
+        };
+        let pl = line.parsed();
+        if pl.indent < min_indent {
+            return Ok(Shape::Map(Map::Empty));
+        }
+        let indent = pl.indent;
+        let at = line.content_span();
+        match pl.kind {
+            LineKind::KeyOnly { key: "[0]" } => {
+                self.lines.take();
+                Ok(Shape::Seq(Seq::inline(Vec::new(), at)))
+            }
+            LineKind::KeyOnly { key: "{0}" } => {
+                self.lines.take();
+                Ok(Shape::Map(Map::Empty))
+            }
+            LineKind::KeyOnly { key } | LineKind::KeyValue { key, .. } if key.starts_with('[') => {
+                let (text, tail) = match pl.kind {
+                    LineKind::KeyValue { key, value } => (format!("{}: {}", key, value), value),
+                    _ => (format!("{}:", key), ""),
+                };
+                match parse_array_header(&text).filter(|h| h.key.is_none()) {
+                    Some(header) => {
+                        let values = inline_values(line, &header, tail);
+                        self.lines.take();
+                        self.resolve_array(header, values, at, indent)
+                    }
+                    None => Ok(Shape::Map(Map::Fields {
+                        indent,
+                        first: None,
+                    })),
+                }
+            }
+            LineKind::ListItem { .. } => Ok(Shape::Seq(Seq {
+                kind: SeqKind::List { indent },
+                declared: None,
+                count: 0,
+                header_at: at,
+            })),
+            LineKind::KeyValue { .. } | LineKind::KeyOnly { .. } => Ok(Shape::Map(Map::Fields {
+                indent,
+                first: None,
+            })),
+            LineKind::Scalar(s) => {
+                if let Some((dch, header_str)) = parse_header(s) {
+                    let header_str = header_str.to_string();
+                    self.lines.take();
+                    return self.resolve_legacy_table(dch, &header_str, indent, at);
+                }
+                let shape = self.scalar(&line.text, s, line.span(s))?;
+                self.lines.take();
+                Ok(shape)
+            }
+            LineKind::Blank => Ok(Shape::Null),
+        }
+    }
+
+    /// Value of an array whose header line has been consumed.
+    fn resolve_array(
+        &mut self,
+        header: ArrayHeader,
+        values: Option<Cell<'de>>,
+        at: Span,
+        parent_indent: usize,
+    ) -> Result<Shape<'de>> {
+        if self.strict && header.fields_delimiter_mismatch {
+            return Err(Error::syntax(
+                ErrorKind::DelimiterMismatch,
+                at,
+                "mismatched delimiter between bracket and brace fields",
+            ));
+        }
+        if let Some(fields) = &header.fields {
+            let fields = self.header_keys(fields, at)?;
+            let indent = self
+                .child_indent(parent_indent)?
+                .unwrap_or(parent_indent + 1);
+            return Ok(Shape::Seq(Seq {
+                kind: SeqKind::Table {
+                    indent,
+                    fields: fields.into(),
+                    delimiter: header.delimiter,
+                },
+                declared: Some(header.length),
+                count: 0,
+                header_at: at,
+            }));
+        }
+        if let Some((inline, inline_at)) = values.filter(|(s, _)| !s.is_empty()) {
+            let cells = split_cells(&inline, &inline, inline_at, header.delimiter);
+            if cells.len() != header.length {
+                return Err(Error::syntax(
+                    ErrorKind::LengthMismatch,
+                    inline_at,
+                    format!(
+                        "array length mismatch: header declares {} elements but found {}",
+                        header.length,
+                        cells.len()
+                    ),
+                ));
+            }
+            return Ok(Shape::Seq(Seq::inline(cells, at)));
+        }
+        let indent = self
+            .child_indent(parent_indent)?
+            .unwrap_or(parent_indent + 1);
+        Ok(Shape::Seq(Seq {
+            kind: SeqKind::List { indent },
+            declared: Some(header.length),
+            count: 0,
+            header_at: at,
+        }))
+    }
+
+    fn resolve_legacy_table(
+        &mut self,
+        dch: char,
+        header_str: &str,
+        indent: usize,
+        at: Span,
+    ) -> Result<Shape<'de>> {
+        if self.strict && !(dch == ',' || dch == '\t' || dch == '|') {
+            return Err(Error::syntax(
+                ErrorKind::DelimiterMismatch,
+                at,
+                format!(
+                    "invalid header delimiter '{}': expected ',', '\\t', or '|'",
+                    dch
+                ),
+            ));
+        }
+        let raw_tokens = split_delim_aware(header_str, dch);
+        let keys = self.header_keys(&raw_tokens, at)?;
+        if self.strict {
+            if keys.is_empty() {
+                return Err(Error::syntax(ErrorKind::Syntax, at, "empty tabular header"));
+            }
+            if let Some(htok) = raw_tokens
+                .iter()
+                .find(|h| !is_quoted_token(h) && token_requires_quotes(h, dch))
+            {
+                return Err(Error::syntax(
+                    ErrorKind::Syntax,
+                    at,
+                    format!("unquoted header token requires quotes: {}", htok),
+                ));
+            }
+            for (i, k) in keys.iter().enumerate() {
+                if keys[i + 1..].contains(k) {
+                    return Err(Error::syntax(
+                        ErrorKind::DuplicateKey,
+                        at,
+                        format!("duplicate header key: {}", k),
+                    ));
+                }
+            }
+        }
+        Ok(Shape::Seq(Seq {
+            kind: SeqKind::LegacyTable {
+                indent,
+                fields: keys.into(),
+                delimiter: dch,
+            },
+            declared: None,
+            count: 0,
+            header_at: at,
+        }))
+    }
+
+    /// Next element of `seq`, or `None` once the array ends.
+    fn next_item(&mut self, seq: &mut Seq<'de>) -> Result<Option<Slot<'de>>> {
+        let slot = match &mut seq.kind {
+            SeqKind::Inline { cells } => cells.next().map(|(text, at)| Slot::Token { text, at }),
+            SeqKind::List { indent } => {
+                let indent = *indent;
+                self.next_list_item(indent, seq.count > 0)?
+            }
+            SeqKind::Table {
+                indent,
+                fields,
+                delimiter,
+            } => {
+                let (indent, delimiter) = (*indent, *delimiter);
+                let fields = fields.clone();
+                self.next_table_row(indent, fields, delimiter, seq.count > 0)?
+            }
+            SeqKind::LegacyTable {
+                indent,
+                fields,
+                delimiter,
+            } => {
+                let (indent, delimiter) = (*indent, *delimiter);
+                let fields = fields.clone();
+                self.next_legacy_row(indent, fields, delimiter)?
+            }
+        };
+        if slot.is_some() {
+            seq.count += 1;
+        }
+        Ok(slot)
+    }
+
+    fn next_list_item(&mut self, indent: usize, inside: bool) -> Result<Option<Slot<'de>>> {
+        self.lines.fill()?;
+        let Some(line) = self.lines.peeked() else {
+            return Ok(None);
+        };
+        let pl = line.parsed();
+        if pl.indent != indent || !matches!(pl.kind, LineKind::ListItem { .. }) {
+            return Ok(None);
+        }
+        if self.strict && inside {
+            if let Some(blank) = self.lines.blank_before() {
+                return Err(Error::syntax(
+                    ErrorKind::Indentation,
+                    blank,
+                    "blank line inside array",
+                ));
+            }
+        }
+        let line = self.lines.take().expect("peeked line");
+        let LineKind::ListItem { value } = line.parsed().kind else {
+            unreachable!()
+        };
+        let Some(vs) = value else {
+            // Bare "-": nested value or empty object
+            return Ok(Some(Slot::Node {
+                min_indent: indent + 1,
+            }));
+        };
+        // 1) Array header on the hyphen line: "- [N]: ..." or "- key[N]{fields}:"
+        if is_array_header_line(vs) || vs.starts_with('[') {
+            if let Some(header) = parse_array_header(vs) {
+                let values = inline_values(&line, &header, vs);
+                let at = line.span(vs);
+                let Some(key) = header.key.as_deref() else {
+                    let shape = self.resolve_array(header, values, at, indent)?;
+                    return Ok(Some(Slot::Resolved {
+                        shape,
+                        at: Some(at),
+                    }));
+                };
+                let key = self.header_key(&line, vs, key)?;
+                let first = Slot::Array {
+                    header,
+                    values,
+                    at,
+                    parent_indent: indent,
+                };
+                return Ok(Some(Slot::Resolved {
+                    shape: Shape::Map(Map::Fields {
+                        indent: indent + 2,
+                        first: Some((key, Box::new(first))),
+                    }),
+                    at: Some(at),
+                }));
+            }
+        }
+        // 2) Object with its first field on the hyphen line: "- key: value"
+        if let Some((kraw, vraw)) = split_kv_quote_aware(vs) {
+            let key = self.key(&line, kraw)?;
+            let first = if vraw.is_empty() {
+                // Nested value sits deeper than the sibling fields
+                Slot::Node {
+                    min_indent: indent + 3,
+                }
+            } else {
+                Slot::Token {
+                    text: line.slice(vraw),
+                    at: line.span(vraw),
+                }
+            };
+            return Ok(Some(Slot::Resolved {
+                shape: Shape::Map(Map::Fields {
+                    indent: indent + 2,
+                    first: Some((key, Box::new(first))),
+                }),
+                at: Some(line.span(vs)),
+            }));
+        }
+        // 3) Primitive list item
+        Ok(Some(Slot::Token {
+            text: line.slice(vs),
+            at: line.span(vs),
+        }))
+    }
+
+    fn next_table_row(
+        &mut self,
+        indent: usize,
+        fields: Rc<[Cow<'de, str>]>,
+        delimiter: char,
+        inside: bool,
+    ) -> Result<Option<Slot<'de>>> {
+        self.lines.fill()?;
+        let Some(line) = self.lines.peeked() else {
+            return Ok(None);
+        };
+        let pl = line.parsed();
+        let row = match pl.kind {
+            LineKind::Scalar(row) => row,
+            // Outside strict mode, rows written as `- ` items are read as the
+            // direct deserializer reads them
+            LineKind::ListItem { value: Some(row) } if !self.strict => row,
+            _ => return Ok(None),
+        };
+        if pl.indent != indent {
+            return Ok(None);
+        }
+        if self.strict && inside {
+            if let Some(blank) = self.lines.blank_before() {
+                return Err(Error::syntax(
+                    ErrorKind::Indentation,
+                    blank,
+                    "blank line inside table",
+                ));
+            }
+        }
+        let row_at = line.span(row);
+        if self.strict && check_delimiter_mismatch(row, delimiter) {
+            return Err(Error::syntax(
+                ErrorKind::DelimiterMismatch,
+                row_at,
+                "delimiter mismatch: row uses different delimiter than header declares",
+            ));
+        }
+        let cells = split_cells(&line.text, row, row_at, delimiter);
+        if cells.len() != fields.len() {
+            return Err(Error::syntax(
+                ErrorKind::LengthMismatch,
+                row_at,
+                format!(
+                    "tabular row has {} values but header declares {} fields",
+                    cells.len(),
+                    fields.len()
+                ),
+            ));
+        }
+        self.lines.take();
+        Ok(Some(Slot::Resolved {
+            shape: Shape::Map(Map::row(fields, cells)),
+            at: Some(row_at),
+        }))
+    }
+
+    fn next_legacy_row(
+        &mut self,
+        indent: usize,
+        fields: Rc<[Cow<'de, str>]>,
+        delimiter: char,
+    ) -> Result<Option<Slot<'de>>> {
+        self.lines.fill()?;
+        if self.strict {
+            if let Some(blank) = self.lines.blank_before() {
+                return Err(Error::syntax(
+                    ErrorKind::Indentation,
+                    blank,
+                    "blank line inside table",
+                ));
+            }
+        }
+        let Some(line) = self.lines.peeked() else {
+            return Ok(None);
+        };
+        let pl = line.parsed();
+        let LineKind::ListItem { value: Some(row) } = pl.kind else {
+            return Ok(None);
+        };
+        if pl.indent != indent {
+            return Ok(None);
+        }
+        let row_at = line.span(row);
+        if self.strict {
+            let trimmed = row.trim_end();
+            if trimmed.as_bytes().last().copied() == Some(delimiter as u8) {
+                let at = row_at.within(row, &trimmed[trimmed.len() - 1..]);
+                return Err(Error::syntax(
+                    ErrorKind::DelimiterMismatch,
+                    at,
+                    "trailing delimiter in row",
+                ));
+            }
+            let raw_cells = split_delim_aware(row, delimiter);
+            if raw_cells.len() != fields.len() {
+                return Err(Error::syntax(
+                    ErrorKind::LengthMismatch,
+                    row_at,
+                    format!(
+                        "row cell count {} does not match header {}",
+                        raw_cells.len(),
+                        fields.len()
+                    ),
+                ));
+            }
+            if let Some(ctok) = raw_cells
+                .iter()
+                .find(|c| !is_quoted_token(c) && cell_token_requires_quotes(c, delimiter))
+            {
+                return Err(Error::syntax(
+                    ErrorKind::Syntax,
+                    row_at.within(row, ctok),
+                    format!("unquoted cell requires quotes: {}", ctok),
+                ));
+            }
+        }
+        let mut cells = split_cells(&line.text, row, row_at, delimiter);
+        cells.resize(fields.len(), (Cow::Borrowed("null"), row_at));
+        self.lines.take();
+        Ok(Some(Slot::Resolved {
+            shape: Shape::Map(Map::row(fields, cells)),
+            at: Some(row_at),
+        }))
+    }
+
+    /// Check the element count once an array has been fully read.
+    fn finish_seq(&self, seq: &Seq<'de>) -> Result<()> {
+        match (&seq.kind, seq.declared) {
+            (SeqKind::Table { .. }, Some(n)) if n != seq.count => Err(Error::syntax(
+                ErrorKind::LengthMismatch,
+                seq.header_at,
+                format!(
+                    "tabular array has {} rows but header declares {}",
+                    seq.count, n
+                ),
+            )),
+            (SeqKind::List { .. }, Some(n)) if n != seq.count => Err(Error::syntax(
+                ErrorKind::LengthMismatch,
+                seq.header_at,
+                format!(
+                    "array length mismatch: header declares {} elements but found {}",
+                    n, seq.count
+                ),
+            )),
+            (SeqKind::LegacyTable { .. }, _) if self.strict && seq.count == 0 => {
+                Err(Error::syntax(
+                    ErrorKind::LengthMismatch,
+                    seq.header_at,
+                    "empty table (no rows)",
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Consume a root `key[N]{fields}:` header and return a cursor over its rows.
+    #[cfg(feature = "std")]
+    pub(crate) fn open_table(&mut self) -> Result<(String, TableCursor<'de>)> {
+        self.lines.fill()?;
+        let Some(line) = self.lines.peeked() else {
+            return Err(Error::data(
+                ErrorKind::Syntax,
+                "expected a tabular array header, found end of input",
+            ));
+        };
+        let at = line.content_span();
+        let pl = line.parsed();
+        let header = match pl.kind {
+            LineKind::KeyOnly { key } if pl.indent == 0 => {
+                parse_array_header(&format!("{}:", key)).filter(|h| h.fields.is_some())
+            }
+            _ => None,
+        };
+        let Some(header) = header else {
+            return Err(Error::syntax(
+                ErrorKind::Syntax,
+                at,
+                "expected a tabular array header like `items[N]{fields}:`",
+            ));
+        };
+        let key = match header.key.as_deref() {
+            Some(k) => self.owned_key(k, at)?.into_owned(),
+            None => String::new(),
+        };
+        self.lines.take();
+        match self.resolve_array(header, None, at, 0)? {
+            Shape::Seq(seq) => Ok((key, TableCursor(seq))),
+            _ => unreachable!("tabular headers resolve to a sequence"),
+        }
+    }
+
+    /// Deserialize the next row of an open table.
+    #[cfg(feature = "std")]
+    pub(crate) fn next_row<T: Deserialize<'de>>(
+        &mut self,
+        cursor: &mut TableCursor<'de>,
+    ) -> Result<Option<T>> {
+        match self.next_item(&mut cursor.0)? {
+            Some(slot) => T::deserialize(ValueDe { de: self, slot }).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Check the declared row count of a fully read table.
+    #[cfg(feature = "std")]
+    pub(crate) fn finish_table(&self, cursor: &TableCursor<'de>) -> Result<()> {
+        self.finish_seq(&cursor.0)
+    }
+
+    /// Check that nothing follows a fully read table.
+    #[cfg(feature = "std")]
+    pub(crate) fn end_table(&mut self) -> Result<()> {
+        self.lines.fill()?;
+        match self.lines.peeked() {
+            Some(line) => Err(Error::syntax(
+                ErrorKind::Syntax,
+                line.content_span(),
+                "unexpected content after the table",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Next field of `map`, or `None` once the object ends.
+    fn next_field(&mut self, map: &mut Map<'de>) -> Result<Option<(Cow<'de, str>, Slot<'de>)>> {
+        match map {
+            Map::Empty => Ok(None),
+            Map::Row { fields, cells, idx } => {
+                let Some((text, at)) = cells.next() else {
+                    return Ok(None);
+                };
+                let key = fields[*idx].clone();
+                *idx += 1;
+                Ok(Some((key, Slot::Token { text, at })))
+            }
+            Map::Fields { indent, first } => {
+                if let Some((key, slot)) = first.take() {
+                    return Ok(Some((key, *slot)));
+                }
+                let indent = *indent;
+                self.lines.fill()?;
+                let Some(line) = self.lines.peeked() else {
+                    return Ok(None);
+                };
+                let pl = line.parsed();
+                if pl.indent != indent {
+                    return Ok(None);
+                }
+                let header = match pl.kind {
+                    // Scalar keyed-array header lines like "key[N] v1,v2"
+                    LineKind::Scalar(s) if is_array_header_line(s) && s.contains('[') => {
+                        match parse_scalar_keyed_array_header(s) {
+                            Some(h) if h.key.is_some() => Some((h, s)),
+                            _ => return Ok(None),
+                        }
+                    }
+                    LineKind::KeyValue { key, value } => {
+                        parse_array_header(&format!("{}: {}", key, value)).map(|h| (h, value))
+                    }
+                    LineKind::KeyOnly { key } => {
+                        parse_array_header(&format!("{}:", key)).map(|h| (h, ""))
+                    }
+                    _ => return Ok(None),
+                };
+                let header = header.map(|(h, tail)| {
+                    let values = inline_values(line, &h, tail);
+                    (h, values)
+                });
+                let line = self.lines.take().expect("peeked line");
+                self.field_from_line(line, header, indent).map(Some)
+            }
+        }
+    }
+
+    fn field_from_line(
+        &mut self,
+        line: Line<'de>,
+        header: Option<(ArrayHeader, Option<Cell<'de>>)>,
+        indent: usize,
+    ) -> Result<(Cow<'de, str>, Slot<'de>)> {
+        if let Some((header, values)) = header {
+            let key = match (header.key.as_deref(), line.parsed().kind) {
+                (None, _) => Cow::Borrowed(""),
+                (
+                    Some(k),
+                    LineKind::KeyValue { key: head, .. }
+                    | LineKind::KeyOnly { key: head }
+                    | LineKind::Scalar(head),
+                ) => self.header_key(&line, head, k)?,
+                (Some(k), _) => self.owned_key(k, line.content_span())?,
+            };
+            return Ok((
+                key,
+                Slot::Array {
+                    header,
+                    values,
+                    at: line.content_span(),
+                    parent_indent: indent,
+                },
+            ));
+        }
+        match line.parsed().kind {
+            LineKind::KeyValue { key, value } => Ok((
+                self.key(&line, key)?,
+                Slot::Token {
+                    text: line.slice(value),
+                    at: line.span(value),
+                },
+            )),
+            LineKind::KeyOnly { key } => Ok((
+                self.key(&line, key)?,
+                Slot::Child {
+                    parent_indent: indent,
+                },
+            )),
+            _ => unreachable!("field lines are key lines or headers"),
+        }
+    }
+}
+
+/// Row position inside a table opened with [`Deserializer::open_table`].
+#[cfg(feature = "std")]
+pub(crate) struct TableCursor<'de>(Seq<'de>);
+
+#[cfg(feature = "std")]
+impl<'de> TableCursor<'de> {
+    pub(crate) fn fields(&self) -> &[Cow<'de, str>] {
+        match &self.0.kind {
+            SeqKind::Table { fields, .. } => fields,
+            _ => &[],
+        }
+    }
+
+    pub(crate) fn declared_len(&self) -> usize {
+        self.0.declared.unwrap_or(0)
+    }
+
+    pub(crate) fn rows_read(&self) -> usize {
+        self.0.count
+    }
+}
+
+/// Cells of `row`, a slice of `text`; `row_at` locates `row`.
+fn split_cells<'de>(
+    text: &Cow<'de, str>,
+    row: &str,
+    row_at: Span,
+    delimiter: char,
+) -> Vec<Cell<'de>> {
+    split_delim_aware(row, delimiter)
+        .into_iter()
+        .map(|cell| (slice_of(text, cell), row_at.within(row, cell)))
+        .collect()
+}
+
+/// Inline values of `header`, taken from `tail` (the part of `line` it was parsed from).
+fn inline_values<'de>(line: &Line<'de>, header: &ArrayHeader, tail: &str) -> Option<Cell<'de>> {
+    let values = header.inline_values.as_deref()?;
+    match tail.len().checked_sub(values.len()) {
+        Some(start) if tail.ends_with(values) => {
+            let part = &tail[start..];
+            Some((line.slice(part), line.span(part)))
+        }
+        _ => Some((Cow::Owned(values.to_string()), line.content_span())),
+    }
+}
+
+impl<'de> Seq<'de> {
+    fn inline(cells: Vec<Cell<'de>>, header_at: Span) -> Self {
+        Seq {
+            declared: None,
+            count: 0,
+            header_at,
+            kind: SeqKind::Inline {
+                cells: cells.into_iter(),
+            },
+        }
+    }
+}
+
+impl<'de> Map<'de> {
+    fn row(fields: Rc<[Cow<'de, str>]>, cells: Vec<Cell<'de>>) -> Self {
+        Map::Row {
+            fields,
+            cells: cells.into_iter(),
+            idx: 0,
+        }
+    }
+}
+
+/// Hand a key or string to a visitor, borrowed when it came straight from the input.
+fn visit_str<'de, V: Visitor<'de>>(s: Cow<'de, str>, visitor: V) -> Result<V::Value> {
+    match s {
+        Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
+        Cow::Owned(s) => visitor.visit_string(s),
+    }
+}
+
+/// Deserialize a key, borrowed when it came straight from the input; the key
+/// is kept for error paths.
+fn key_seed<'de, K: DeserializeSeed<'de>>(key: &Cow<'de, str>, seed: K) -> Result<K::Value> {
+    match key {
+        Cow::Borrowed(k) => seed.deserialize(BorrowedStrDeserializer::new(k)),
+        Cow::Owned(k) => seed.deserialize(StrDeserializer::new(k)),
+    }
+}
+
+struct ValueDe<'a, 'de, S> {
+    de: &'a mut Deserializer<'de, S>,
+    slot: Slot<'de>,
+}
+
+/// Locate a value error at `at`, if known.
+fn located<T>(result: Result<T>, at: Option<Span>) -> Result<T> {
+    match at {
+        Some(at) => result.map_err(|e| e.at(at)),
+        None => result,
+    }
+}
+
+impl<'de, S: LineSource<'de>> ValueDe<'_, 'de, S> {
+    /// Where the slot's value starts in the input, when it can be told
+    /// without consuming anything.
+    fn locate(&mut self) -> Result<Option<Span>> {
+        let min_indent = match &self.slot {
+            Slot::Token { at, .. } | Slot::Array { at, .. } => return Ok(Some(*at)),
+            Slot::Resolved { at, .. } => return Ok(*at),
+            Slot::Document => 0,
+            Slot::Node { min_indent } => *min_indent,
+            Slot::Child { parent_indent } => parent_indent + 1,
+        };
+        self.de.lines.fill()?;
+        Ok(self
+            .de
+            .lines
+            .peeked()
+            .filter(|l| l.parsed().indent >= min_indent)
+            .map(Line::content_span))
+    }
+
+    fn visit<V: Visitor<'de>>(self, shape: Shape<'de>, visitor: V) -> Result<V::Value> {
+        match shape {
+            Shape::Null => visitor.visit_unit(),
+            Shape::Str(s) => visit_str(s, visitor),
+            Shape::Primitive(v) => match v {
+                Value::Null => visitor.visit_unit(),
+                Value::Bool(b) => visitor.visit_bool(b),
+                Value::Number(Number::I64(i)) => visitor.visit_i64(i),
+                Value::Number(Number::U64(u)) => visitor.visit_u64(u),
+                Value::Number(Number::F64(f)) => visitor.visit_f64(f),
+                Value::String(s) => visitor.visit_string(s),
+                // Tokens never parse to containers
+                Value::Array(_) | Value::Object(_) => visitor.visit_unit(),
+            },
+            Shape::Seq(seq) => {
+                let mut access = SeqAccessor { de: self.de, seq };
+                let out = visitor.visit_seq(&mut access)?;
+                access.finish()?;
+                Ok(out)
+            }
+            Shape::Map(map) => {
+                let mut access = MapAccessor {
+                    de: self.de,
+                    map,
+                    pending: None,
+                };
+                let out = visitor.visit_map(&mut access)?;
+                access.finish()?;
+                Ok(out)
+            }
+        }
+    }
+}
+
+impl<'de, S: LineSource<'de>> de::Deserializer<'de> for ValueDe<'_, 'de, S> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value> {
+        let at = self.locate()?;
+        let slot = core::mem::replace(
+            &mut self.slot,
+            Slot::Resolved {
+                shape: Shape::Null,
+                at: None,
+            },
+        );
+        let shape = self.de.resolve(slot)?;
+        located(self.visit(shape, visitor), at)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value> {
+        let at = self.locate()?;
+        let shape = self.de.resolve(self.slot)?;
+        let result = match shape {
+            Shape::Null | Shape::Primitive(Value::Null) => visitor.visit_none(),
+            shape => visitor.visit_some(ValueDe {
+                de: self.de,
+                slot: Slot::Resolved { shape, at },
+            }),
+        };
+        located(result, at)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        mut self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let at = self.locate()?;
+        let result = match self.de.resolve(self.slot)? {
+            Shape::Str(Cow::Borrowed(s)) => visitor.visit_enum(BorrowedStrDeserializer::new(s)),
+            Shape::Str(Cow::Owned(s)) => {
+                visitor.visit_enum(IntoDeserializer::<Error>::into_deserializer(s))
+            }
+            Shape::Map(map) => visitor.visit_enum(MapAccessor {
+                de: self.de,
+                map,
+                pending: None,
+            }),
+            _ => Err(Error::data(
+                ErrorKind::TypeMismatch,
+                "expected string or single-key object for enum",
+            )),
+        };
+        located(result, at)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        mut self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        if spanned::is_spanned(name, fields) {
+            let start = self.locate()?;
+            return visitor.visit_map(SpannedAccess::new(SpannedSlot {
+                de: self.de,
+                slot: Some(self.slot),
+                start,
+                exact: None,
+            }));
+        }
+        self.deserialize_any(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map identifier ignored_any
+    }
+}
+
+/// The value of a [`crate::Spanned`]; unless it is a single token, where it
+/// ends is only known once it has been read.
+struct SpannedSlot<'a, 'de, S> {
+    de: &'a mut Deserializer<'de, S>,
+    slot: Option<Slot<'de>>,
+    start: Option<Span>,
+    exact: Option<Span>,
+}
+
+impl<'de, S: LineSource<'de>> SpannedSource<'de> for SpannedSlot<'_, 'de, S> {
+    type Error = Error;
+
+    fn value<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value> {
+        let slot = self
+            .slot
+            .take()
+            .ok_or_else(|| <Error as de::Error>::custom("spanned value read twice"))?;
+        // Tokens and inline array values end where their span says, not
+        // where their line does
+        match &slot {
+            Slot::Token { at, .. } => self.exact = Some(*at),
+            Slot::Array {
+                values: Some((values, at)),
+                ..
+            } if !values.is_empty() => self.exact = Some(*at),
+            _ => {}
+        }
+        seed.deserialize(ValueDe { de: self.de, slot })
+    }
+
+    fn span(&mut self) -> Span {
+        let last = self.de.lines.last_taken();
+        let end = last.map_or(0, |l| l.offset + l.len);
+        match (self.exact, self.start) {
+            (Some(exact), _) => exact,
+            (None, Some(start)) => spanned::span_to(start, end),
+            // Nothing followed (say, an empty nested object): just past the last line read
+            (None, None) => Span {
+                offset: end,
+                ..Span::line(last.map_or(0, |l| l.line))
+            },
+        }
+    }
+}
+
+struct SeqAccessor<'a, 'de, S> {
+    de: &'a mut Deserializer<'de, S>,
+    seq: Seq<'de>,
+}
+
+impl<'de, S: LineSource<'de>> SeqAccessor<'_, 'de, S> {
+    /// Skip elements the visitor did not ask for, then check the declared length.
+    fn finish(&mut self) -> Result<()> {
+        while let Some(slot) = self.de.next_item(&mut self.seq)? {
+            de::Deserialize::deserialize(ValueDe { de: self.de, slot }).map(|_: IgnoredAny| ())?;
+        }
+        self.de.finish_seq(&self.seq)
+    }
+}
+
+impl<'de, S: LineSource<'de>> SeqAccess<'de> for SeqAccessor<'_, 'de, S> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        let index = self.seq.count;
+        // Rows and list items start on the next line; inline cells carry their own span
+        let at = match self.seq.kind {
+            SeqKind::Inline { .. } => None,
+            _ => {
+                self.de.lines.fill()?;
+                self.de.lines.peeked().map(Line::content_span)
+            }
+        };
+        match self.de.next_item(&mut self.seq)? {
+            Some(slot) => located(seed.deserialize(ValueDe { de: self.de, slot }), at)
+                .map(Some)
+                .map_err(|e| e.in_index(index)),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        // Cap the hint so a bogus header cannot force a huge allocation
+        self.seq
+            .declared
+            .map(|n| n.saturating_sub(self.seq.count).min(4096))
+    }
+}
+
+struct MapAccessor<'a, 'de, S> {
+    de: &'a mut Deserializer<'de, S>,
+    map: Map<'de>,
+    /// Key and value of the field whose key was last handed out
+    pending: Option<(Cow<'de, str>, Slot<'de>)>,
+}
+
+impl<'de, S: LineSource<'de>> MapAccessor<'_, 'de, S> {
+    fn skip_pending(&mut self) -> Result<()> {
+        if let Some((_, slot)) = self.pending.take() {
+            de::Deserialize::deserialize(ValueDe { de: self.de, slot }).map(|_: IgnoredAny| ())?;
+        }
+        Ok(())
+    }
+
+    /// Skip fields the visitor did not ask for.
+    fn finish(&mut self) -> Result<()> {
+        self.skip_pending()?;
+        while let Some(field) = self.de.next_field(&mut self.map)? {
+            self.pending = Some(field);
+            self.skip_pending()?;
+        }
+        Ok(())
+    }
+
+    fn take_value(&mut self) -> Slot<'de> {
+        self.take_field().1
+    }
+
+    fn take_field(&mut self) -> (Cow<'de, str>, Slot<'de>) {
+        self.pending.take().unwrap_or((
+            Cow::Borrowed(""),
+            Slot::Resolved {
+                shape: Shape::Null,
+                at: None,
+            },
+        ))
+    }
+
+    /// An enum object must hold exactly one key.
+    fn expect_end(mut self) -> Result<()> {
+        if self.de.next_field(&mut self.map)?.is_some() {
+            return Err(Error::data(
+                ErrorKind::TypeMismatch,
+                "expected string or single-key object for enum",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<'de, S: LineSource<'de>> MapAccess<'de> for MapAccessor<'_, 'de, S> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        self.skip_pending()?;
+        match self.de.next_field(&mut self.map)? {
+            Some((key, slot)) => {
+                let out = key_seed(&key, seed).map(Some);
+                self.pending = Some((key, slot));
+                out
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let (key, slot) = self.take_field();
+        seed.deserialize(ValueDe { de: self.de, slot })
+            .map_err(|e| e.in_key(&key))
+    }
+}
+
+impl<'de, S: LineSource<'de>> EnumAccess<'de> for MapAccessor<'_, 'de, S> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(mut self, seed: V) -> Result<(V::Value, Self)> {
+        match self.de.next_field(&mut self.map)? {
+            Some((key, slot)) => {
+                let v = key_seed(&key, seed)?;
+                self.pending = Some((key, slot));
+                Ok((v, self))
+            }
+            None => Err(Error::data(
+                ErrorKind::TypeMismatch,
+                "expected string or single-key object for enum",
+            )),
+        }
+    }
+}
+
+impl<'de, S: LineSource<'de>> VariantAccess<'de> for MapAccessor<'_, 'de, S> {
+    type Error = Error;
+
+    fn unit_variant(mut self) -> Result<()> {
+        let slot = self.take_value();
+        de::Deserialize::deserialize(ValueDe { de: self.de, slot }).map(|_: IgnoredAny| ())?;
+        self.expect_end()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(mut self, seed: T) -> Result<T::Value> {
+        let slot = self.take_value();
+        let v = seed.deserialize(ValueDe { de: self.de, slot })?;
+        self.expect_end()?;
+        Ok(v)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(mut self, _len: usize, visitor: V) -> Result<V::Value> {
+        let slot = self.take_value();
+        let v = de::Deserializer::deserialize_seq(ValueDe { de: self.de, slot }, visitor)?;
+        self.expect_end()?;
+        Ok(v)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        mut self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let slot = self.take_value();
+        let v = de::Deserializer::deserialize_map(ValueDe { de: self.de, slot }, visitor)?;
+        self.expect_end()?;
+        Ok(v)
+    }
+}
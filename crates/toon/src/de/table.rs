@@ -0,0 +1,106 @@
+//! Lazy typed iteration over a root tabular array
+//!
+//! For documents shaped like `items[N]{id,name}:` followed by rows, `TableReader`
+//! parses the header once and then deserializes one row per `next()` call.
+
+use core::marker::PhantomData;
+use std::io::BufRead;
+
+use serde::de::DeserializeOwned;
+
+use crate::de::reader::{Deserializer, TableCursor};
+use crate::decode::reader::IoLines;
+use crate::{Options, Result};
+
+/// Iterator of typed rows from a root `key[N]{fields}:` table.
+///
+/// Rows are read from the underlying reader on demand; the table is never
+/// collected. In strict mode the declared length `N` is checked once the last
+/// row has been read. The table must be the whole document: anything after its
+/// rows is an error. Iteration stops after the first error.
+///
+/// ```
+/// # use serde::Deserialize;
+/// #[derive(Deserialize)]
+/// struct User { id: u32, name: String }
+///
+/// let input = "users[2]{id,name}:\n  1,Ada\n  2,Bob\n";
+/// let rows = toon_rs::TableReader::<User, _>::new(input.as_bytes(), &toon_rs::Options::default())?;
+/// assert_eq!(rows.key(), "users");
+/// let users = rows.collect::<toon_rs::Result<Vec<User>>>()?;
+/// assert_eq!(users[1].name, "Bob");
+/// # Ok::<(), toon_rs::Error>(())
+/// ```
+pub struct TableReader<T, R> {
+    de: Deserializer<'static, IoLines<R>>,
+    key: String,
+    fields: Vec<String>,
+    cursor: TableCursor<'static>,
+    strict: bool,
+    done: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned, R: BufRead> TableReader<T, R> {
+    /// Read the table header from `reader`.
+    pub fn new(reader: R, options: &Options) -> Result<Self> {
+        let mut de = Deserializer::new(IoLines::new(reader), options);
+        let (key, cursor) = de.open_table()?;
+        Ok(Self {
+            de,
+            key,
+            fields: cursor.fields().iter().map(|f| f.to_string()).collect(),
+            cursor,
+            strict: options.strict,
+            done: false,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Key of the table (empty for a keyless root `[N]{...}:` header).
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Field names declared in the header.
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+
+    /// Row count `N` declared in the header.
+    pub fn declared_len(&self) -> usize {
+        self.cursor.declared_len()
+    }
+
+    /// Rows read so far.
+    pub fn rows_read(&self) -> usize {
+        self.cursor.rows_read()
+    }
+}
+
+impl<T: DeserializeOwned, R: BufRead> Iterator for TableReader<T, R> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.de.next_row(&mut self.cursor) {
+            Ok(Some(row)) => Some(Ok(row)),
+            Ok(None) => {
+                self.done = true;
+                let end = match self.strict {
+                    true => self.de.finish_table(&self.cursor),
+                    false => Ok(()),
+                };
+                end.and_then(|()| self.de.end_table()).err().map(Err)
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<T: DeserializeOwned, R: BufRead> core::iter::FusedIterator for TableReader<T, R> {}
@@ -0,0 +1,156 @@
+//! Whole-document validation that reports every problem, not just the first
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use crate::decode::parser::Parser;
+use crate::decode::scanner::scan;
+use crate::decode::validation::indentation_errors;
+use crate::error::{Error, ErrorKind, Span};
+use crate::options::Options;
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Accepted by the decoder in non-strict mode, but rejected in strict mode
+    Warning,
+    /// Rejected by the decoder with the given options
+    Error,
+}
+
+/// One problem found by [`validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: ErrorKind,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// The offending line of `source` with a caret under the problem.
+    pub fn snippet(&self, source: &str) -> String {
+        self.span.snippet(source)
+    }
+
+    fn from_error(e: Error, severity: Severity) -> Option<Self> {
+        match e {
+            Error::Syntax {
+                kind,
+                message,
+                span,
+                ..
+            } => Some(Diagnostic {
+                severity,
+                kind,
+                message,
+                span,
+            }),
+            // Decoding errors found after the text was read, such as a short
+            // table, may have no position
+            Error::Data {
+                kind,
+                message,
+                span,
+                ..
+            } => Some(Diagnostic {
+                severity,
+                kind,
+                message,
+                span: span.unwrap_or_default(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{} at {}: {}", severity, self.span, self.message)
+    }
+}
+
+/// Check `input` and report every problem found, in document order.
+///
+/// Unlike decoding, this carries on after each problem, so a document with
+/// five defects yields five diagnostics (at most one per line). With
+/// `options.strict` every problem is an error; otherwise problems only strict
+/// mode rejects are reported as warnings. Nothing is returned besides the
+/// diagnostics, so this doubles as a cheap "is this valid TOON?" check:
+///
+/// ```
+/// let opts = toon_rs::Options { strict: true, ..Default::default() };
+/// let found = toon_rs::validate("items[3]: a,b\nname: \"oops\n", &opts);
+/// assert_eq!(found.len(), 2);
+/// assert_eq!(found[1].span.line, 2);
+/// ```
+pub fn validate(input: &str, options: &Options) -> Vec<Diagnostic> {
+    let strict_errors = check(input, true, options.indent);
+    let mut found: Vec<Diagnostic> = Vec::new();
+    if !options.strict {
+        found.extend(
+            check(input, false, options.indent)
+                .into_iter()
+                .filter_map(|e| Diagnostic::from_error(e, Severity::Error)),
+        );
+    }
+    let severity = if options.strict {
+        Severity::Error
+    } else {
+        Severity::Warning
+    };
+    for d in strict_errors
+        .into_iter()
+        .filter_map(|e| Diagnostic::from_error(e, severity))
+    {
+        if !found.iter().any(|f| f.span.line == d.span.line) {
+            found.push(d);
+        }
+    }
+    found.sort_by_key(|d| d.span.offset);
+    found
+}
+
+/// Parser errors, plus indentation errors in strict mode.
+///
+/// With `serde`, the decoder behind [`crate::decode_from_str`] has the last
+/// word: if it accepts `input` there are no errors, and otherwise its error
+/// comes first, followed by the parser's errors on later lines.
+fn check(input: &str, strict: bool, indent_size: usize) -> Vec<Error> {
+    let mut errors = Vec::new();
+    if strict {
+        let raw_lines: Vec<&str> = input.split('\n').collect();
+        for e in indentation_errors(&scan(input), &raw_lines, indent_size) {
+            let offset = Span::of(input, raw_lines[e.line - 1]).map_or(0, |at| at.offset);
+            errors.push(e.into_error(offset));
+        }
+    }
+    let mut parser = Parser::from_input_with_strict(input, strict);
+    parser.parse_document();
+    errors.extend(parser.into_errors());
+    #[cfg(feature = "serde")]
+    {
+        let options = Options {
+            strict,
+            indent: indent_size,
+            ..Options::default()
+        };
+        match crate::de::from_str::<crate::value::Value>(input, &options) {
+            Ok(_) => errors.clear(),
+            Err(first) => {
+                let line = first.span().map_or(0, |at| at.line);
+                errors.retain(|e| e.span().is_some_and(|at| at.line > line));
+                errors.insert(0, first);
+            }
+        }
+    }
+    errors
+}
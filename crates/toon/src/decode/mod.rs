@@ -1,6 +1,10 @@
 //! Decoding pipeline (phase 1: scanner/parser skeleton)
 
+pub mod diagnostic;
 pub mod parser;
+#[cfg(feature = "serde")]
+pub mod partial;
 pub mod path_expand;
+pub mod reader;
 pub mod scanner;
 pub mod validation;
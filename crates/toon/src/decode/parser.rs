@@ -2,46 +2,56 @@ use crate::decode::scanner::{LineKind, ParsedLine, scan};
 
 #[cfg(not(feature = "std"))]
 use alloc::{
+    borrow::Cow,
     format,
     string::{String, ToString},
     vec::Vec,
 };
+#[cfg(feature = "std")]
+use std::borrow::Cow;
 
+use crate::error::{Error, ErrorKind, Span};
 use crate::number::has_forbidden_leading_zeros;
-use crate::value::{Number, Value};
+use crate::value::{Map, Number, Value};
 
 pub struct Parser<'a> {
+    /// The input, when known, for locating errors
+    source: Option<&'a str>,
     lines: Vec<ParsedLine<'a>>,
     idx: usize,
     strict: bool,
-    error: Option<crate::error::Error>,
+    /// Problems found so far, at most one per line, in the order reported
+    errors: Vec<Error>,
 }
 
 impl<'a> Parser<'a> {
     pub fn from_input(input: &'a str) -> Self {
         Self {
+            source: Some(input),
             lines: scan(input),
             idx: 0,
             strict: false,
-            error: None,
+            errors: Vec::new(),
         }
     }
 
     pub fn from_input_with_strict(input: &'a str, strict: bool) -> Self {
         Self {
+            source: Some(input),
             lines: scan(input),
             idx: 0,
             strict,
-            error: None,
+            errors: Vec::new(),
         }
     }
 
     pub fn from_lines(lines: Vec<ParsedLine<'a>>, strict: bool) -> Self {
         Self {
+            source: None,
             lines,
             idx: 0,
             strict,
-            error: None,
+            errors: Vec::new(),
         }
     }
 
@@ -49,6 +59,43 @@ impl<'a> Parser<'a> {
         self.lines.is_empty()
     }
 
+    /// Errors found by [`Parser::parse_document`]; parsing carries on past each
+    /// one, so a document can report several.
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    pub fn into_errors(self) -> Vec<Error> {
+        self.errors
+    }
+
+    /// Record `e` unless the previous error is on the same line, where it is
+    /// usually a consequence of the same defect.
+    fn report(&mut self, e: Error) {
+        let line = e.span().map(|s| s.line);
+        if self.errors.last().and_then(Error::span).map(|s| s.line) != line {
+            self.errors.push(e);
+        }
+    }
+
+    /// A syntax error at `part` (a slice of the input) or, failing that, at the
+    /// content of line `line_no`.
+    fn syntax(
+        &self,
+        kind: ErrorKind,
+        line_no: usize,
+        part: Option<&str>,
+        message: impl Into<String>,
+    ) -> Error {
+        let span = match self.source {
+            Some(src) => part
+                .and_then(|p| Span::of(src, p))
+                .unwrap_or_else(|| line_span(src, line_no)),
+            None => Span::line(line_no),
+        };
+        Error::syntax(kind, span, message)
+    }
+
     fn skip_blanks(&mut self) {
         while let Some(line) = self.lines.get(self.idx) {
             if matches!(line.kind, LineKind::Blank) {
@@ -74,83 +121,15 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_scalar_token_at_line(&mut self, s: &str, line_no: usize) -> Value {
-        if s.starts_with('"') {
-            match try_unescape_json_string(s) {
-                Ok(st) => return Value::String(st),
-                Err(e) if self.error.is_none() => {
-                    self.error = Some(crate::error::Error::Syntax {
-                        line: line_no,
-                        message: match e {
-                            StringParseError::Unterminated => "unterminated string".to_string(),
-                            StringParseError::InvalidEscape => {
-                                "invalid escape sequence".to_string()
-                            }
-                        },
-                    });
-                    // Return an empty string as a fallback
-                    return Value::String(String::new());
-                }
-                Err(_) => return Value::String(String::new()),
+        match parse_scalar(s) {
+            Ok(v) => v,
+            Err(e) => {
+                let e = self.syntax(e.kind(), line_no, Some(s), e.message());
+                self.report(e);
+                // Return an empty string as a fallback
+                Value::String(String::new())
             }
         }
-        if has_forbidden_leading_zeros(s) {
-            return Value::String(s.to_string());
-        }
-        match s {
-            "true" => return Value::Bool(true),
-            "false" => return Value::Bool(false),
-            "null" => return Value::Null,
-            _ => {}
-        }
-        // Fast path for pure integers (ASCII digits, optional leading '-')
-        let bs = s.as_bytes();
-        if !bs.is_empty() {
-            if bs[0] == b'-' {
-                if bs.len() > 1 && bs[1..].iter().all(|c| c.is_ascii_digit()) {
-                    if let Ok(i) = s.parse::<i64>() {
-                        return Value::Number(Number::I64(i));
-                    }
-                }
-            } else if bs.iter().all(|c| c.is_ascii_digit()) {
-                if let Ok(u) = s.parse::<u64>() {
-                    return Value::Number(Number::U64(u));
-                }
-            }
-        }
-        match classify_numeric_hint(s) {
-            Some(NumHint::Float) => {
-                if let Ok(f) = s.parse::<f64>() {
-                    // Normalize integer-valued floats to integers
-                    // Use f % 1.0 instead of f.fract() for no_std compatibility
-                    if f.is_finite() && f % 1.0 == 0.0 {
-                        if f >= 0.0 {
-                            return Value::Number(Number::U64(f as u64));
-                        } else {
-                            return Value::Number(Number::I64(f as i64));
-                        }
-                    }
-                    return Value::Number(Number::F64(f));
-                }
-            }
-            Some(NumHint::IntSigned) => {
-                if let Ok(i) = s.parse::<i64>() {
-                    return Value::Number(Number::I64(i));
-                }
-                if let Ok(f) = s.parse::<f64>() {
-                    return Value::Number(Number::F64(f));
-                }
-            }
-            Some(NumHint::IntUnsigned) => {
-                if let Ok(u) = s.parse::<u64>() {
-                    return Value::Number(Number::U64(u));
-                }
-                if let Ok(f) = s.parse::<f64>() {
-                    return Value::Number(Number::F64(f));
-                }
-            }
-            None => {}
-        }
-        Value::String(s.to_string())
     }
 
     fn parse_key_token(&mut self, k: &str) -> String {
@@ -158,35 +137,14 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_key_token_at_line(&mut self, k: &str, line_no: usize) -> String {
-        if k.starts_with('"') {
-            match try_unescape_json_string(k) {
-                Ok(st) => {
-                    // If this quoted key contains a dot, mark it to prevent path expansion
-                    // by prefixing with a zero-width space (U+200B)
-                    if st.contains('.') {
-                        let mut marked = String::with_capacity(st.len() + 3);
-                        marked.push('\u{200B}');
-                        marked.push_str(&st);
-                        return marked;
-                    }
-                    return st;
-                }
-                Err(e) if self.error.is_none() => {
-                    self.error = Some(crate::error::Error::Syntax {
-                        line: line_no,
-                        message: match e {
-                            StringParseError::Unterminated => "unterminated string".to_string(),
-                            StringParseError::InvalidEscape => {
-                                "invalid escape sequence".to_string()
-                            }
-                        },
-                    });
-                    return String::new();
-                }
-                Err(_) => return String::new(),
+        match parse_key(k, true) {
+            Ok(key) => key,
+            Err(e) => {
+                let e = self.syntax(e.kind(), line_no, Some(k), e.message());
+                self.report(e);
+                String::new()
             }
         }
-        k.to_string()
     }
 
     fn parse_array(&mut self, indent: usize) -> Value {
@@ -208,10 +166,12 @@ impl<'a> Parser<'a> {
                         self.idx = saved_idx; // Restore position
                         if is_inside {
                             let line_no = self.idx + 1;
-                            self.error = Some(crate::error::Error::Syntax {
-                                line: line_no,
-                                message: "blank line inside array".to_string(),
-                            });
+                            self.report(self.syntax(
+                                ErrorKind::Indentation,
+                                line_no,
+                                None,
+                                "blank line inside array",
+                            ));
                         }
                     }
                 }
@@ -241,9 +201,9 @@ impl<'a> Parser<'a> {
                         if let Some(ref key) = header.key {
                             let key_parsed = self.parse_key_token(key);
                             let child_indent = indent + 2;
-                            let mut map: Vec<(String, Value)> = Vec::new();
+                            let mut map = Map::new();
                             let v = self.parse_keyed_array_value(&header);
-                            map.push((key_parsed, v));
+                            map.push(key_parsed, v);
                             // Parse any additional fields at child indent
                             if let Value::Object(mut rest) = self.parse_object(child_indent) {
                                 map.append(&mut rest);
@@ -293,13 +253,13 @@ impl<'a> Parser<'a> {
                 if let Some((kraw, vraw)) = split_kv_quote_aware(vs) {
                     let key = self.parse_key_token(kraw);
                     let child_indent = indent + 2;
-                    let mut map: Vec<(String, Value)> = Vec::new();
+                    let mut map = Map::new();
                     if vraw.is_empty() {
                         // Value on following indented lines
                         let v = self.parse_node(child_indent);
-                        map.push((key, v));
+                        map.push(key, v);
                     } else {
-                        map.push((key, self.parse_scalar_token(vraw)));
+                        map.push(key, self.parse_scalar_token(vraw));
                     }
                     // Parse any additional fields at child indent and merge
                     if let Value::Object(mut rest) = self.parse_object(child_indent) {
@@ -316,7 +276,7 @@ impl<'a> Parser<'a> {
                 let child_val = self.parse_node(child_indent);
                 // If parse_node returns Null (no children), this is an empty object
                 if matches!(child_val, Value::Null) {
-                    arr.push(Value::Object(Vec::new()));
+                    arr.push(Value::Object(Map::new()));
                 } else {
                     arr.push(child_val);
                 }
@@ -326,7 +286,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_object(&mut self, indent: usize) -> Value {
-        let mut map: Vec<(String, Value)> = Vec::new();
+        let mut map = Map::new();
         loop {
             self.skip_blanks();
             // Support scalar keyed-array header lines like "key[N] v1,v2" by synthesizing a header
@@ -343,7 +303,7 @@ impl<'a> Parser<'a> {
                                         .map(|k| self.parse_key_token(k))
                                         .unwrap_or_default();
                                     let v = self.parse_keyed_array_value(&header);
-                                    map.push((k, v));
+                                    map.push(k, v);
                                     continue;
                                 }
                             }
@@ -379,14 +339,14 @@ impl<'a> Parser<'a> {
                             .map(|k| self.parse_key_token(k))
                             .unwrap_or_default();
                         let v = self.parse_keyed_array_value(&header);
-                        map.push((k, v));
+                        map.push(k, v);
                         continue;
                     }
 
                     self.next();
                     let k = self.parse_key_token(kref);
                     let v = self.parse_scalar_token(vref);
-                    map.push((k, v));
+                    map.push(k, v);
                 }
                 (Some(kref), None) => {
                     // Check if this is an array header like "key[3]:" or "key[2]{id,name}:"
@@ -398,7 +358,7 @@ impl<'a> Parser<'a> {
                             .map(|k| self.parse_key_token(k))
                             .unwrap_or_default();
                         let v = self.parse_keyed_array_value(&header);
-                        map.push((k, v));
+                        map.push(k, v);
                         continue;
                     }
                     self.next();
@@ -413,13 +373,10 @@ impl<'a> Parser<'a> {
                                 if let Some((dch, header_str)) = parse_header(header_text) {
                                     if self.strict && !(dch == ',' || dch == '\t' || dch == '|') {
                                         let line_no = self.idx + 1;
-                                        self.error = Some(crate::error::Error::Syntax {
-                                            line: line_no,
-                                            message: format!(
+                                        self.report(self.syntax(ErrorKind::DelimiterMismatch, line_no, None, format!(
                                                 "invalid header delimiter '{}': expected ',', '\\t', or '|'",
                                                 dch
-                                            ),
-                                        });
+                                            )));
                                     }
                                     self.next();
                                     let raw_header_tokens = split_delim_aware(header_str, dch);
@@ -430,23 +387,27 @@ impl<'a> Parser<'a> {
                                     if self.strict {
                                         if header_keys.is_empty() {
                                             let line_no = self.idx;
-                                            self.error = Some(crate::error::Error::Syntax {
-                                                line: line_no,
-                                                message: "empty tabular header".to_string(),
-                                            });
+                                            self.report(self.syntax(
+                                                ErrorKind::Syntax,
+                                                line_no,
+                                                None,
+                                                "empty tabular header",
+                                            ));
                                         }
                                         for &htok in raw_header_tokens.iter() {
                                             if !is_quoted_token(htok)
                                                 && token_requires_quotes(htok, dch)
                                             {
                                                 let line_no = self.idx;
-                                                self.error = Some(crate::error::Error::Syntax {
-                                                    line: line_no,
-                                                    message: format!(
+                                                self.report(self.syntax(
+                                                    ErrorKind::Syntax,
+                                                    line_no,
+                                                    Some(htok),
+                                                    format!(
                                                         "unquoted header token requires quotes: {}",
                                                         htok
                                                     ),
-                                                });
+                                                ));
                                                 break;
                                             }
                                         }
@@ -454,14 +415,15 @@ impl<'a> Parser<'a> {
                                             for j in (i + 1)..header_keys.len() {
                                                 if header_keys[i] == header_keys[j] {
                                                     let line_no = self.idx;
-                                                    self.error =
-                                                        Some(crate::error::Error::Syntax {
-                                                            line: line_no,
-                                                            message: format!(
-                                                                "duplicate header key: {}",
-                                                                header_keys[i]
-                                                            ),
-                                                        });
+                                                    self.report(self.syntax(
+                                                        ErrorKind::DuplicateKey,
+                                                        line_no,
+                                                        None,
+                                                        format!(
+                                                            "duplicate header key: {}",
+                                                            header_keys[i]
+                                                        ),
+                                                    ));
                                                     break;
                                                 }
                                             }
@@ -474,12 +436,12 @@ impl<'a> Parser<'a> {
                                             if let Some(bl) = self.peek() {
                                                 if matches!(bl.kind, LineKind::Blank) {
                                                     let line_no = self.idx + 1;
-                                                    self.error =
-                                                        Some(crate::error::Error::Syntax {
-                                                            line: line_no,
-                                                            message: "blank line inside table"
-                                                                .to_string(),
-                                                        });
+                                                    self.report(self.syntax(
+                                                        ErrorKind::Indentation,
+                                                        line_no,
+                                                        None,
+                                                        "blank line inside table",
+                                                    ));
                                                 }
                                             }
                                         }
@@ -506,58 +468,61 @@ impl<'a> Parser<'a> {
                                             && row_trimmed.as_bytes().last().copied()
                                                 == Some(dch as u8)
                                         {
-                                            self.error = Some(crate::error::Error::Syntax {
-                                                line: row_line,
-                                                message: "trailing delimiter in row".to_string(),
-                                            });
+                                            self.report(self.syntax(
+                                                ErrorKind::DelimiterMismatch,
+                                                row_line,
+                                                Some(&row_trimmed[row_trimmed.len() - 1..]),
+                                                "trailing delimiter in row",
+                                            ));
                                         }
                                         let cells = split_delim_aware(rs, dch);
-                                        if self.strict
-                                            && self.error.is_none()
-                                            && cells.len() != expected_cells
-                                        {
-                                            self.error = Some(crate::error::Error::Syntax {
-                                                line: row_line,
-                                                message: format!(
+                                        if self.strict && cells.len() != expected_cells {
+                                            self.report(self.syntax(
+                                                ErrorKind::LengthMismatch,
+                                                row_line,
+                                                Some(rs),
+                                                format!(
                                                     "row cell count {} does not match header {}",
                                                     cells.len(),
                                                     expected_cells
                                                 ),
-                                            });
+                                            ));
                                         }
-                                        if self.strict && self.error.is_none() {
+                                        if self.strict {
                                             for ctok in &cells {
                                                 if !is_quoted_token(ctok)
                                                     && cell_token_requires_quotes(ctok, dch)
                                                 {
-                                                    self.error =
-                                                        Some(crate::error::Error::Syntax {
-                                                            line: row_line,
-                                                            message: format!(
-                                                                "unquoted cell requires quotes: {}",
-                                                                ctok
-                                                            ),
-                                                        });
+                                                    self.report(self.syntax(
+                                                        ErrorKind::Syntax,
+                                                        row_line,
+                                                        Some(ctok),
+                                                        format!(
+                                                            "unquoted cell requires quotes: {}",
+                                                            ctok
+                                                        ),
+                                                    ));
                                                     break;
                                                 }
                                             }
                                         }
-                                        let mut om: Vec<(String, Value)> =
-                                            Vec::with_capacity(expected_cells);
+                                        let mut om = Map::with_capacity(expected_cells);
                                         for (i, hk) in header_keys.iter().enumerate() {
                                             let cell = cells.get(i).copied().unwrap_or("null");
-                                            om.push((hk.clone(), self.parse_scalar_token(cell)));
+                                            om.push(hk.clone(), self.parse_scalar_token(cell));
                                         }
                                         rows.push(Value::Object(om));
                                     }
                                     if self.strict && rows.is_empty() {
                                         let line_no = self.idx;
-                                        self.error = Some(crate::error::Error::Syntax {
-                                            line: line_no,
-                                            message: "empty table (no rows)".to_string(),
-                                        });
+                                        self.report(self.syntax(
+                                            ErrorKind::LengthMismatch,
+                                            line_no,
+                                            None,
+                                            "empty table (no rows)",
+                                        ));
                                     }
-                                    map.push((k.clone(), Value::Array(rows)));
+                                    map.push(k.clone(), Value::Array(rows));
                                     handled = true;
                                 }
                             }
@@ -570,15 +535,13 @@ impl<'a> Parser<'a> {
                             if nl.indent > indent {
                                 if let LineKind::Scalar(s) = &nl.kind {
                                     // Only error if it's a plain scalar, not a header line
-                                    if !s.starts_with('@')
-                                        && !s.starts_with('[')
-                                        && self.error.is_none()
-                                    {
-                                        self.error = Some(crate::error::Error::Syntax {
-                                            line: self.idx + 1,
-                                            message: "missing colon in key-value context"
-                                                .to_string(),
-                                        });
+                                    if !s.starts_with('@') && !s.starts_with('[') {
+                                        self.report(self.syntax(
+                                            ErrorKind::Syntax,
+                                            self.idx + 1,
+                                            None,
+                                            "missing colon in key-value context",
+                                        ));
                                     }
                                 }
                             }
@@ -586,9 +549,9 @@ impl<'a> Parser<'a> {
                         let v = self.parse_node(child_indent);
                         // If key: has no children, produce empty object instead of null
                         if matches!(v, Value::Null) {
-                            map.push((k, Value::Object(Vec::new())));
+                            map.push(k, Value::Object(Map::new()));
                         } else {
-                            map.push((k, v));
+                            map.push(k, v);
                         }
                     }
                 }
@@ -613,13 +576,15 @@ impl<'a> Parser<'a> {
                 // This is a tabular header, parse it as a root-level tabular array
                 if self.strict && !(dch == ',' || dch == '\t' || dch == '|') {
                     let line_no = self.idx + 1;
-                    self.error = Some(crate::error::Error::Syntax {
-                        line: line_no,
-                        message: format!(
+                    self.report(self.syntax(
+                        ErrorKind::DelimiterMismatch,
+                        line_no,
+                        None,
+                        format!(
                             "invalid header delimiter '{}': expected ',', '\\t', or '|'",
                             dch
                         ),
-                    });
+                    ));
                 }
                 self.next(); // Consume the header line
 
@@ -632,18 +597,22 @@ impl<'a> Parser<'a> {
                 if self.strict {
                     if header_keys.is_empty() {
                         let line_no = self.idx;
-                        self.error = Some(crate::error::Error::Syntax {
-                            line: line_no,
-                            message: "empty tabular header".to_string(),
-                        });
+                        self.report(self.syntax(
+                            ErrorKind::Syntax,
+                            line_no,
+                            None,
+                            "empty tabular header",
+                        ));
                     }
                     for htok in raw_header_tokens.iter() {
                         if !is_quoted_token(htok) && token_requires_quotes(htok, dch) {
                             let line_no = self.idx;
-                            self.error = Some(crate::error::Error::Syntax {
-                                line: line_no,
-                                message: format!("unquoted header token requires quotes: {}", htok),
-                            });
+                            self.report(self.syntax(
+                                ErrorKind::Syntax,
+                                line_no,
+                                Some(htok),
+                                format!("unquoted header token requires quotes: {}", htok),
+                            ));
                             break;
                         }
                     }
@@ -651,10 +620,12 @@ impl<'a> Parser<'a> {
                         for j in (i + 1)..header_keys.len() {
                             if header_keys[i] == header_keys[j] {
                                 let line_no = self.idx;
-                                self.error = Some(crate::error::Error::Syntax {
-                                    line: line_no,
-                                    message: format!("duplicate header key: {}", header_keys[i]),
-                                });
+                                self.report(self.syntax(
+                                    ErrorKind::DuplicateKey,
+                                    line_no,
+                                    None,
+                                    format!("duplicate header key: {}", header_keys[i]),
+                                ));
                                 break;
                             }
                         }
@@ -669,10 +640,12 @@ impl<'a> Parser<'a> {
                         if let Some(bl) = self.peek() {
                             if matches!(bl.kind, LineKind::Blank) {
                                 let line_no = self.idx + 1;
-                                self.error = Some(crate::error::Error::Syntax {
-                                    line: line_no,
-                                    message: "blank line inside table".to_string(),
-                                });
+                                self.report(self.syntax(
+                                    ErrorKind::Indentation,
+                                    line_no,
+                                    None,
+                                    "blank line inside table",
+                                ));
                             }
                         }
                     }
@@ -698,49 +671,57 @@ impl<'a> Parser<'a> {
 
                     let row_trimmed = rs.trim_end();
                     if self.strict && row_trimmed.as_bytes().last().copied() == Some(dch as u8) {
-                        self.error = Some(crate::error::Error::Syntax {
-                            line: row_line,
-                            message: "trailing delimiter in row".to_string(),
-                        });
+                        self.report(self.syntax(
+                            ErrorKind::DelimiterMismatch,
+                            row_line,
+                            Some(&row_trimmed[row_trimmed.len() - 1..]),
+                            "trailing delimiter in row",
+                        ));
                     }
 
                     let cells = split_delim_aware(rs, dch);
-                    if self.strict && self.error.is_none() && cells.len() != expected_cells {
-                        self.error = Some(crate::error::Error::Syntax {
-                            line: row_line,
-                            message: format!(
+                    if self.strict && cells.len() != expected_cells {
+                        self.report(self.syntax(
+                            ErrorKind::LengthMismatch,
+                            row_line,
+                            Some(rs),
+                            format!(
                                 "row cell count {} does not match header {}",
                                 cells.len(),
                                 expected_cells
                             ),
-                        });
+                        ));
                     }
-                    if self.strict && self.error.is_none() {
+                    if self.strict {
                         for ctok in &cells {
                             if !is_quoted_token(ctok) && cell_token_requires_quotes(ctok, dch) {
-                                self.error = Some(crate::error::Error::Syntax {
-                                    line: row_line,
-                                    message: format!("unquoted cell requires quotes: {}", ctok),
-                                });
+                                self.report(self.syntax(
+                                    ErrorKind::Syntax,
+                                    row_line,
+                                    Some(ctok),
+                                    format!("unquoted cell requires quotes: {}", ctok),
+                                ));
                                 break;
                             }
                         }
                     }
 
-                    let mut om: Vec<(String, Value)> = Vec::with_capacity(expected_cells);
+                    let mut om = Map::with_capacity(expected_cells);
                     for (i, hk) in header_keys.iter().enumerate() {
                         let cell = cells.get(i).copied().unwrap_or("null");
-                        om.push((hk.clone(), self.parse_scalar_token(cell)));
+                        om.push(hk.clone(), self.parse_scalar_token(cell));
                     }
                     rows.push(Value::Object(om));
                 }
 
                 if self.strict && rows.is_empty() {
                     let line_no = self.idx;
-                    self.error = Some(crate::error::Error::Syntax {
-                        line: line_no,
-                        message: "empty table (no rows)".to_string(),
-                    });
+                    self.report(self.syntax(
+                        ErrorKind::LengthMismatch,
+                        line_no,
+                        None,
+                        "empty table (no rows)",
+                    ));
                 }
 
                 return Value::Array(rows);
@@ -779,7 +760,7 @@ impl<'a> Parser<'a> {
                     }
                     if *key == "{0}" {
                         self.next();
-                        return Value::Object(Vec::new());
+                        return Value::Object(Map::new());
                     }
 
                     // Check for array headers like [N]: or [N]{fields}:
@@ -854,7 +835,7 @@ impl<'a> Parser<'a> {
         self.skip_blanks();
         if self.peek().is_none() {
             // Empty document represents an empty object (root documents are implicitly objects)
-            return Value::Object(Vec::new());
+            return Value::Object(Map::new());
         }
 
         // Check for root-level array headers: [N]:, [N]{fields}:, etc.
@@ -865,7 +846,7 @@ impl<'a> Parser<'a> {
                         // Check for empty object {0}:
                         if *key == "{0}" {
                             self.next();
-                            return Value::Object(Vec::new());
+                            return Value::Object(Map::new());
                         }
 
                         // Try to parse as root array header (only when keyless)
@@ -904,16 +885,18 @@ impl<'a> Parser<'a> {
         let result = self.parse_node(indent);
 
         // In strict mode, check for multiple root-level scalars
-        if self.strict && self.error.is_none() {
+        if self.strict {
             self.skip_blanks();
             if let Some(next_line) = self.peek() {
                 if next_line.indent == indent {
                     if let LineKind::Scalar(_) = &next_line.kind {
                         // Two scalars at root level in strict mode is an error
-                        self.error = Some(crate::error::Error::Syntax {
-                            line: self.idx + 1,
-                            message: "two primitives at root depth in strict mode".to_string(),
-                        });
+                        self.report(self.syntax(
+                            ErrorKind::Syntax,
+                            self.idx + 1,
+                            None,
+                            "two primitives at root depth in strict mode",
+                        ));
                     }
                 }
             }
@@ -944,14 +927,16 @@ impl<'a> Parser<'a> {
                 let values = split_delim_aware(inline, header.delimiter);
                 // Validate array length
                 if values.len() != header.length {
-                    self.error = Some(crate::error::Error::Syntax {
-                        line: line_no,
-                        message: format!(
+                    self.report(self.syntax(
+                        ErrorKind::LengthMismatch,
+                        line_no,
+                        None,
+                        format!(
                             "array length mismatch: header declares {} elements but found {}",
                             header.length,
                             values.len()
                         ),
-                    });
+                    ));
                 }
                 return Value::Array(
                     values
@@ -971,11 +956,13 @@ impl<'a> Parser<'a> {
         let header_line_no = self.idx; // Header was already consumed
 
         // In strict mode, check for delimiter mismatch between bracket and brace
-        if self.strict && header.fields_delimiter_mismatch && self.error.is_none() {
-            self.error = Some(crate::error::Error::Syntax {
-                line: header_line_no,
-                message: "mismatched delimiter between bracket and brace fields".to_string(),
-            });
+        if self.strict && header.fields_delimiter_mismatch {
+            self.report(self.syntax(
+                ErrorKind::DelimiterMismatch,
+                header_line_no,
+                None,
+                "mismatched delimiter between bracket and brace fields",
+            ));
         }
 
         // If there are fields, it's a tabular array
@@ -997,15 +984,17 @@ impl<'a> Parser<'a> {
             if !inline.is_empty() {
                 let values = split_delim_aware(inline, header.delimiter);
                 // Validate array length
-                if values.len() != header.length && self.error.is_none() {
-                    self.error = Some(crate::error::Error::Syntax {
-                        line: header_line_no,
-                        message: format!(
+                if values.len() != header.length {
+                    self.report(self.syntax(
+                        ErrorKind::LengthMismatch,
+                        header_line_no,
+                        None,
+                        format!(
                             "array length mismatch: header declares {} elements but found {}",
                             header.length,
                             values.len()
                         ),
-                    });
+                    ));
                 }
                 return Value::Array(
                     values
@@ -1052,10 +1041,12 @@ impl<'a> Parser<'a> {
                         self.idx = saved_idx;
                         if is_inside {
                             let line_no = self.idx + 1;
-                            self.error = Some(crate::error::Error::Syntax {
-                                line: line_no,
-                                message: "blank line inside table".to_string(),
-                            });
+                            self.report(self.syntax(
+                                ErrorKind::Indentation,
+                                line_no,
+                                None,
+                                "blank line inside table",
+                            ));
                         }
                     }
                 }
@@ -1093,48 +1084,51 @@ impl<'a> Parser<'a> {
             self.next();
 
             // In strict mode, check if row uses a different delimiter than declared
-            if self.strict && self.error.is_none() && check_delimiter_mismatch(row_text, delimiter)
-            {
-                self.error = Some(crate::error::Error::Syntax {
-                    line: row_line_no,
-                    message:
-                        "delimiter mismatch: row uses different delimiter than header declares"
-                            .to_string(),
-                });
+            if self.strict && check_delimiter_mismatch(row_text, delimiter) {
+                self.report(self.syntax(
+                    ErrorKind::DelimiterMismatch,
+                    row_line_no,
+                    Some(row_text),
+                    "delimiter mismatch: row uses different delimiter than header declares",
+                ));
             }
 
             let cells = split_delim_aware(row_text, delimiter);
 
             // Validate cell count matches header field count
-            if cells.len() != expected_cells && self.error.is_none() {
-                self.error = Some(crate::error::Error::Syntax {
-                    line: row_line_no,
-                    message: format!(
+            if cells.len() != expected_cells {
+                self.report(self.syntax(
+                    ErrorKind::LengthMismatch,
+                    row_line_no,
+                    Some(row_text),
+                    format!(
                         "tabular row has {} values but header declares {} fields",
                         cells.len(),
                         expected_cells
                     ),
-                });
+                ));
             }
 
-            let mut om: Vec<(String, Value)> = Vec::with_capacity(header_keys.len());
+            let mut om = Map::with_capacity(header_keys.len());
             for (i, hk) in header_keys.iter().enumerate() {
                 let cell = cells.get(i).copied().unwrap_or("");
-                om.push((hk.clone(), self.parse_scalar_token(cell)));
+                om.push(hk.clone(), self.parse_scalar_token(cell));
             }
             rows.push(Value::Object(om));
         }
 
         // Validate row count matches header length
-        if rows.len() != expected_count && self.error.is_none() {
-            self.error = Some(crate::error::Error::Syntax {
-                line: header_line_no,
-                message: format!(
+        if rows.len() != expected_count {
+            self.report(self.syntax(
+                ErrorKind::LengthMismatch,
+                header_line_no,
+                None,
+                format!(
                     "tabular array has {} rows but header declares {}",
                     rows.len(),
                     expected_count
                 ),
-            });
+            ));
         }
 
         Value::Array(rows)
@@ -1149,23 +1143,129 @@ impl<'a> Parser<'a> {
     ) -> Value {
         let arr = self.parse_array(indent);
         if let Value::Array(ref items) = arr {
-            if items.len() != expected_len && self.error.is_none() {
-                self.error = Some(crate::error::Error::Syntax {
-                    line: header_line_no,
-                    message: format!(
+            if items.len() != expected_len {
+                self.report(self.syntax(
+                    ErrorKind::LengthMismatch,
+                    header_line_no,
+                    None,
+                    format!(
                         "array length mismatch: header declares {} elements but found {}",
                         expected_len,
                         items.len()
                     ),
-                });
+                ));
             }
         }
         arr
     }
 }
 
+/// Parse a primitive token (quoted string, literal, number or bare string).
+pub(crate) fn parse_scalar(s: &str) -> Result<Value, StringParseError> {
+    Ok(match parse_scalar_token(s)? {
+        Scalar::Str(st) => Value::String(st.into_owned()),
+        Scalar::Other(v) => v,
+    })
+}
+
+/// A classified scalar token; strings borrow from the token when no unescaping is needed.
+pub(crate) enum Scalar<'a> {
+    Str(Cow<'a, str>),
+    Other(Value),
+}
+
+pub(crate) fn parse_scalar_token(s: &str) -> Result<Scalar<'_>, StringParseError> {
+    if s.starts_with('"') {
+        return unescape_token(s).map(Scalar::Str);
+    }
+    if has_forbidden_leading_zeros(s) {
+        return Ok(Scalar::Str(Cow::Borrowed(s)));
+    }
+    match s {
+        "true" => return Ok(Scalar::Other(Value::Bool(true))),
+        "false" => return Ok(Scalar::Other(Value::Bool(false))),
+        "null" => return Ok(Scalar::Other(Value::Null)),
+        _ => {}
+    }
+    // Fast path for pure integers (ASCII digits, optional leading '-')
+    let bs = s.as_bytes();
+    if !bs.is_empty() {
+        if bs[0] == b'-' {
+            if bs.len() > 1 && bs[1..].iter().all(|c| c.is_ascii_digit()) {
+                if let Ok(i) = s.parse::<i64>() {
+                    return Ok(Scalar::Other(Value::Number(Number::I64(i))));
+                }
+            }
+        } else if bs.iter().all(|c| c.is_ascii_digit()) {
+            if let Ok(u) = s.parse::<u64>() {
+                return Ok(Scalar::Other(Value::Number(Number::U64(u))));
+            }
+        }
+    }
+    match classify_numeric_hint(s) {
+        Some(NumHint::Float) => {
+            if let Ok(f) = s.parse::<f64>() {
+                // Normalize integer-valued floats to integers, when they fit
+                // Use f % 1.0 instead of f.fract() for no_std compatibility
+                if f.is_finite() && f % 1.0 == 0.0 {
+                    if (0.0..18446744073709551616.0).contains(&f) {
+                        return Ok(Scalar::Other(Value::Number(Number::U64(f as u64))));
+                    } else if (-9223372036854775808.0..0.0).contains(&f) {
+                        return Ok(Scalar::Other(Value::Number(Number::I64(f as i64))));
+                    }
+                }
+                return Ok(Scalar::Other(Value::Number(Number::F64(f))));
+            }
+        }
+        Some(NumHint::IntSigned) => {
+            if let Ok(i) = s.parse::<i64>() {
+                return Ok(Scalar::Other(Value::Number(Number::I64(i))));
+            }
+            if let Ok(f) = s.parse::<f64>() {
+                return Ok(Scalar::Other(Value::Number(Number::F64(f))));
+            }
+        }
+        Some(NumHint::IntUnsigned) => {
+            if let Ok(u) = s.parse::<u64>() {
+                return Ok(Scalar::Other(Value::Number(Number::U64(u))));
+            }
+            if let Ok(f) = s.parse::<f64>() {
+                return Ok(Scalar::Other(Value::Number(Number::F64(f))));
+            }
+        }
+        None => {}
+    }
+    Ok(Scalar::Str(Cow::Borrowed(s)))
+}
+
+/// Parse a key token, unescaping quoted keys.
+///
+/// When `mark_quoted_dots` is set, quoted keys containing a dot are prefixed with
+/// a zero-width space (U+200B) so that path expansion leaves them intact.
+pub(crate) fn parse_key(k: &str, mark_quoted_dots: bool) -> Result<String, StringParseError> {
+    parse_key_token(k, mark_quoted_dots).map(Cow::into_owned)
+}
+
+/// [`parse_key`] that borrows from `k` when the key needs no unescaping or marking.
+pub(crate) fn parse_key_token(
+    k: &str,
+    mark_quoted_dots: bool,
+) -> Result<Cow<'_, str>, StringParseError> {
+    if !k.starts_with('"') {
+        return Ok(Cow::Borrowed(k));
+    }
+    let st = unescape_token(k)?;
+    if mark_quoted_dots && st.contains('.') {
+        let mut marked = String::with_capacity(st.len() + 3);
+        marked.push('\u{200B}');
+        marked.push_str(&st);
+        return Ok(Cow::Owned(marked));
+    }
+    Ok(st)
+}
+
 /// Legacy header parser for old @<delim> format (for backwards compatibility during transition)
-fn parse_header(s: &str) -> Option<(char, &str)> {
+pub(crate) fn parse_header(s: &str) -> Option<(char, &str)> {
     let mut it = s.chars();
     let at = it.next()?;
     if at != '@' {
@@ -1189,7 +1289,7 @@ pub struct ArrayHeader {
 
 /// Parse a spec-compliant array header: key[N<delim?>]{fields}:[ values]
 /// Returns None if not a valid array header
-fn parse_array_header(s: &str) -> Option<ArrayHeader> {
+pub(crate) fn parse_array_header(s: &str) -> Option<ArrayHeader> {
     // Find the bracket that represents the array length, skipping any brackets inside quotes
     let bracket_start = find_unquoted_bracket(s)?;
     let bracket_end = find_matching_bracket(s, bracket_start)?;
@@ -1251,7 +1351,7 @@ fn parse_array_header(s: &str) -> Option<ArrayHeader> {
 }
 
 // Attempt to interpret a scalar line like "key[N]{fields}? values" as an array header with inline values
-fn parse_scalar_keyed_array_header(s: &str) -> Option<ArrayHeader> {
+pub(crate) fn parse_scalar_keyed_array_header(s: &str) -> Option<ArrayHeader> {
     let bracket_start = find_unquoted_bracket(s)?;
     let bracket_end = find_matching_bracket(s, bracket_start)?;
     // Position after potential fields segment
@@ -1271,7 +1371,7 @@ fn parse_scalar_keyed_array_header(s: &str) -> Option<ArrayHeader> {
 
 /// Check if a string uses a different delimiter than declared.
 /// Returns true if there's evidence of using a different delimiter.
-fn check_delimiter_mismatch(s: &str, declared: char) -> bool {
+pub(crate) fn check_delimiter_mismatch(s: &str, declared: char) -> bool {
     // Count unquoted occurrences of each potential delimiter
     let bytes = s.as_bytes();
     let mut in_quote = false;
@@ -1358,7 +1458,7 @@ fn trim_spaces_only(s: &str) -> &str {
 }
 
 /// Find the first '[' that's not inside quotes
-fn find_unquoted_bracket(s: &str) -> Option<usize> {
+pub(crate) fn find_unquoted_bracket(s: &str) -> Option<usize> {
     let bytes = s.as_bytes();
     let mut in_quote = false;
     let mut escape = false;
@@ -1444,7 +1544,7 @@ fn find_matching_brace(s: &str, start: usize) -> Option<usize> {
 }
 
 /// Check if a line looks like an array header (has [N] pattern, not inside quotes)
-fn is_array_header_line(s: &str) -> bool {
+pub(crate) fn is_array_header_line(s: &str) -> bool {
     // Find the first '[' that's not inside quotes
     let Some(bracket_start) = find_unquoted_bracket(s) else {
         return false;
@@ -1474,7 +1574,7 @@ fn is_array_header_line(s: &str) -> bool {
 }
 
 #[cfg(feature = "perf_memchr")]
-fn split_delim_aware<'a>(s: &'a str, dch: char) -> Vec<&'a str> {
+pub(crate) fn split_delim_aware<'a>(s: &'a str, dch: char) -> Vec<&'a str> {
     let bytes = s.as_bytes();
     let delim = dch as u8;
     #[cfg(feature = "perf_smallvec")]
@@ -1547,7 +1647,7 @@ fn split_delim_aware<'a>(s: &'a str, dch: char) -> Vec<&'a str> {
 }
 
 #[cfg(not(feature = "perf_memchr"))]
-fn split_delim_aware<'a>(s: &'a str, dch: char) -> Vec<&'a str> {
+pub(crate) fn split_delim_aware<'a>(s: &'a str, dch: char) -> Vec<&'a str> {
     let bytes = s.as_bytes();
     #[cfg(feature = "perf_smallvec")]
     let mut out: smallvec::SmallVec<[&'a str; 8]> = smallvec::SmallVec::new();
@@ -1630,7 +1730,7 @@ fn trim_ascii_start(s: &str) -> &str {
 }
 
 // Split a "key: value" pair in a single line, respecting quotes around the key and value
-fn split_kv_quote_aware(s: &str) -> Option<(&str, &str)> {
+pub(crate) fn split_kv_quote_aware(s: &str) -> Option<(&str, &str)> {
     let bytes = s.as_bytes();
     let mut i = 0usize;
     let mut in_str = false;
@@ -1668,12 +1768,12 @@ fn split_kv_quote_aware(s: &str) -> Option<(&str, &str)> {
     None
 }
 
-fn is_quoted_token(s: &str) -> bool {
+pub(crate) fn is_quoted_token(s: &str) -> bool {
     let t = trim_ascii(s);
     t.starts_with('"') && t.ends_with('"') && t.len() >= 2
 }
 
-fn token_requires_quotes(s: &str, dch: char) -> bool {
+pub(crate) fn token_requires_quotes(s: &str, dch: char) -> bool {
     use crate::options::Delimiter;
     let delim = match dch {
         ',' => Delimiter::Comma,
@@ -1684,7 +1784,7 @@ fn token_requires_quotes(s: &str, dch: char) -> bool {
     crate::encode::primitives::needs_quotes(s, delim)
 }
 
-fn cell_token_requires_quotes(s: &str, dch: char) -> bool {
+pub(crate) fn cell_token_requires_quotes(s: &str, dch: char) -> bool {
     // Stricter check for row cells that ignores numeric/boolean-like tokens,
     // focusing only on characters that would break parsing if left unquoted.
     if s.is_empty() {
@@ -1732,6 +1832,18 @@ fn is_control_char(c: char) -> bool {
     u < 0x20 || u == 0x7F
 }
 
+/// Span of the content (after indentation) of 1-based line `line_no`, or the
+/// end of input if there is no such line.
+pub(crate) fn line_span(source: &str, line_no: usize) -> Span {
+    match source.split('\n').nth(line_no.wrapping_sub(1)) {
+        Some(line) => {
+            let content = line.trim_start_matches(' ').trim_end_matches('\r');
+            Span::of(source, content).unwrap_or(Span::line(line_no))
+        }
+        None => Span::locate(source, source.len(), 0),
+    }
+}
+
 pub fn parse_to_internal_value(input: &str) -> Value {
     let mut p = Parser::from_input(input);
     p.parse_document()
@@ -1740,23 +1852,21 @@ pub fn parse_to_internal_value(input: &str) -> Value {
 pub fn parse_to_internal_value_from_lines<'a>(
     lines: Vec<ParsedLine<'a>>,
     strict: bool,
-) -> Result<Value, crate::error::Error> {
+) -> Result<Value, Error> {
     let mut p = Parser::from_lines(lines, strict);
     let v = p.parse_document();
-    if let Some(err) = p.error {
-        Err(err)
-    } else {
-        Ok(v)
+    match p.into_errors().into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(v),
     }
 }
 
-pub fn parse_to_value_with_strict(input: &str, strict: bool) -> Result<Value, crate::error::Error> {
+pub fn parse_to_value_with_strict(input: &str, strict: bool) -> Result<Value, Error> {
     let mut p = Parser::from_input_with_strict(input, strict);
     let v = p.parse_document();
-    if let Some(err) = p.error {
-        Err(err)
-    } else {
-        Ok(v)
+    match p.into_errors().into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(v),
     }
 }
 
@@ -1791,11 +1901,35 @@ fn to_json_value(v: Value) -> serde_json::Value {
 }
 
 #[derive(Debug, Clone)]
-enum StringParseError {
+pub(crate) enum StringParseError {
     Unterminated,
     InvalidEscape,
 }
 
+impl StringParseError {
+    pub(crate) fn message(&self) -> &'static str {
+        match self {
+            StringParseError::Unterminated => "unterminated string",
+            StringParseError::InvalidEscape => "invalid escape sequence",
+        }
+    }
+
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            StringParseError::Unterminated => ErrorKind::UnterminatedString,
+            StringParseError::InvalidEscape => ErrorKind::InvalidEscape,
+        }
+    }
+}
+
+/// Unescape a quoted token, borrowing its contents when it has no escapes.
+fn unescape_token(s: &str) -> Result<Cow<'_, str>, StringParseError> {
+    if s.len() >= 2 && s.ends_with('"') && !s.contains('\\') {
+        return Ok(Cow::Borrowed(&s[1..s.len() - 1]));
+    }
+    try_unescape_json_string(s).map(Cow::Owned)
+}
+
 fn try_unescape_json_string(s: &str) -> Result<String, StringParseError> {
     // Check for proper termination: must start with " and end with "
     if !s.starts_with('"') {
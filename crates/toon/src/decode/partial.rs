@@ -0,0 +1,418 @@
+//! Best-effort decoding of a document that is still being written
+//!
+//! [`PartialParser`] takes a document in chunks, such as the tokens of a
+//! streamed model reply, and after each one returns the value of the text so
+//! far. Every line but the last is final, so the text is read by the batch
+//! [`Parser`] in lenient mode, which keeps arrays that are shorter than their
+//! `[N]` headers. The last line, if it has no newline yet, is read as far as it
+//! goes: a string whose closing quote has not arrived is closed, and a table
+//! row leaves out the fields it has not reached. What is still being written is
+//! listed in [`Partial::open`].
+//!
+//! Entries of a root object are final once the next one has begun, so they
+//! are read once and kept; each chunk only has the entry still being written
+//! read again.
+//!
+//! ```
+//! use toon_rs::{Options, toon};
+//! use toon_rs::{Open, OpenKind, PartialParser};
+//!
+//! let mut parser = PartialParser::new(&Options::default());
+//! parser.feed("users[3]{id,name}:\n  1,Ada\n");
+//! let partial = parser.feed("  2,\"Bo");
+//! assert_eq!(
+//!     partial.value,
+//!     toon!({ "users": [{ "id": 1, "name": "Ada" }, { "id": 2, "name": "Bo" }] })
+//! );
+//! assert_eq!(
+//!     partial.open,
+//!     [
+//!         Open { path: "users".into(), kind: OpenKind::Array { declared: 3, received: 2 } },
+//!         Open { path: "users[1].name".into(), kind: OpenKind::String },
+//!     ]
+//! );
+//!
+//! let partial = parser.feed("b\"\n  3,Cy\n");
+//! assert!(partial.open.is_empty());
+//! assert_eq!(parser.finish()?, partial.value);
+//! # Ok::<(), toon_rs::Error>(())
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::decode::parser::{
+    Parser, is_array_header_line, parse_array_header, parse_key, split_delim_aware,
+    split_kv_quote_aware,
+};
+use crate::decode::path_expand::{QUOTED_DOT_MARKER, expand_into, expand_paths, should_expand_key};
+use crate::decode::scanner::{LineKind, scan_line};
+use crate::error::{Result, path_key};
+use crate::options::{ExpandPaths, Options};
+use crate::value::{Map, Value};
+
+/// Decodes a document fed to it in chunks, as far as it has arrived.
+#[derive(Debug, Clone, Default)]
+pub struct PartialParser {
+    options: Options,
+    text: String,
+    /// Start of the root entry still being written; the text before it is
+    /// read into `root`
+    settled: usize,
+    /// The root entries before `settled`, as they are in the value
+    root: Map,
+    /// End of the finished lines already looked at for a new root entry
+    scanned: usize,
+    /// Whether the parser has left the root object, or the root is not one,
+    /// so that nothing more is settled
+    stuck: bool,
+}
+
+/// The value of a document so far, from [`PartialParser::feed`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partial {
+    pub value: Value,
+    /// Arrays and strings that are still being written, outermost first
+    pub open: Vec<Open>,
+}
+
+/// An array or string that is still being written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Open {
+    /// Path of the value, such as `users[1].name`; empty for the root
+    pub path: String,
+    pub kind: OpenKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenKind {
+    /// An array with fewer elements so far than its header declares
+    Array { declared: usize, received: usize },
+    /// The last string on a line whose end has not arrived
+    String,
+}
+
+impl PartialParser {
+    /// A parser for a document to be decoded with `options`. Until
+    /// [`finish`](Self::finish), only `indent` and `expand_paths` are used.
+    pub fn new(options: &Options) -> Self {
+        PartialParser {
+            options: options.clone(),
+            ..PartialParser::default()
+        }
+    }
+
+    /// Add `chunk` to the document and return its value so far.
+    ///
+    /// The root entry still being written is read again on each call, so on a
+    /// long one, such as a large table, feeding a line at a time rather than a
+    /// token at a time keeps the cost down.
+    pub fn feed(&mut self, chunk: &str) -> Partial {
+        self.text.push_str(chunk);
+        self.settle();
+        self.snapshot()
+    }
+
+    /// Read the root entries before the last one begun on a finished line
+    /// into `root`, if the parser reads that line as a root entry.
+    fn settle(&mut self) {
+        let Some(done) = self.text.rfind('\n').map(|end| end + 1) else {
+            return;
+        };
+        if self.stuck || done <= self.scanned {
+            return;
+        }
+        let mut entry = None;
+        let mut at = self.scanned;
+        for line in self.text[self.scanned..done].split_inclusive('\n') {
+            if at > self.settled && starts_entry(line) {
+                entry = Some((at, at + line.len()));
+            }
+            at += line.len();
+        }
+        self.scanned = done;
+        let Some((start, end)) = entry else {
+            return;
+        };
+
+        // Only when the entry adds one to the root entries before it did the
+        // parser read it as a root entry, rather than stop at it or nest it
+        let before =
+            Parser::from_input_with_strict(&self.text[self.settled..start], false).parse_document();
+        let with =
+            Parser::from_input_with_strict(&self.text[self.settled..end], false).parse_document();
+        match (before, with) {
+            (Value::Object(before), Value::Object(with)) if with.len() == before.len() + 1 => {
+                let mut with = Vec::from(with);
+                with.pop();
+                add_entries(&mut self.root, Map::from(with), self.options.expand_paths);
+                self.settled = start;
+            }
+            _ => self.stuck = true,
+        }
+    }
+
+    /// The value of the document so far, without adding to it.
+    pub fn snapshot(&self) -> Partial {
+        let tail = &self.text[self.settled..];
+        let (done, last) = match tail.rfind('\n') {
+            Some(end) => tail.split_at(end + 1),
+            None => ("", tail),
+        };
+        let last = last.trim_end_matches('\r');
+        let unfinished = !last.trim().is_empty();
+        let mut text = String::from(done);
+        if unfinished {
+            text.push_str(&close_string(last));
+        }
+
+        let mut value = match Parser::from_input_with_strict(&text, false).parse_document() {
+            Value::Object(entries) => {
+                let mut root = self.root.clone();
+                add_entries(&mut root, entries, self.options.expand_paths);
+                Value::Object(root)
+            }
+            value => match self.options.expand_paths {
+                ExpandPaths::Safe => {
+                    expand_paths(value.clone(), false).unwrap_or_else(|_| unmark(value))
+                }
+                ExpandPaths::Off => unmark(value),
+            },
+        };
+        let lines: Vec<&str> = text.lines().collect();
+        let mut open = Vec::new();
+        Spine {
+            options: &self.options,
+            unfinished,
+            open: &mut open,
+        }
+        .walk(&mut value, &lines);
+        Partial { value, open }
+    }
+
+    /// The text fed so far.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Decode the whole document with the parser's options, now that all of it
+    /// has been fed. Unlike [`feed`](Self::feed), this fails on a document
+    /// that is cut short, as [`crate::from_str`] does.
+    pub fn finish(self) -> Result<Value> {
+        crate::from_str(&self.text, &self.options)
+    }
+}
+
+/// Whether `line` starts an entry of a root object, and one that the parser
+/// also reads as such at the start of a document.
+fn starts_entry(line: &str) -> bool {
+    let line = scan_line(line.trim_end_matches('\n'));
+    line.indent == 0
+        && match line.kind {
+            LineKind::KeyValue { key, .. } | LineKind::KeyOnly { key } => {
+                !key.starts_with('[') && key != "{0}"
+            }
+            _ => false,
+        }
+}
+
+/// `line` with its last string closed, if it ends inside quotes.
+fn close_string(line: &str) -> String {
+    let (mut quoted, mut escaped) = (false, false);
+    for c in line.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            _ => {}
+        }
+    }
+    match (quoted, escaped) {
+        (false, _) => line.to_string(),
+        // A backslash whose escape has not arrived is left out
+        (true, true) => format!("{}\"", &line[..line.len() - 1]),
+        (true, false) => format!("{}\"", line),
+    }
+}
+
+/// Add the object entries the parser wrote to `root`, an object built from
+/// earlier entries, as [`PartialParser::snapshot`] builds a root object.
+fn add_entries(root: &mut Map, entries: Map, expand: ExpandPaths) {
+    match expand {
+        ExpandPaths::Safe => {
+            let mut merged = Vec::from(core::mem::take(root));
+            // Later values win on a conflict, so this does not fail
+            let _ = expand_into(&mut merged, Vec::from(entries), false);
+            *root = Map::from(merged);
+        }
+        ExpandPaths::Off => unmark_into(root, entries),
+    }
+}
+
+/// Add `entries` to `map` without the marker the parser puts on quoted keys
+/// with dots.
+fn unmark_into(map: &mut Map, entries: Map) {
+    map.extend(entries.into_iter().map(|(key, value)| {
+        let key = match key.strip_prefix(QUOTED_DOT_MARKER) {
+            Some(key) => key.to_string(),
+            None => key,
+        };
+        (key, unmark(value))
+    }));
+}
+
+/// `value` without the marker the parser puts on quoted keys with dots.
+fn unmark(value: Value) -> Value {
+    match value {
+        Value::Object(entries) => {
+            let mut map = Map::new();
+            unmark_into(&mut map, entries);
+            Value::Object(map)
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(unmark).collect()),
+        value => value,
+    }
+}
+
+/// Follows the lines that the last line is nested in, from the root down,
+/// along the values they wrote.
+struct Spine<'a> {
+    options: &'a Options,
+    /// Whether the last line may still grow
+    unfinished: bool,
+    open: &'a mut Vec<Open>,
+}
+
+impl Spine<'_> {
+    fn walk(&mut self, value: &mut Value, lines: &[&str]) -> Option<()> {
+        let indent = |line: &str| line.len() - line.trim_start_matches(' ').len();
+        let last = lines.iter().rposition(|l| !l.trim().is_empty())?;
+        let mut spine = Vec::from([last]);
+        let mut depth = indent(lines[last]);
+        for at in (0..last).rev() {
+            if depth == 0 {
+                break;
+            }
+            if !lines[at].trim().is_empty() && indent(lines[at]) < depth {
+                spine.push(at);
+                depth = indent(lines[at]);
+            }
+        }
+        spine.reverse();
+
+        let mut cursor = value;
+        let mut path = String::new();
+        // Fields and delimiter of the table the next line is a row of
+        let mut table: Option<(Vec<String>, char)> = None;
+        for (i, &at) in spine.iter().enumerate() {
+            let is_last = i + 1 == spine.len();
+            let mut body = lines[at].trim();
+            if let Some((fields, delimiter)) = table.take() {
+                cursor = last_item(cursor, &mut path)?;
+                if is_last && self.unfinished {
+                    // Fields the row has not reached yet are left out
+                    let cells = split_delim_aware(body, delimiter).len();
+                    if let Some(row) = cursor.as_object_mut() {
+                        for field in fields.iter().skip(cells) {
+                            row.remove(field);
+                        }
+                    }
+                    let field = fields.get(cells.min(fields.len()).checked_sub(1)?)?;
+                    cursor = self.descend(cursor, field, &mut path)?;
+                    self.string(cursor, &path);
+                }
+                return Some(());
+            }
+            if body == "-" || body.starts_with("- ") {
+                cursor = last_item(cursor, &mut path)?;
+                body = body[1..].trim_start();
+                // Later lines are fields of the item, unless they are nested
+                // under its first field
+                let next = spine.get(i + 1).map(|&n| indent(lines[n]));
+                if body.is_empty()
+                    || next.is_some_and(|n| n <= indent(lines[at]) + self.options.indent)
+                {
+                    continue;
+                }
+            }
+            if is_array_header_line(body)
+                && let Some(head) = parse_array_header(body)
+            {
+                if let Some(key) = &head.key {
+                    cursor = self.descend(cursor, &parse_key(key, true).ok()?, &mut path)?;
+                }
+                let received = cursor.as_array()?.len();
+                if received < head.length {
+                    self.open.push(Open {
+                        path: path.clone(),
+                        kind: OpenKind::Array {
+                            declared: head.length,
+                            received,
+                        },
+                    });
+                }
+                if let Some(fields) = head.fields {
+                    let fields = fields
+                        .iter()
+                        .filter_map(|f| parse_key(f, true).ok())
+                        .collect();
+                    table = Some((fields, head.delimiter));
+                } else if is_last && received > 0 && head.inline_values.is_some() {
+                    cursor = last_item(cursor, &mut path)?;
+                    self.string(cursor, &path);
+                }
+                continue;
+            }
+            if let Some((key, _)) = split_kv_quote_aware(body) {
+                cursor = self.descend(cursor, &parse_key(key, true).ok()?, &mut path)?;
+            }
+            if is_last {
+                self.string(cursor, &path);
+            }
+        }
+        Some(())
+    }
+
+    /// The value under `key`, which is as the parser wrote it.
+    fn descend<'v>(
+        &self,
+        mut cursor: &'v mut Value,
+        key: &str,
+        path: &mut String,
+    ) -> Option<&'v mut Value> {
+        let segments: Vec<&str> = match self.options.expand_paths {
+            ExpandPaths::Safe if should_expand_key(key) => key.split('.').collect(),
+            _ => Vec::from([key.trim_start_matches(QUOTED_DOT_MARKER)]),
+        };
+        for segment in segments {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(&path_key(segment));
+            cursor = cursor.as_object_mut()?.get_mut(segment)?;
+        }
+        Some(cursor)
+    }
+
+    /// Mark `value` at `path` open if it is a string on the unfinished line.
+    fn string(&mut self, value: &Value, path: &str) {
+        if self.unfinished && value.is_string() {
+            self.open.push(Open {
+                path: path.to_string(),
+                kind: OpenKind::String,
+            });
+        }
+    }
+}
+
+/// The last element of the array `cursor`.
+fn last_item<'v>(cursor: &'v mut Value, path: &mut String) -> Option<&'v mut Value> {
+    let items = cursor.as_array_mut()?;
+    let at = items.len().checked_sub(1)?;
+    path.push_str(&format!("[{}]", at));
+    items.get_mut(at)
+}
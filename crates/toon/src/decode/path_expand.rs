@@ -19,7 +19,12 @@ use alloc::{
     vec::Vec,
 };
 
+#[cfg(feature = "serde")]
+use crate::error::Span;
+use crate::error::{Error, ErrorKind, Result};
 use crate::value::Value;
+#[cfg(feature = "serde")]
+use crate::value::spanned::{Node, SpannedValue};
 
 /// Check if a string is a valid identifier segment for path expansion.
 /// Valid identifiers contain only ASCII letters, digits, and underscores,
@@ -42,10 +47,10 @@ fn is_valid_identifier(s: &str) -> bool {
 
 /// Marker character used to indicate a key came from a quoted string with dots.
 /// This is a zero-width space (U+200B) which is stripped before output.
-const QUOTED_DOT_MARKER: char = '\u{200B}';
+pub(crate) const QUOTED_DOT_MARKER: char = '\u{200B}';
 
 /// Check if a key should be expanded (unquoted and all segments are valid identifiers).
-fn should_expand_key(key: &str) -> bool {
+pub(crate) fn should_expand_key(key: &str) -> bool {
     // Keys marked with the quoted-dot marker should not be expanded
     if key.starts_with(QUOTED_DOT_MARKER) {
         return false;
@@ -63,112 +68,198 @@ fn split_key(key: &str) -> Vec<&str> {
     key.split('.').collect()
 }
 
-/// Deep merge `source` into `target`, applying path expansion rules.
-/// Returns an error message if there's a conflict in strict mode.
-#[allow(clippy::ptr_arg)]
-fn deep_merge(
-    target: &mut Vec<(String, Value)>,
+/// How path expansion sees a node.
+pub(crate) enum Shape<T> {
+    Object(Vec<(String, T)>),
+    Array(Vec<T>),
+    Leaf(T),
+}
+
+/// A document tree path expansion can take apart and rebuild: [`Value`], or
+/// the span-carrying tree used when deserializing with expansion.
+pub(crate) trait Tree: Sized + Default {
+    /// What a node records besides its shape; copied onto objects created
+    /// for dotted keys.
+    type Meta: Clone;
+
+    fn into_parts(self) -> (Shape<Self>, Self::Meta);
+
+    fn from_parts(shape: Shape<Self>, meta: Self::Meta) -> Self;
+
+    fn meta(&self) -> Self::Meta;
+
+    /// Attach what `meta` knows about a node to an error about it.
+    fn locate(_meta: &Self::Meta, e: Error) -> Error {
+        e
+    }
+}
+
+impl Tree for Value {
+    type Meta = ();
+
+    fn into_parts(self) -> (Shape<Self>, ()) {
+        match self {
+            Value::Object(map) => (Shape::Object(map.into()), ()),
+            Value::Array(items) => (Shape::Array(items), ()),
+            other => (Shape::Leaf(other), ()),
+        }
+    }
+
+    fn from_parts(shape: Shape<Self>, _meta: ()) -> Self {
+        match shape {
+            Shape::Object(entries) => Value::Object(entries.into()),
+            Shape::Array(items) => Value::Array(items),
+            Shape::Leaf(v) => v,
+        }
+    }
+
+    fn meta(&self) {}
+}
+
+#[cfg(feature = "serde")]
+impl Tree for SpannedValue {
+    type Meta = Span;
+
+    fn into_parts(self) -> (Shape<Self>, Span) {
+        let span = self.span;
+        match self.node {
+            Node::Object(entries) => (Shape::Object(entries), span),
+            Node::Array(items) => (Shape::Array(items), span),
+            node => (Shape::Leaf(SpannedValue { node, span }), span),
+        }
+    }
+
+    fn from_parts(shape: Shape<Self>, span: Span) -> Self {
+        let node = match shape {
+            Shape::Object(entries) => Node::Object(entries),
+            Shape::Array(items) => Node::Array(items),
+            Shape::Leaf(v) => return v,
+        };
+        SpannedValue { node, span }
+    }
+
+    fn meta(&self) -> Span {
+        self.span
+    }
+
+    fn locate(span: &Span, e: Error) -> Error {
+        e.at(*span)
+    }
+}
+
+/// Deep merge `value` into `target` under `key`, applying path expansion rules.
+/// Returns a [`ErrorKind::PathExpansionConflict`] error if there's a conflict in strict mode.
+fn deep_merge<T: Tree>(
+    target: &mut Vec<(String, T)>,
     key: String,
-    value: Value,
+    value: T,
     strict: bool,
-) -> Result<(), String> {
+) -> Result<()> {
     // Find existing entry with the same key
-    if let Some(idx) = target.iter().position(|(k, _)| k == &key) {
-        let existing = &mut target[idx].1;
-
-        // Check for conflicts
-        match (&existing, &value) {
-            (Value::Object(existing_obj), Value::Object(new_obj)) => {
-                // Both are objects - deep merge
-                let mut merged = existing_obj.clone();
-                for (k, v) in new_obj.clone() {
-                    deep_merge(&mut merged, k, v, strict)?;
-                }
-                *existing = Value::Object(merged);
+    let Some(idx) = target.iter().position(|(k, _)| k == &key) else {
+        // No existing entry - just add
+        target.push((key, value));
+        return Ok(());
+    };
+    let existing = core::mem::take(&mut target[idx].1);
+    target[idx].1 = match (existing.into_parts(), value.into_parts()) {
+        ((Shape::Object(mut merged), meta), (Shape::Object(entries), _)) => {
+            // Both are objects - deep merge
+            for (k, v) in entries {
+                deep_merge(&mut merged, k, v, strict)?;
             }
-            _ => {
-                // Conflict: different types or primitive/array
-                if strict {
-                    return Err(format!(
-                        "path expansion conflict: key '{}' has conflicting types",
-                        key
-                    ));
-                }
-                // LWW: later value overwrites
-                *existing = value;
+            T::from_parts(Shape::Object(merged), meta)
+        }
+        (_, (shape, meta)) => {
+            // Conflict: different types or primitive/array
+            if strict {
+                return Err(T::locate(
+                    &meta,
+                    Error::data(
+                        ErrorKind::PathExpansionConflict,
+                        format!(
+                            "path expansion conflict: key '{}' has conflicting types",
+                            key
+                        ),
+                    ),
+                ));
             }
+            // LWW: later value overwrites
+            T::from_parts(shape, meta)
         }
-    } else {
-        // No existing entry - just add
-        target.push((key, value));
-    }
+    };
     Ok(())
 }
 
 /// Apply path expansion to a Value, returning the expanded value.
 /// If `strict` is true, conflicts will cause an error.
-pub fn expand_paths(value: Value, strict: bool) -> Result<Value, String> {
-    match value {
-        Value::Object(entries) => {
-            let mut result: Vec<(String, Value)> = Vec::new();
-
-            for (key, val) in entries {
-                // Recursively expand nested values first
-                let expanded_val = expand_paths(val, strict)?;
-
-                if should_expand_key(&key) {
-                    // Expand the dotted key
-                    let segments = split_key(&key);
-                    let nested = build_nested_from_segments(&segments, expanded_val);
-
-                    // Merge into result
-                    if let Value::Object(nested_entries) = nested {
-                        for (k, v) in nested_entries {
-                            deep_merge(&mut result, k, v, strict)?;
-                        }
-                    }
-                } else {
-                    // Strip the marker if present and keep the key as-is
-                    let clean_key = if key.starts_with(QUOTED_DOT_MARKER) {
-                        key[QUOTED_DOT_MARKER.len_utf8()..].to_string()
-                    } else {
-                        key
-                    };
-                    deep_merge(&mut result, clean_key, expanded_val, strict)?;
-                }
-            }
+pub fn expand_paths(value: Value, strict: bool) -> Result<Value> {
+    expand(value, strict)
+}
 
-            Ok(Value::Object(result))
+/// [`expand_paths`] over any [`Tree`].
+pub(crate) fn expand<T: Tree>(tree: T, strict: bool) -> Result<T> {
+    match tree.into_parts() {
+        (Shape::Object(entries), meta) => {
+            let mut result: Vec<(String, T)> = Vec::new();
+            expand_into(&mut result, entries, strict)?;
+            Ok(T::from_parts(Shape::Object(result), meta))
         }
-        Value::Array(arr) => {
+        (Shape::Array(arr), meta) => {
             let mut result = Vec::with_capacity(arr.len());
             for item in arr {
-                result.push(expand_paths(item, strict)?);
+                result.push(expand(item, strict)?);
             }
-            Ok(Value::Array(result))
+            Ok(T::from_parts(Shape::Array(result), meta))
         }
         // Primitives pass through unchanged
-        other => Ok(other),
+        (Shape::Leaf(other), _) => Ok(other),
     }
 }
 
+/// Expand `entries` and merge them, in order, into `result`: the entries of
+/// an object expanded so far.
+pub(crate) fn expand_into<T: Tree>(
+    result: &mut Vec<(String, T)>,
+    entries: Vec<(String, T)>,
+    strict: bool,
+) -> Result<()> {
+    for (key, val) in entries {
+        // Recursively expand nested values first
+        let expanded_val = expand(val, strict)?;
+
+        if should_expand_key(&key) {
+            // Expand the dotted key and merge its first segment
+            let segments = split_key(&key);
+            let nested = build_nested_from_segments(&segments[1..], expanded_val);
+            deep_merge(result, segments[0].to_string(), nested, strict)?;
+        } else {
+            // Strip the marker if present and keep the key as-is
+            let clean_key = if key.starts_with(QUOTED_DOT_MARKER) {
+                key[QUOTED_DOT_MARKER.len_utf8()..].to_string()
+            } else {
+                key
+            };
+            deep_merge(result, clean_key, expanded_val, strict)?;
+        }
+    }
+    Ok(())
+}
+
 /// Build a nested object structure from key segments.
-fn build_nested_from_segments(segments: &[&str], value: Value) -> Value {
-    if segments.is_empty() {
+fn build_nested_from_segments<T: Tree>(segments: &[&str], value: T) -> T {
+    let Some((first, rest)) = segments.split_first() else {
         return value;
-    }
-    if segments.len() == 1 {
-        return Value::Object(vec![(segments[0].to_string(), value)]);
-    }
-    // Build from the last segment to the first
-    let inner = build_nested_from_segments(&segments[1..], value);
-    Value::Object(vec![(segments[0].to_string(), inner)])
+    };
+    let meta = value.meta();
+    let inner = build_nested_from_segments(rest, value);
+    T::from_parts(Shape::Object(vec![(first.to_string(), inner)]), meta)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::value::Number;
+    use crate::value::{Map, Number};
 
     #[test]
     fn test_is_valid_identifier() {
@@ -197,17 +288,23 @@ mod tests {
 
     #[test]
     fn test_expand_simple() {
-        let input = Value::Object(vec![("a.b.c".to_string(), Value::Number(Number::I64(1)))]);
+        let input = Value::Object(Map::from(vec![(
+            "a.b.c".to_string(),
+            Value::Number(Number::I64(1)),
+        )]));
 
         let result = expand_paths(input, false).unwrap();
 
-        let expected = Value::Object(vec![(
+        let expected = Value::Object(Map::from(vec![(
             "a".to_string(),
-            Value::Object(vec![(
+            Value::Object(Map::from(vec![(
                 "b".to_string(),
-                Value::Object(vec![("c".to_string(), Value::Number(Number::I64(1)))]),
-            )]),
-        )]);
+                Value::Object(Map::from(vec![(
+                    "c".to_string(),
+                    Value::Number(Number::I64(1)),
+                )])),
+            )])),
+        )]));
 
         assert_eq!(result, expected);
     }
@@ -0,0 +1,224 @@
+//! Incremental line source over `std::io::BufRead` or an in-memory `&str`
+//!
+//! Lines are read one at a time; at most one look-ahead line is buffered. Lines
+//! taken from a `&str` borrow from the input, so tokens can be handed to serde
+//! without copying.
+
+#[cfg(not(feature = "std"))]
+use alloc::borrow::Cow;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use crate::decode::scanner::{LineKind, ParsedLine, scan_line};
+use crate::decode::validation::validate_line_indentation;
+use crate::error::{Result, Span};
+use crate::options::Options;
+
+/// Raw input lines, without their trailing newline.
+pub trait LineSource<'de> {
+    fn next_line(&mut self) -> Result<Option<Cow<'de, str>>>;
+}
+
+/// Lines borrowed from an in-memory document.
+pub struct StrLines<'de> {
+    rest: &'de str,
+}
+
+impl<'de> StrLines<'de> {
+    pub fn new(input: &'de str) -> Self {
+        Self { rest: input }
+    }
+}
+
+impl<'de> LineSource<'de> for StrLines<'de> {
+    fn next_line(&mut self) -> Result<Option<Cow<'de, str>>> {
+        if self.rest.is_empty() {
+            return Ok(None);
+        }
+        let (line, rest) = match self.rest.find('\n') {
+            Some(i) => (&self.rest[..i], &self.rest[i + 1..]),
+            None => (self.rest, ""),
+        };
+        self.rest = rest;
+        Ok(Some(Cow::Borrowed(line)))
+    }
+}
+
+/// Owned lines read from a `BufRead`.
+#[cfg(feature = "std")]
+pub struct IoLines<R> {
+    reader: R,
+    buf: String,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::BufRead> IoLines<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: String::new(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de, R: std::io::BufRead> LineSource<'de> for IoLines<R> {
+    fn next_line(&mut self) -> Result<Option<Cow<'de, str>>> {
+        self.buf.clear();
+        if self.reader.read_line(&mut self.buf)? == 0 {
+            return Ok(None);
+        }
+        let text = self.buf.strip_suffix('\n').unwrap_or(&self.buf);
+        Ok(Some(Cow::Owned(text.into())))
+    }
+}
+
+/// A single non-blank input line together with its 1-based line number and
+/// the byte offset at which it starts.
+#[derive(Debug, Clone)]
+pub struct Line<'de> {
+    pub number: usize,
+    pub offset: usize,
+    pub text: Cow<'de, str>,
+}
+
+impl<'de> Line<'de> {
+    pub fn parsed(&self) -> ParsedLine<'_> {
+        scan_line(&self.text)
+    }
+
+    /// Span of `part`, a slice of this line's text.
+    pub fn span(&self, part: &str) -> Span {
+        Span {
+            offset: self.offset,
+            line: self.number,
+            column: 1,
+            len: self.text.len(),
+        }
+        .within(&self.text, part)
+    }
+
+    /// Span of the line after its indentation.
+    pub fn content_span(&self) -> Span {
+        self.span(self.text.trim_start_matches(' ').trim_end_matches('\r'))
+    }
+
+    /// `part` (a slice of this line's text) with the lifetime of the input.
+    pub fn slice(&self, part: &str) -> Cow<'de, str> {
+        slice_of(&self.text, part)
+    }
+}
+
+/// Re-borrow `part`, a slice of `text`, for as long as `text` borrows the input.
+pub fn slice_of<'de>(text: &Cow<'de, str>, part: &str) -> Cow<'de, str> {
+    match text {
+        Cow::Borrowed(base) => {
+            let start = part.as_ptr() as usize - base.as_ptr() as usize;
+            debug_assert!(start + part.len() <= base.len());
+            Cow::Borrowed(&base[start..start + part.len()])
+        }
+        Cow::Owned(_) => Cow::Owned(part.into()),
+    }
+}
+
+/// Pulls non-blank lines from a [`LineSource`], validating indentation in strict mode.
+pub struct LineReader<'de, S> {
+    source: S,
+    line_no: usize,
+    offset: usize,
+    peeked: Option<Line<'de>>,
+    /// Content of the most recently consumed line
+    taken: Option<Span>,
+    blank: Option<Span>,
+    eof: bool,
+    strict: bool,
+    indent_size: usize,
+}
+
+impl<'de, S: LineSource<'de>> LineReader<'de, S> {
+    pub fn new(source: S, options: &Options) -> Self {
+        Self {
+            source,
+            line_no: 0,
+            offset: 0,
+            peeked: None,
+            taken: None,
+            blank: None,
+            eof: false,
+            strict: options.strict,
+            indent_size: options.indent,
+        }
+    }
+
+    /// Make sure the next non-blank line (if any) is buffered.
+    pub fn fill(&mut self) -> Result<()> {
+        if self.peeked.is_some() || self.eof {
+            return Ok(());
+        }
+        self.blank = None;
+        loop {
+            let Some(text) = self.source.next_line()? else {
+                self.eof = true;
+                return Ok(());
+            };
+            self.line_no += 1;
+            let offset = self.offset;
+            self.offset += text.len() + 1;
+            let pl = scan_line(&text);
+            if matches!(pl.kind, LineKind::Blank) {
+                self.blank.get_or_insert(Span {
+                    offset,
+                    line: self.line_no,
+                    column: 1,
+                    len: 0,
+                });
+                continue;
+            }
+            if self.strict {
+                validate_line_indentation(&text, pl.indent, self.indent_size, self.line_no)
+                    .map_err(|e| e.into_error(offset))?;
+            }
+            self.peeked = Some(Line {
+                number: self.line_no,
+                offset,
+                text,
+            });
+            return Ok(());
+        }
+    }
+
+    /// The buffered look-ahead line; call [`LineReader::fill`] first.
+    pub fn peeked(&self) -> Option<&Line<'de>> {
+        self.peeked.as_ref()
+    }
+
+    /// Fill and return the indent of the next non-blank line.
+    pub fn peek_indent(&mut self) -> Result<Option<usize>> {
+        self.fill()?;
+        Ok(self.peeked.as_ref().map(|l| l.parsed().indent))
+    }
+
+    /// Consume the buffered look-ahead line.
+    pub fn take(&mut self) -> Option<Line<'de>> {
+        let line = self.peeked.take();
+        if let Some(l) = &line {
+            self.taken = Some(l.content_span());
+        }
+        line
+    }
+
+    /// Content span of the most recently consumed line.
+    pub fn last_taken(&self) -> Option<Span> {
+        self.taken
+    }
+
+    /// The first blank line skipped before the look-ahead line (or EOF).
+    pub fn blank_before(&self) -> Option<Span> {
+        self.blank
+    }
+
+    /// Number of the most recently read line.
+    pub fn line_number(&self) -> usize {
+        self.line_no
+    }
+}
@@ -114,66 +114,78 @@ fn find_unquoted_colon(s: &str) -> Option<usize> {
 }
 
 pub fn scan<'a>(input: &'a str) -> Vec<ParsedLine<'a>> {
-    let mut out = Vec::new();
-    for raw in input.split_inclusive('\n') {
-        let line = raw.trim_end_matches('\n');
-        let indent = leading_spaces(line);
-        let body = &line[indent..];
-        if body.is_empty() {
-            out.push(ParsedLine {
-                indent,
-                kind: LineKind::Blank,
-            });
-            continue;
-        }
-        if let Some(rest) = body.strip_prefix("- ") {
-            out.push(ParsedLine {
-                indent,
-                kind: LineKind::ListItem { value: Some(rest) },
-            });
-            continue;
-        }
-        if body == "-" {
-            out.push(ParsedLine {
-                indent,
-                kind: LineKind::ListItem { value: None },
-            });
-            continue;
-        }
-        if body.starts_with('@') {
-            // Table header line; treat entire line as scalar
-            out.push(ParsedLine {
+    input
+        .split_inclusive('\n')
+        .map(|raw| scan_line(raw.trim_end_matches('\n')))
+        .collect()
+}
+
+/// The indentation per level of `input`: the largest step every indented
+/// line is a multiple of, if any line is indented.
+#[cfg(feature = "serde")]
+pub(crate) fn indent_step(input: &str) -> Option<usize> {
+    fn gcd(a: usize, b: usize) -> usize {
+        if b == 0 { a } else { gcd(b, a % b) }
+    }
+    let step = input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(leading_spaces)
+        .fold(0, gcd);
+    (step > 0).then_some(step)
+}
+
+/// Classify a single line (without its trailing newline).
+pub fn scan_line(line: &str) -> ParsedLine<'_> {
+    let indent = leading_spaces(line);
+    let body = &line[indent..];
+    if body.is_empty() {
+        return ParsedLine {
+            indent,
+            kind: LineKind::Blank,
+        };
+    }
+    if let Some(rest) = body.strip_prefix("- ") {
+        return ParsedLine {
+            indent,
+            kind: LineKind::ListItem { value: Some(rest) },
+        };
+    }
+    if body == "-" {
+        return ParsedLine {
+            indent,
+            kind: LineKind::ListItem { value: None },
+        };
+    }
+    if body.starts_with('@') {
+        // Table header line; treat entire line as scalar
+        return ParsedLine {
+            indent,
+            kind: LineKind::Scalar(body),
+        };
+    }
+    if let Some(idx) = find_unquoted_colon(body) {
+        let (k, v) = body.split_at(idx);
+        let after = &v[1..];
+        let after_trimmed = trim_ascii(after);
+        if after_trimmed.is_empty() {
+            return ParsedLine {
                 indent,
-                kind: LineKind::Scalar(body),
-            });
-            continue;
+                kind: LineKind::KeyOnly { key: k },
+            };
         }
-        if let Some(idx) = find_unquoted_colon(body) {
-            let (k, v) = body.split_at(idx);
-            let after = &v[1..];
-            let after_trimmed = trim_ascii(after);
-            if after_trimmed.is_empty() {
-                out.push(ParsedLine {
-                    indent,
-                    kind: LineKind::KeyOnly { key: k },
-                });
-            } else {
-                out.push(ParsedLine {
-                    indent,
-                    kind: LineKind::KeyValue {
-                        key: k,
-                        value: trim_ascii_start(after),
-                    },
-                });
-            }
-            continue;
-        }
-        out.push(ParsedLine {
+        return ParsedLine {
             indent,
-            kind: LineKind::Scalar(body),
-        });
+            kind: LineKind::KeyValue {
+                key: k,
+                value: trim_ascii_start(after),
+            },
+        };
+    }
+    ParsedLine {
+        indent,
+        kind: LineKind::Scalar(body),
     }
-    out
 }
 
 fn trim_ascii(s: &str) -> &str {
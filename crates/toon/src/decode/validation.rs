@@ -3,14 +3,37 @@
 use alloc::{
     format,
     string::{String, ToString},
+    vec::Vec,
 };
 
+use crate::error::{Error, ErrorKind, Span};
+
 #[derive(Debug)]
 pub struct ValidationError {
     pub line: usize,
+    /// 1-based column of the offending indentation
+    pub column: usize,
+    /// Length in bytes of the offending indentation
+    pub len: usize,
     pub message: String,
 }
 
+impl ValidationError {
+    /// Convert to a syntax error, given the byte offset at which the line starts.
+    pub fn into_error(self, line_offset: usize) -> Error {
+        Error::syntax(
+            ErrorKind::Indentation,
+            Span {
+                offset: line_offset + self.column - 1,
+                line: self.line,
+                column: self.column,
+                len: self.len,
+            },
+            self.message,
+        )
+    }
+}
+
 /// Validate indentation with configurable indent size (typically 2 or 4)
 pub fn validate_indentation_with_size<'a>(
     lines: &[crate::decode::scanner::ParsedLine<'a>],
@@ -24,34 +47,67 @@ pub fn validate_indentation_with_size<'a>(
         if matches!(pl.kind, LineKind::Blank) {
             continue;
         }
+        let raw = raw_lines.get(idx).copied().unwrap_or("");
+        validate_line_indentation(raw, pl.indent, indent_size, idx + 1)?;
+    }
+    Ok(())
+}
 
-        // Check for tabs in indentation (strict mode forbids tabs)
-        if idx < raw_lines.len() {
-            let raw = raw_lines[idx];
-            for c in raw.chars() {
-                if c != ' ' && c != '\t' {
-                    break;
-                }
-                if c == '\t' {
-                    return Err(ValidationError {
-                        line: idx + 1,
-                        message: "tab character used in indentation".to_string(),
-                    });
-                }
-            }
-        }
+/// Every indentation problem in the document, at most one per line.
+pub fn indentation_errors<'a>(
+    lines: &[crate::decode::scanner::ParsedLine<'a>],
+    raw_lines: &[&str],
+    indent_size: usize,
+) -> Vec<ValidationError> {
+    use crate::decode::scanner::LineKind;
 
-        // §14.3: Leading spaces must be an exact multiple of indentSize
-        if pl.indent % indent_size != 0 {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, pl)| !matches!(pl.kind, LineKind::Blank))
+        .filter_map(|(idx, pl)| {
+            let raw = raw_lines.get(idx).copied().unwrap_or("");
+            validate_line_indentation(raw, pl.indent, indent_size, idx + 1).err()
+        })
+        .collect()
+}
+
+/// Validate the indentation of a single non-blank line (1-based `line`).
+pub fn validate_line_indentation(
+    raw: &str,
+    indent: usize,
+    indent_size: usize,
+    line: usize,
+) -> Result<(), ValidationError> {
+    let indent_size = if indent_size == 0 { 2 } else { indent_size };
+
+    // Check for tabs in indentation (strict mode forbids tabs)
+    for (i, c) in raw.char_indices() {
+        if c != ' ' && c != '\t' {
+            break;
+        }
+        if c == '\t' {
             return Err(ValidationError {
-                line: idx + 1,
-                message: format!(
-                    "indentation ({}) must be a multiple of {}",
-                    pl.indent, indent_size
-                ),
+                line,
+                column: i + 1,
+                len: 1,
+                message: "tab character used in indentation".to_string(),
             });
         }
     }
+
+    // §14.3: Leading spaces must be an exact multiple of indentSize
+    if indent % indent_size != 0 {
+        return Err(ValidationError {
+            line,
+            column: 1,
+            len: indent,
+            message: format!(
+                "indentation ({}) must be a multiple of {}",
+                indent, indent_size
+            ),
+        });
+    }
     Ok(())
 }
 
@@ -69,6 +125,8 @@ pub fn validate_indentation<'a>(
         if pl.indent % 2 != 0 {
             return Err(ValidationError {
                 line: idx + 1,
+                column: 1,
+                len: pl.indent,
                 message: format!("indentation ({}) must be a multiple of 2", pl.indent),
             });
         }
@@ -0,0 +1,684 @@
+//! Format-preserving edits of TOON text
+//!
+//! A [`Document`] keeps its input line by line and reads the structure with
+//! the decoder's own scanner and array header parser. Printing a document that
+//! has not been edited gives back the input byte for byte. An edit rewrites
+//! only the token or lines it changes, so quoting, the delimiter of each
+//! array, blank lines and indentation elsewhere are kept, and inserting or
+//! removing array elements updates the array's `[N]` length marker.
+//!
+//! ```
+//! use toon_rs::edit::Document;
+//!
+//! let mut doc: Document = "name: api  \ntags[2|]: a|b\n".parse()?;
+//! doc.set("name", "web")?;
+//! doc.push("tags", "c")?;
+//! assert_eq!(doc.to_string(), "name: web  \ntags[3|]: a|b|c\n");
+//! # Ok::<(), toon_rs::Error>(())
+//! ```
+//!
+//! Paths are written the way [`Error::path`] prints them: keys joined with
+//! `.`, indices in brackets, and keys that are not plain words in quotes, as
+//! in `users[1].email` or `"a key"[0]`. The empty path is the root.
+//!
+//! The legacy `@<delim>` table form is not supported.
+
+mod tree;
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt;
+use core::ops::Range;
+
+use crate::decode::scanner::indent_step;
+use crate::encode::primitives;
+use crate::error::{Error, ErrorKind};
+use crate::options::{Delimiter, Options};
+use crate::value::{Map, Value};
+use tree::{Array, Body, Node, Object};
+
+/// A TOON document that can be edited in place.
+///
+/// See the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct Document {
+    lines: Vec<Line>,
+    /// Line ending for new lines: the first one in the input
+    newline: &'static str,
+    /// Indentation per level, as found in the input
+    step: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    text: String,
+    eol: &'static str,
+}
+
+pub(crate) enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Where a path leads: a node, or a token or row that belongs to an array.
+#[derive(Clone, Copy)]
+enum Slot<'n> {
+    Root(&'n Node),
+    Field(&'n Object, usize),
+    Item(&'n Array, usize),
+    Cell(&'n Array, usize),
+    Row(&'n Array, usize),
+    RowCell(&'n Array, usize, usize),
+}
+
+impl Document {
+    /// Read the structure of `input`; fails if it is not TOON this module can
+    /// edit.
+    pub fn parse(input: &str) -> crate::Result<Self> {
+        let lines: Vec<Line> = input
+            .split_inclusive('\n')
+            .map(|raw| {
+                let (text, eol) = if let Some(text) = raw.strip_suffix("\r\n") {
+                    (text, "\r\n")
+                } else if let Some(text) = raw.strip_suffix('\n') {
+                    (text, "\n")
+                } else {
+                    (raw, "")
+                };
+                Line {
+                    text: text.to_string(),
+                    eol,
+                }
+            })
+            .collect();
+        let newline = lines
+            .iter()
+            .map(|l| l.eol)
+            .find(|eol| !eol.is_empty())
+            .unwrap_or("\n");
+        let step = indent_step(input).unwrap_or(2);
+        let doc = Document {
+            lines,
+            newline,
+            step,
+        };
+        doc.tree()?;
+        Ok(doc)
+    }
+
+    /// Decode the current text.
+    pub fn value(&self) -> crate::Result<Value> {
+        crate::from_str(&self.to_string(), &Options::default())
+    }
+
+    /// Replace the value at `path`. A missing key of an existing object is
+    /// added as its last field.
+    pub fn set(&mut self, path: &str, value: impl Into<Value>) -> crate::Result<()> {
+        let value = value.into();
+        let segments = parse_path(path)?;
+        let root = self.tree()?;
+        let Some((last, parents)) = segments.split_last() else {
+            return self.replace(Slot::Root(&root), &value, path);
+        };
+        let parent = resolve(&root, parents).ok_or_else(|| missing(path))?;
+        match (parent.child(last), parent.node(), last) {
+            (Some(slot), ..) => self.replace(slot, &value, path),
+            (None, Some(Node::Object(object)), Segment::Key(key)) => {
+                self.add_field(object, key, &value)
+            }
+            _ => Err(missing(path)),
+        }
+    }
+
+    /// Add a field or array element at `path`: `a.b` adds key `b` to object
+    /// `a`, and `a[i]` inserts before element `i` of array `a` (or appends,
+    /// when `i` is its length).
+    pub fn insert(&mut self, path: &str, value: impl Into<Value>) -> crate::Result<()> {
+        let value = value.into();
+        let segments = parse_path(path)?;
+        let root = self.tree()?;
+        let Some((last, parents)) = segments.split_last() else {
+            return Err(mismatch(path, "cannot insert the root"));
+        };
+        let parent = resolve(&root, parents).ok_or_else(|| missing(path))?;
+        match (parent.node(), last) {
+            (Some(Node::Object(object)), Segment::Key(key)) => {
+                if object.fields.iter().any(|f| f.key == *key) {
+                    return Err(data(ErrorKind::DuplicateKey, path, "key already exists"));
+                }
+                self.add_field(object, key, &value)
+            }
+            (Some(Node::Array(array)), Segment::Index(index)) if *index <= array.len() => {
+                self.add_element(array, *index, &value, path)
+            }
+            (Some(Node::Array(_)), Segment::Index(_)) => Err(missing(path)),
+            _ => Err(mismatch(
+                path,
+                "parent is not an object or array to insert into",
+            )),
+        }
+    }
+
+    /// Append an element to the array at `path`.
+    pub fn push(&mut self, path: &str, value: impl Into<Value>) -> crate::Result<()> {
+        let value = value.into();
+        let segments = parse_path(path)?;
+        let root = self.tree()?;
+        match resolve(&root, &segments)
+            .ok_or_else(|| missing(path))?
+            .node()
+        {
+            Some(Node::Array(array)) => self.add_element(array, array.len(), &value, path),
+            _ => Err(mismatch(path, "not an array")),
+        }
+    }
+
+    /// Remove the field or array element at `path`.
+    pub fn remove(&mut self, path: &str) -> crate::Result<()> {
+        let segments = parse_path(path)?;
+        let root = self.tree()?;
+        match resolve(&root, &segments).ok_or_else(|| missing(path))? {
+            Slot::Root(_) => Err(mismatch(path, "cannot remove the root")),
+            Slot::Field(object, index) => {
+                self.remove_field(object, index);
+                Ok(())
+            }
+            Slot::Item(array, index) => {
+                let Body::List { items, .. } = &array.body else {
+                    unreachable!()
+                };
+                self.splice(items[index].line..items[index].end, Vec::new());
+                self.set_len(array, array.len() - 1);
+                Ok(())
+            }
+            Slot::Cell(array, index) => {
+                let Body::Inline { line, colon, cells } = &array.body else {
+                    unreachable!()
+                };
+                let range = if cells.len() == 1 {
+                    *colon..cells[0].end
+                } else if index + 1 < cells.len() {
+                    cells[index].start..cells[index + 1].start
+                } else {
+                    cells[index - 1].end..cells[index].end
+                };
+                self.lines[*line].text.replace_range(range, "");
+                self.set_len(array, array.len() - 1);
+                Ok(())
+            }
+            Slot::Row(array, index) => {
+                let Body::Table { rows, .. } = &array.body else {
+                    unreachable!()
+                };
+                let line = rows[index].line;
+                self.splice(line..line + 1, Vec::new());
+                self.set_len(array, array.len() - 1);
+                Ok(())
+            }
+            Slot::RowCell(..) => Err(mismatch(path, "cannot remove one cell of a table row")),
+        }
+    }
+
+    fn tree(&self) -> crate::Result<Node> {
+        tree::build(&self.lines, self.step)
+    }
+
+    fn options(&self, delimiter: char) -> Options {
+        Options {
+            delimiter: delimiter_of(delimiter),
+            indent: self.step,
+            ..Options::default()
+        }
+    }
+
+    fn replace(&mut self, slot: Slot<'_>, value: &Value, path: &str) -> crate::Result<()> {
+        match slot {
+            Slot::Root(Node::Scalar(token)) if value.is_primitive() => {
+                self.replace_token(token.line, token.range.clone(), value, ',');
+                Ok(())
+            }
+            Slot::Root(_) => {
+                let text = crate::ser::to_string_streaming(value, &self.options(','))?;
+                let lines = text.split('\n').map(String::from).collect();
+                self.splice(0..self.lines.len(), lines);
+                Ok(())
+            }
+            Slot::Field(object, index) => {
+                let field = &object.fields[index];
+                match &field.value {
+                    Node::Scalar(token) if value.is_primitive() => {
+                        self.replace_token(token.line, token.range.clone(), value, ',');
+                    }
+                    _ => {
+                        let lines = self.render_field(&field.key, value, object.indent)?;
+                        self.splice_field(object, index, lines);
+                    }
+                }
+                Ok(())
+            }
+            Slot::Item(array, index) => {
+                let Body::List { indent, items, .. } = &array.body else {
+                    unreachable!()
+                };
+                let item = &items[index];
+                match &item.value {
+                    Node::Scalar(token) if value.is_primitive() => {
+                        self.replace_token(
+                            token.line,
+                            token.range.clone(),
+                            value,
+                            array.delimiter(),
+                        );
+                    }
+                    _ => {
+                        let lines = self.render_item(value, *indent, array.delimiter())?;
+                        self.splice(item.line..item.end, lines);
+                    }
+                }
+                Ok(())
+            }
+            Slot::Cell(array, index) => {
+                let Body::Inline { line, cells, .. } = &array.body else {
+                    unreachable!()
+                };
+                if !value.is_primitive() {
+                    return Err(mismatch(path, "inline arrays hold only primitives"));
+                }
+                self.replace_token(*line, cells[index].clone(), value, array.delimiter());
+                Ok(())
+            }
+            Slot::Row(array, index) => {
+                let Body::Table { fields, rows, .. } = &array.body else {
+                    unreachable!()
+                };
+                let row = &rows[index];
+                let sep = self.separator(row.line, &row.cells, array.delimiter());
+                let cells = row_cells(fields, value, array.delimiter()).ok_or_else(|| {
+                    mismatch(path, "table rows need an object with the table's fields")
+                })?;
+                let (Some(first), Some(last)) = (row.cells.first(), row.cells.last()) else {
+                    unreachable!()
+                };
+                let range = first.start..last.end;
+                self.lines[row.line]
+                    .text
+                    .replace_range(range, &cells.join(&sep));
+                Ok(())
+            }
+            Slot::RowCell(array, row, cell) => {
+                let Body::Table { rows, .. } = &array.body else {
+                    unreachable!()
+                };
+                if !value.is_primitive() {
+                    return Err(mismatch(path, "table cells hold only primitives"));
+                }
+                let row = &rows[row];
+                self.replace_token(row.line, row.cells[cell].clone(), value, array.delimiter());
+                Ok(())
+            }
+        }
+    }
+
+    /// Replace the field at `path` with `text`, the field as the encoder
+    /// writes it at the root.
+    pub(crate) fn replace_field(&mut self, path: &str, text: &str) -> crate::Result<()> {
+        let root = self.tree()?;
+        let Some(Slot::Field(object, index)) = resolve(&root, &parse_path(path)?) else {
+            return Err(missing(path));
+        };
+        let lines = text
+            .split('\n')
+            .map(|line| format!("{}{}", spaces(object.indent), line))
+            .collect();
+        self.splice_field(object, index, lines);
+        Ok(())
+    }
+
+    /// Replace the lines of field `index` of `object` with `lines`, which are
+    /// indented as the object's fields.
+    fn splice_field(&mut self, object: &Object, index: usize, mut lines: Vec<String>) {
+        let field = &object.fields[index];
+        if let Some(hyphen) = field.hyphen {
+            lines[0] = format!("{}- {}", spaces(hyphen), lines[0].trim_start());
+        }
+        self.splice(field.line..field.end, lines);
+    }
+
+    /// Overwrite a primitive token, keeping the quotes of a quoted string.
+    fn replace_token(&mut self, line: usize, range: Range<usize>, value: &Value, delimiter: char) {
+        let text = &mut self.lines[line].text;
+        let token = match value {
+            Value::String(s) if text[range.clone()].starts_with('"') => {
+                primitives::escape_and_quote(s)
+            }
+            _ => token(value, delimiter),
+        };
+        text.replace_range(range, &token);
+    }
+
+    fn add_field(&mut self, object: &Object, key: &str, value: &Value) -> crate::Result<()> {
+        let lines = self.render_field(key, value, object.indent)?;
+        self.splice(object.end..object.end, lines);
+        Ok(())
+    }
+
+    fn add_element(
+        &mut self,
+        array: &Array,
+        index: usize,
+        value: &Value,
+        path: &str,
+    ) -> crate::Result<()> {
+        let delimiter = array.delimiter();
+        match &array.body {
+            Body::Inline { line, colon, cells } => {
+                if !value.is_primitive() {
+                    return Err(mismatch(path, "inline arrays hold only primitives"));
+                }
+                let token = token(value, delimiter);
+                let sep = self.separator(*line, cells, delimiter);
+                let (at, insert) = match cells.get(index) {
+                    _ if cells.is_empty() => (*colon, format!(" {}", token)),
+                    Some(cell) => (cell.start, format!("{}{}", token, sep)),
+                    None => (cells[index - 1].end, format!("{}{}", sep, token)),
+                };
+                self.lines[*line].text.insert_str(at, &insert);
+            }
+            Body::Table {
+                fields,
+                indent,
+                rows,
+                end,
+            } => {
+                let cells = row_cells(fields, value, delimiter).ok_or_else(|| {
+                    mismatch(path, "table rows need an object with the table's fields")
+                })?;
+                let sep = match rows.first() {
+                    Some(row) => self.separator(row.line, &row.cells, delimiter),
+                    None => delimiter.to_string(),
+                };
+                let at = rows.get(index).map_or(*end, |row| row.line);
+                let row = format!("{}{}", spaces(*indent), cells.join(&sep));
+                self.splice(at..at, vec![row]);
+            }
+            Body::List { indent, items, end } => {
+                let lines = self.render_item(value, *indent, delimiter)?;
+                let at = items.get(index).map_or(*end, |item| item.line);
+                self.splice(at..at, lines);
+            }
+        }
+        self.set_len(array, array.len() + 1);
+        Ok(())
+    }
+
+    fn remove_field(&mut self, object: &Object, index: usize) {
+        let field = &object.fields[index];
+        let Some(hyphen) = field.hyphen else {
+            self.splice(field.line..field.end, Vec::new());
+            return;
+        };
+        // The first field of a list item: the next field moves up onto the
+        // hyphen line, or the item becomes an empty object
+        match object.fields.get(index + 1) {
+            Some(next) => {
+                let moved = format!(
+                    "{}- {}",
+                    spaces(hyphen),
+                    self.lines[next.line].text.trim_start()
+                );
+                self.splice(field.line..next.line + 1, vec![moved]);
+            }
+            None => self.splice(field.line..field.end, vec![format!("{}-", spaces(hyphen))]),
+        }
+    }
+
+    fn set_len(&mut self, array: &Array, len: usize) {
+        if let Some(header) = &array.header {
+            self.lines[header.line]
+                .text
+                .replace_range(header.len.clone(), &len.to_string());
+        }
+    }
+
+    /// The text between the first two cells, or the bare delimiter.
+    fn separator(&self, line: usize, cells: &[Range<usize>], delimiter: char) -> String {
+        match cells {
+            [a, b, ..] => self.lines[line].text[a.end..b.start].to_string(),
+            _ => delimiter.to_string(),
+        }
+    }
+
+    /// `key: value` as the encoder writes it, at `indent`.
+    fn render_field(&self, key: &str, value: &Value, indent: usize) -> crate::Result<Vec<String>> {
+        let mut object = Map::new();
+        object.insert(key.to_string(), value.clone());
+        let text = crate::ser::to_string_streaming(&Value::Object(object), &self.options(','))?;
+        Ok(text
+            .split('\n')
+            .map(|line| format!("{}{}", spaces(indent), line))
+            .collect())
+    }
+
+    /// A list item as the encoder writes it, at `indent`.
+    fn render_item(
+        &self,
+        value: &Value,
+        indent: usize,
+        delimiter: char,
+    ) -> crate::Result<Vec<String>> {
+        if value.is_primitive() {
+            return Ok(vec![format!(
+                "{}- {}",
+                spaces(indent),
+                token(value, delimiter)
+            )]);
+        }
+        // Encode `[value, null]`, which is always written as a list, and keep
+        // the lines of the first item
+        let pair = Value::Array(vec![value.clone(), Value::Null]);
+        let text = crate::ser::to_string_streaming(&pair, &self.options(delimiter))?;
+        let lines: Vec<&str> = text.split('\n').collect();
+        Ok(lines[1..lines.len() - 1]
+            .iter()
+            .map(|line| format!("{}{}", spaces(indent), &line[self.step..]))
+            .collect())
+    }
+
+    /// Replace lines `at` with `texts`, keeping whether the document ends
+    /// with a line break.
+    fn splice(&mut self, at: Range<usize>, texts: Vec<String>) {
+        let unterminated = self.lines.last().is_some_and(|l| l.eol.is_empty());
+        let newline = self.newline;
+        self.lines.splice(
+            at,
+            texts.into_iter().map(|text| Line { text, eol: newline }),
+        );
+        for line in &mut self.lines {
+            if line.eol.is_empty() {
+                line.eol = newline;
+            }
+        }
+        if let (true, Some(last)) = (unterminated, self.lines.last_mut()) {
+            last.eol = "";
+        }
+    }
+}
+
+impl core::str::FromStr for Document {
+    type Err = Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        Document::parse(s)
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            f.write_str(&line.text)?;
+            f.write_str(line.eol)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'n> Slot<'n> {
+    fn node(self) -> Option<&'n Node> {
+        match self {
+            Slot::Root(node) => Some(node),
+            Slot::Field(object, index) => Some(&object.fields[index].value),
+            Slot::Item(array, index) => match &array.body {
+                Body::List { items, .. } => Some(&items[index].value),
+                _ => None,
+            },
+            Slot::Cell(..) | Slot::Row(..) | Slot::RowCell(..) => None,
+        }
+    }
+
+    fn child(self, segment: &Segment) -> Option<Slot<'n>> {
+        if let (Slot::Row(array, row), Segment::Key(key)) = (self, segment) {
+            let Body::Table { fields, .. } = &array.body else {
+                return None;
+            };
+            let cell = fields.iter().position(|f| f == key)?;
+            return Some(Slot::RowCell(array, row, cell));
+        }
+        match (self.node()?, segment) {
+            (Node::Object(object), Segment::Key(key)) => object
+                .fields
+                .iter()
+                .position(|f| f.key == *key)
+                .map(|index| Slot::Field(object, index)),
+            (Node::Array(array), Segment::Index(index)) if *index < array.len() => {
+                Some(match array.body {
+                    Body::Inline { .. } => Slot::Cell(array, *index),
+                    Body::Table { .. } => Slot::Row(array, *index),
+                    Body::List { .. } => Slot::Item(array, *index),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+fn resolve<'n>(root: &'n Node, segments: &[Segment]) -> Option<Slot<'n>> {
+    segments
+        .iter()
+        .try_fold(Slot::Root(root), |slot, segment| slot.child(segment))
+}
+
+/// The value at `path` in a decoded tree.
+pub(crate) fn lookup<'v>(value: &'v Value, path: &str) -> crate::Result<&'v Value> {
+    parse_path(path)?
+        .iter()
+        .try_fold(value, |node, segment| match segment {
+            Segment::Key(key) => node.as_object()?.get(key),
+            Segment::Index(index) => node.as_array()?.get(*index),
+        })
+        .ok_or_else(|| missing(path))
+}
+
+pub(crate) fn parse_path(path: &str) -> crate::Result<Vec<Segment>> {
+    let invalid = || mismatch(path, "invalid path");
+    let mut segments = Vec::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+        if !segments.is_empty() && !rest.starts_with('[') {
+            rest = rest.strip_prefix('.').ok_or_else(invalid)?;
+        }
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let index = after[..end].parse().map_err(|_| invalid())?;
+            segments.push(Segment::Index(index));
+            rest = &after[end + 1..];
+        } else if rest.starts_with('"') {
+            let end = quoted_len(rest).ok_or_else(invalid)?;
+            let key =
+                crate::decode::parser::parse_key(&rest[..end], false).map_err(|_| invalid())?;
+            segments.push(Segment::Key(key));
+            rest = &rest[end..];
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            segments.push(Segment::Key(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+    }
+    Ok(segments)
+}
+
+/// Length of the quoted string `s` starts with, quotes included.
+fn quoted_len(s: &str) -> Option<usize> {
+    let mut escape = false;
+    for (i, c) in s.char_indices().skip(1) {
+        match c {
+            _ if escape => escape = false,
+            '\\' => escape = true,
+            '"' => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Cells of a table row for `value`, in the order of `fields`.
+fn row_cells(fields: &[String], value: &Value, delimiter: char) -> Option<Vec<String>> {
+    let object = value.as_object()?;
+    if object.len() != fields.len() {
+        return None;
+    }
+    fields
+        .iter()
+        .map(|f| {
+            let v = object.get(f)?;
+            v.is_primitive().then(|| token(v, delimiter))
+        })
+        .collect()
+}
+
+/// A primitive as the encoder writes it inside an array using `delimiter`.
+fn token(value: &Value, delimiter: char) -> String {
+    match value {
+        Value::Null => primitives::format_null().to_string(),
+        Value::Bool(b) => primitives::format_bool(*b).to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => primitives::format_string(s, delimiter_of(delimiter)),
+        Value::Array(_) | Value::Object(_) => unreachable!("not a primitive"),
+    }
+}
+
+fn delimiter_of(c: char) -> Delimiter {
+    match c {
+        '\t' => Delimiter::Tab,
+        '|' => Delimiter::Pipe,
+        _ => Delimiter::Comma,
+    }
+}
+
+fn spaces(n: usize) -> String {
+    " ".repeat(n)
+}
+
+pub(crate) fn data(kind: ErrorKind, path: &str, message: &str) -> Error {
+    Error::Data {
+        kind,
+        message: message.to_string(),
+        path: path.to_string(),
+        span: None,
+    }
+}
+
+pub(crate) fn missing(path: &str) -> Error {
+    data(ErrorKind::Other, path, "no value at this path")
+}
+
+fn mismatch(path: &str, message: &str) -> Error {
+    data(ErrorKind::TypeMismatch, path, message)
+}
@@ -0,0 +1,459 @@
+//! The structure of a [`Document`](super::Document) as line and byte ranges
+//!
+//! Nodes own no text; they point at lines of the document and at byte ranges
+//! within those lines, so an edit can rewrite exactly the part it changes.
+//! The tree is rebuilt from the lines after every edit.
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+use core::ops::Range;
+
+use super::Line;
+use crate::decode::parser::{self, ArrayHeader};
+use crate::decode::scanner::{LineKind, ParsedLine, scan_line};
+use crate::error::{Error, ErrorKind, Span};
+
+pub(super) enum Node {
+    Scalar(Token),
+    Object(Object),
+    Array(Array),
+}
+
+/// A primitive token: `range` bytes of line `line`.
+pub(super) struct Token {
+    pub(super) line: usize,
+    pub(super) range: Range<usize>,
+}
+
+pub(super) struct Object {
+    /// Indentation of the field lines
+    pub(super) indent: usize,
+    pub(super) fields: Vec<Field>,
+    /// Line a new field is inserted before
+    pub(super) end: usize,
+}
+
+pub(super) struct Field {
+    pub(super) key: String,
+    pub(super) line: usize,
+    /// Indentation of the `- ` when the field shares its line with a list item
+    pub(super) hyphen: Option<usize>,
+    /// One past the last line of the field
+    pub(super) end: usize,
+    pub(super) value: Node,
+}
+
+pub(super) struct Array {
+    /// `None` for a `key:` followed by list items, which has no length marker
+    pub(super) header: Option<Header>,
+    pub(super) body: Body,
+}
+
+pub(super) struct Header {
+    pub(super) line: usize,
+    /// The digits of `[N]`
+    pub(super) len: Range<usize>,
+    pub(super) delimiter: char,
+}
+
+pub(super) enum Body {
+    /// `key[N]: a,b,c`; `colon` is the byte just past the header's colon
+    Inline {
+        line: usize,
+        colon: usize,
+        cells: Vec<Range<usize>>,
+    },
+    /// `key[N]{fields}:` with one row per line at `indent`
+    Table {
+        fields: Vec<String>,
+        indent: usize,
+        rows: Vec<Row>,
+        end: usize,
+    },
+    /// `key[N]:` with `- ` items at `indent`
+    List {
+        indent: usize,
+        items: Vec<Item>,
+        end: usize,
+    },
+}
+
+pub(super) struct Row {
+    pub(super) line: usize,
+    pub(super) cells: Vec<Range<usize>>,
+}
+
+pub(super) struct Item {
+    pub(super) line: usize,
+    pub(super) end: usize,
+    pub(super) value: Node,
+}
+
+impl Node {
+    /// One past the last line of the node.
+    pub(super) fn end(&self) -> usize {
+        match self {
+            Node::Scalar(token) => token.line + 1,
+            Node::Object(object) => object.end,
+            Node::Array(array) => match &array.body {
+                Body::Inline { line, .. } => line + 1,
+                Body::Table { end, .. } | Body::List { end, .. } => *end,
+            },
+        }
+    }
+}
+
+impl Array {
+    pub(super) fn len(&self) -> usize {
+        match &self.body {
+            Body::Inline { cells, .. } => cells.len(),
+            Body::Table { rows, .. } => rows.len(),
+            Body::List { items, .. } => items.len(),
+        }
+    }
+
+    pub(super) fn delimiter(&self) -> char {
+        self.header.as_ref().map_or(',', |h| h.delimiter)
+    }
+}
+
+pub(super) fn build(lines: &[Line], step: usize) -> Result<Node, Error> {
+    let builder = Builder {
+        lines,
+        parsed: lines.iter().map(|l| scan_line(&l.text)).collect(),
+        step,
+    };
+    builder.root()
+}
+
+struct Builder<'a> {
+    lines: &'a [Line],
+    parsed: Vec<ParsedLine<'a>>,
+    step: usize,
+}
+
+/// Byte offset of `part` within `text`, of which it is a slice.
+fn offset(text: &str, part: &str) -> usize {
+    part.as_ptr() as usize - text.as_ptr() as usize
+}
+
+fn range(text: &str, part: &str) -> Range<usize> {
+    let start = offset(text, part);
+    start..start + part.len()
+}
+
+fn is_header(key: &str) -> bool {
+    parser::is_array_header_line(key)
+}
+
+impl<'a> Builder<'a> {
+    fn text(&self, line: usize) -> &'a str {
+        &self.lines[line].text
+    }
+
+    fn next_content(&self, from: usize) -> Option<usize> {
+        (from..self.parsed.len()).find(|&i| self.parsed[i].kind != LineKind::Blank)
+    }
+
+    /// The next content line if it is indented deeper than `block`.
+    fn child(&self, from: usize, block: usize) -> Option<usize> {
+        self.next_content(from)
+            .filter(|&i| self.parsed[i].indent > block)
+    }
+
+    fn error(&self, line: usize, kind: ErrorKind, message: &str) -> Error {
+        let offset = self.lines[..line]
+            .iter()
+            .map(|l| l.text.len() + l.eol.len())
+            .sum();
+        let span = Span {
+            offset,
+            line: line + 1,
+            column: 1,
+            len: self.lines[line].text.len(),
+        };
+        Error::syntax(kind, span, message)
+    }
+
+    fn root(&self) -> Result<Node, Error> {
+        let Some(first) = self.next_content(0) else {
+            return Ok(Node::Object(Object {
+                indent: 0,
+                fields: Vec::new(),
+                end: self.lines.len(),
+            }));
+        };
+        let line = &self.parsed[first];
+        let body = &self.text(first)[line.indent..];
+        match line.kind {
+            LineKind::KeyValue { key, .. } | LineKind::KeyOnly { key }
+                if key.starts_with('[') && is_header(key) =>
+            {
+                let (_, node) = self.entry(first, body, line.kind.clone(), line.indent)?;
+                Ok(node)
+            }
+            LineKind::Scalar(s) if self.next_content(first + 1).is_none() => {
+                Ok(Node::Scalar(self.token(first, s)))
+            }
+            _ => Ok(Node::Object(self.object(first, line.indent)?)),
+        }
+    }
+
+    fn token(&self, line: usize, s: &str) -> Token {
+        Token {
+            line,
+            range: range(self.text(line), s.trim_end()),
+        }
+    }
+
+    fn key(&self, line: usize, raw: &str) -> Result<String, Error> {
+        parser::parse_key(raw.trim(), false).map_err(|e| self.error(line, e.kind(), e.message()))
+    }
+
+    /// The fields at `indent` starting at line `start`.
+    fn object(&self, start: usize, indent: usize) -> Result<Object, Error> {
+        let mut fields = Vec::new();
+        let mut end = start;
+        let mut next = start;
+        while let Some(i) = self.next_content(next) {
+            let line = &self.parsed[i];
+            if line.indent < indent {
+                break;
+            }
+            if line.indent > indent {
+                return Err(self.error(i, ErrorKind::Indentation, "unexpected indentation"));
+            }
+            if !matches!(
+                line.kind,
+                LineKind::KeyValue { .. } | LineKind::KeyOnly { .. }
+            ) {
+                return Err(self.error(i, ErrorKind::Syntax, "expected `key: value`"));
+            }
+            let body = &self.text(i)[indent..];
+            let (key, value) = self.entry(i, body, line.kind.clone(), indent)?;
+            end = value.end();
+            next = end;
+            fields.push(Field {
+                key: key.unwrap_or_default(),
+                line: i,
+                hyphen: None,
+                end,
+                value,
+            });
+        }
+        Ok(Object {
+            indent,
+            fields,
+            end,
+        })
+    }
+
+    /// A `key: value`, `key:` or array header line whose text from the key on
+    /// is `body`; nested lines are those deeper than `block`.
+    fn entry(
+        &self,
+        line: usize,
+        body: &'a str,
+        kind: LineKind<'a>,
+        block: usize,
+    ) -> Result<(Option<String>, Node), Error> {
+        let (key, value) = match kind {
+            LineKind::KeyValue { key, value } => (key, Some(value)),
+            LineKind::KeyOnly { key } => (key, None),
+            LineKind::Scalar(s) => return Ok((None, Node::Scalar(self.token(line, s)))),
+            _ => return Err(self.error(line, ErrorKind::Syntax, "expected a value")),
+        };
+        if is_header(key) {
+            let header = parser::parse_array_header(body.trim_end())
+                .ok_or_else(|| self.error(line, ErrorKind::Syntax, "invalid array header"))?;
+            let name = match &header.key {
+                Some(raw) => Some(self.key(line, raw)?),
+                None => None,
+            };
+            let colon = offset(self.text(line), key) + key.len() + 1;
+            let array = self.array(line, body, colon, header, value, block)?;
+            return Ok((name, Node::Array(array)));
+        }
+        let name = self.key(line, key)?;
+        let node = match value {
+            Some(v) => Node::Scalar(self.token(line, v)),
+            None => self.nested(line, block)?,
+        };
+        Ok((Some(name), node))
+    }
+
+    /// What follows a `key:` or bare `-` on line `line`.
+    fn nested(&self, line: usize, block: usize) -> Result<Node, Error> {
+        match self.child(line + 1, block) {
+            Some(i) if matches!(self.parsed[i].kind, LineKind::ListItem { .. }) => {
+                Ok(Node::Array(Array {
+                    header: None,
+                    body: self.list(line, block)?,
+                }))
+            }
+            Some(i) => Ok(Node::Object(self.object(i, self.parsed[i].indent)?)),
+            None => Ok(Node::Object(Object {
+                indent: block + self.step,
+                fields: Vec::new(),
+                end: line + 1,
+            })),
+        }
+    }
+
+    fn array(
+        &self,
+        line: usize,
+        body: &'a str,
+        colon: usize,
+        header: ArrayHeader,
+        values: Option<&'a str>,
+        block: usize,
+    ) -> Result<Array, Error> {
+        let text = self.text(line);
+        let key_len = header.key.as_ref().map_or(0, String::len);
+        let bracket = body[key_len..]
+            .find('[')
+            .map(|i| offset(text, body) + key_len + i + 1)
+            .ok_or_else(|| self.error(line, ErrorKind::Syntax, "invalid array header"))?;
+        let digits = text[bracket..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+        let delimiter = header.delimiter;
+        let body = if let Some(fields) = header.fields {
+            let fields = fields
+                .iter()
+                .map(|f| self.key(line, f))
+                .collect::<Result<_, _>>()?;
+            self.table(line, fields, delimiter, block)?
+        } else if let Some(values) = values {
+            let cells = parser::split_delim_aware(values.trim_end(), delimiter)
+                .into_iter()
+                .map(|cell| range(text, cell))
+                .collect();
+            Body::Inline { line, colon, cells }
+        } else if self.child(line + 1, block).is_some() {
+            self.list(line, block)?
+        } else {
+            Body::Inline {
+                line,
+                colon,
+                cells: Vec::new(),
+            }
+        };
+        Ok(Array {
+            header: Some(Header {
+                line,
+                len: bracket..bracket + digits,
+                delimiter,
+            }),
+            body,
+        })
+    }
+
+    fn table(
+        &self,
+        line: usize,
+        fields: Vec<String>,
+        delimiter: char,
+        block: usize,
+    ) -> Result<Body, Error> {
+        let mut rows = Vec::new();
+        let mut indent = block + self.step;
+        let mut end = line + 1;
+        while let Some(i) = self.child(end, block) {
+            let text = self.text(i);
+            let content = text[self.parsed[i].indent..].trim_end();
+            if rows.is_empty() {
+                indent = self.parsed[i].indent;
+            }
+            let cells: Vec<_> = parser::split_delim_aware(content, delimiter)
+                .into_iter()
+                .map(|cell| range(text, cell))
+                .collect();
+            if cells.len() != fields.len() {
+                return Err(self.error(
+                    i,
+                    ErrorKind::LengthMismatch,
+                    &format!(
+                        "tabular row has {} values but header declares {} fields",
+                        cells.len(),
+                        fields.len()
+                    ),
+                ));
+            }
+            rows.push(Row { line: i, cells });
+            end = i + 1;
+        }
+        Ok(Body::Table {
+            fields,
+            indent,
+            rows,
+            end,
+        })
+    }
+
+    fn list(&self, line: usize, block: usize) -> Result<Body, Error> {
+        let mut items = Vec::new();
+        let mut indent = block + self.step;
+        let mut end = line + 1;
+        while let Some(i) = self.child(end, block) {
+            let at = self.parsed[i].indent;
+            if !matches!(self.parsed[i].kind, LineKind::ListItem { .. }) {
+                return Err(self.error(i, ErrorKind::Syntax, "expected a `- ` list item"));
+            }
+            if items.is_empty() {
+                indent = at;
+            } else if at != indent {
+                return Err(self.error(i, ErrorKind::Indentation, "unexpected indentation"));
+            }
+            let item = self.item(i, at)?;
+            end = item.end;
+            items.push(item);
+        }
+        Ok(Body::List { indent, items, end })
+    }
+
+    fn item(&self, line: usize, indent: usize) -> Result<Item, Error> {
+        let rest = match self.parsed[line].kind {
+            LineKind::ListItem { value: Some(rest) } => rest,
+            _ => {
+                let value = self.nested(line, indent)?;
+                let end = value.end();
+                return Ok(Item { line, end, value });
+            }
+        };
+        let inner = scan_line(rest);
+        let body = &rest[inner.indent..];
+        let value = match inner.kind {
+            LineKind::KeyValue { key, .. } | LineKind::KeyOnly { key }
+                if key.starts_with('[') && is_header(key) =>
+            {
+                self.entry(line, body, inner.kind, indent)?.1
+            }
+            LineKind::KeyValue { .. } | LineKind::KeyOnly { .. } => {
+                // The first field shares the hyphen line; the rest line up
+                // one level deeper than the hyphen
+                let siblings = indent + self.step;
+                let (key, value) = self.entry(line, body, inner.kind, siblings)?;
+                let first = Field {
+                    key: key.unwrap_or_default(),
+                    line,
+                    hyphen: Some(indent),
+                    end: value.end(),
+                    value,
+                };
+                let mut object = self.object(first.end, siblings)?;
+                object.end = object.end.max(first.end);
+                object.fields.insert(0, first);
+                Node::Object(object)
+            }
+            _ => Node::Scalar(self.token(line, body)),
+        };
+        Ok(Item {
+            line,
+            end: value.end(),
+            value,
+        })
+    }
+}
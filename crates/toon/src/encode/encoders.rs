@@ -1,6 +1,9 @@
 use crate::{
     Result,
-    encode::{primitives, writer::LineWriter},
+    encode::{
+        primitives,
+        writer::{LineWriter, Sink},
+    },
     options::{KeyFolding, Options},
 };
 
@@ -20,9 +23,9 @@ fn is_primitive_array(items: &[Value]) -> bool {
     })
 }
 
-pub fn encode_value(
+pub fn encode_value<S: Sink>(
     value: &Value,
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<()> {
@@ -52,10 +55,10 @@ pub fn encode_value(
 }
 
 /// Encode an object field (key-value pair) - internal version without sibling context
-fn encode_object_field_internal(
+fn encode_object_field_internal<S: Sink>(
     key: &str,
     value: &Value,
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<()> {
@@ -63,10 +66,10 @@ fn encode_object_field_internal(
 }
 
 /// Encode an object field (key-value pair) - public API for streaming serializer
-pub fn encode_object_field(
+pub fn encode_object_field<S: Sink>(
     key: &str,
     value: &Value,
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<()> {
@@ -75,10 +78,10 @@ pub fn encode_object_field(
 
 /// Encode an object field with sibling key context for collision detection
 /// sibling_keys should contain all keys at the current object level
-pub fn encode_object_field_with_siblings(
+pub fn encode_object_field_with_siblings<S: Sink>(
     key: &str,
     value: &Value,
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
     sibling_keys: &[String],
@@ -87,14 +90,15 @@ pub fn encode_object_field_with_siblings(
 }
 
 /// Encode object field with optional sibling key context for collision detection
-fn encode_object_field_with_context(
+fn encode_object_field_with_context<S: Sink>(
     key: &str,
     value: &Value,
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
     sibling_keys: Option<&[String]>,
 ) -> Result<()> {
+    w.check()?;
     let key_fmt = primitives::format_key(key);
 
     // Track whether we should disable nested folding
@@ -311,10 +315,10 @@ fn key_needs_quotes(key: &str) -> bool {
 
 /// Encode a folded key-value pair
 /// After a fold is performed, we disable further folding in nested content
-fn encode_folded_value(
+fn encode_folded_value<S: Sink>(
     folded_key: &str,
     value: &Value,
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<()> {
@@ -357,10 +361,10 @@ fn encode_folded_value(
 }
 
 /// Encode a keyed array (array as an object field): `key[N]{fields}: ...` or `key[N]: ...`
-fn encode_keyed_array(
+fn encode_keyed_array<S: Sink>(
     key: &str,
     items: &[Value],
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<()> {
@@ -390,6 +394,7 @@ fn encode_keyed_array(
 
         // Emit rows at indent+2
         for item in items {
+            w.check()?;
             let obj = item
                 .as_object()
                 .expect("tabular detection guaranteed object");
@@ -442,7 +447,12 @@ fn encode_keyed_array(
 }
 
 /// Encode a root-level array (no key prefix)
-fn encode_array(items: &[Value], w: &mut LineWriter, opts: &Options, indent: usize) -> Result<()> {
+fn encode_array<S: Sink>(
+    items: &[Value],
+    w: &mut LineWriter<S>,
+    opts: &Options,
+    indent: usize,
+) -> Result<()> {
     let len = items.len();
     let delim = opts.delimiter;
     let dch = primitives::delimiter_char(delim);
@@ -462,6 +472,7 @@ fn encode_array(items: &[Value], w: &mut LineWriter, opts: &Options, indent: usi
 
         // Emit rows at indent+2
         for item in items {
+            w.check()?;
             let obj = item
                 .as_object()
                 .expect("tabular detection guaranteed object");
@@ -509,7 +520,13 @@ fn encode_array(items: &[Value], w: &mut LineWriter, opts: &Options, indent: usi
 }
 
 /// Encode a list item (- prefix)
-fn encode_list_item(item: &Value, w: &mut LineWriter, opts: &Options, indent: usize) -> Result<()> {
+fn encode_list_item<S: Sink>(
+    item: &Value,
+    w: &mut LineWriter<S>,
+    opts: &Options,
+    indent: usize,
+) -> Result<()> {
+    w.check()?;
     match item {
         Value::Null => w.line_list_item(indent, primitives::format_null()),
         Value::Bool(b) => w.line_list_item(indent, primitives::format_bool(*b)),
@@ -528,9 +545,9 @@ fn encode_list_item(item: &Value, w: &mut LineWriter, opts: &Options, indent: us
 }
 
 /// Encode an array as a list item: `- [M]: v1,v2` or `- [M]:` with nested
-fn encode_list_item_array(
+fn encode_list_item_array<S: Sink>(
     items: &[Value],
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<()> {
@@ -575,9 +592,9 @@ fn encode_list_item_array(
 }
 
 /// Encode an object as a list item per §10
-fn encode_list_item_object(
+fn encode_list_item_object<S: Sink>(
     obj: &serde_json::Map<String, Value>,
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<()> {
@@ -607,6 +624,7 @@ fn encode_list_item_object(
 
             // Rows at depth+2 (indent + 4 relative to list item indent)
             for item in items {
+                w.check()?;
                 let inner_obj = item.as_object().unwrap();
                 let cells: Vec<String> = keys
                     .iter()
@@ -725,6 +743,10 @@ pub fn is_tabular_array(arr: &[Value]) -> Option<Vec<String>> {
             _ => return None,
         };
         let kset: Vec<String> = obj.keys().cloned().collect();
+        // A table needs at least one column; `[N]{}:` rows would be blank lines
+        if kset.is_empty() {
+            return None;
+        }
 
         if let Some(ref ks) = keys {
             // Check if keys match (order-insensitive comparison)
@@ -20,3 +20,19 @@ pub fn encode_value_to_string(value: &Value, options: &Options) -> Result<String
     encoders::encode_value(&v, &mut w, options, 0)?;
     Ok(w.into_string())
 }
+
+/// Encode into `sink`, flushing lines in chunks instead of building one `String`.
+#[cfg(all(feature = "serde", feature = "json"))]
+pub fn encode_value_to_sink<S: writer::Sink>(
+    value: &Value,
+    options: &Options,
+    sink: S,
+) -> Result<S> {
+    let mut w = writer::LineWriter::with_sink(sink);
+    let v = crate::encode::normalize::normalize_value(value);
+    match encoders::encode_value(&v, &mut w, options, 0) {
+        // Encoding stops at a failed write, whose error `finish` gives
+        Err(e) if w.check().is_ok() => Err(e),
+        _ => w.finish(),
+    }
+}
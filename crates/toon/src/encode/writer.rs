@@ -1,55 +1,188 @@
+//! Line-oriented output for the encoders
+//!
+//! `LineWriter` buffers lines and hands them to a [`Sink`] in chunks, so encoding
+//! into an `io::Write` or `fmt::Write` never holds the whole document in memory.
+
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
 
-pub struct LineWriter {
-    out: String,
+use crate::error::{Error, Result};
+
+/// Buffered bytes that trigger a flush to a streaming sink.
+const FLUSH_THRESHOLD: usize = 8 * 1024;
+
+/// Destination for encoded TOON text.
+pub trait Sink {
+    fn write_str(&mut self, s: &str) -> Result<()>;
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl Sink for String {
+    fn write_str(&mut self, s: &str) -> Result<()> {
+        self.push_str(s);
+        Ok(())
+    }
 }
 
-impl LineWriter {
+/// [`Sink`] over a `std::io::Write`.
+#[cfg(feature = "std")]
+pub struct IoSink<W>(pub W);
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Sink for IoSink<W> {
+    fn write_str(&mut self, s: &str) -> Result<()> {
+        self.0.write_all(s.as_bytes())?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.0.flush()?;
+        Ok(())
+    }
+}
+
+/// [`Sink`] over a `core::fmt::Write`.
+pub struct FmtSink<W>(pub W);
+
+impl<W: core::fmt::Write> Sink for FmtSink<W> {
+    fn write_str(&mut self, s: &str) -> Result<()> {
+        self.0
+            .write_str(s)
+            .map_err(|_| Error::Message(String::from("formatter error")))
+    }
+}
+
+pub struct LineWriter<S = String> {
+    buf: String,
+    flush_at: usize,
+    started: bool,
+    error: Option<Error>,
+    sink: S,
+}
+
+impl LineWriter<String> {
     pub fn new() -> Self {
-        Self { out: String::new() }
+        Self {
+            buf: String::new(),
+            flush_at: usize::MAX,
+            started: false,
+            error: None,
+            sink: String::new(),
+        }
     }
 
-    fn write_indent(&mut self, indent: usize) {
+    pub fn into_string(self) -> String {
+        // Per spec §12: No trailing newline at the end of the document
+        // (lines are separated, not terminated, so there is nothing to strip)
+        let mut out = self.sink;
+        if out.is_empty() {
+            return self.buf;
+        }
+        out.push_str(&self.buf);
+        out
+    }
+}
+
+impl<S: Sink> LineWriter<S> {
+    /// Stream lines into `sink`, flushing every few kilobytes.
+    pub fn with_sink(sink: S) -> Self {
+        Self {
+            buf: String::with_capacity(FLUSH_THRESHOLD),
+            flush_at: FLUSH_THRESHOLD,
+            started: false,
+            error: None,
+            sink,
+        }
+    }
+
+    /// Start a line, or return false once a write has failed and the rest of
+    /// the output is dropped.
+    fn begin_line(&mut self, indent: usize) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        if self.started {
+            self.buf.push('\n');
+        }
+        self.started = true;
         for _ in 0..indent {
-            self.out.push(' ');
+            self.buf.push(' ');
+        }
+        true
+    }
+
+    fn end_line(&mut self) {
+        if self.buf.len() >= self.flush_at {
+            self.flush_buf();
+        }
+    }
+
+    fn flush_buf(&mut self) {
+        // After a failed write the remaining output is dropped; `finish` reports the error
+        if self.error.is_none() {
+            if let Err(e) = self.sink.write_str(&self.buf) {
+                self.error = Some(e);
+            }
+        }
+        self.buf.clear();
+    }
+
+    /// Fail once a write to the sink has failed, so that encoding can stop
+    /// early; [`finish`](Self::finish) gives the sink's error.
+    pub fn check(&self) -> Result<()> {
+        match self.error {
+            Some(_) => Err(Error::Message(String::from("write to the sink failed"))),
+            None => Ok(()),
         }
     }
 
     pub fn line(&mut self, indent: usize, s: &str) {
-        self.write_indent(indent);
-        self.out.push_str(s);
-        self.out.push('\n');
+        if !self.begin_line(indent) {
+            return;
+        }
+        self.buf.push_str(s);
+        self.end_line();
     }
 
     pub fn line_kv(&mut self, indent: usize, key: &str, value: &str) {
-        self.write_indent(indent);
-        self.out.push_str(key);
-        self.out.push_str(": ");
-        self.out.push_str(value);
-        self.out.push('\n');
+        if !self.begin_line(indent) {
+            return;
+        }
+        self.buf.push_str(key);
+        self.buf.push_str(": ");
+        self.buf.push_str(value);
+        self.end_line();
     }
 
     pub fn line_list_item(&mut self, indent: usize, value: &str) {
-        self.write_indent(indent);
-        self.out.push_str("- ");
-        self.out.push_str(value);
-        self.out.push('\n');
+        if !self.begin_line(indent) {
+            return;
+        }
+        self.buf.push_str("- ");
+        self.buf.push_str(value);
+        self.end_line();
     }
 
     pub fn line_key_only(&mut self, indent: usize, key: &str) {
-        self.write_indent(indent);
-        self.out.push_str(key);
-        self.out.push(':');
-        self.out.push('\n');
+        if !self.begin_line(indent) {
+            return;
+        }
+        self.buf.push_str(key);
+        self.buf.push(':');
+        self.end_line();
     }
 
-    pub fn into_string(mut self) -> String {
-        // Per spec §12: No trailing newline at the end of the document
-        if self.out.ends_with('\n') {
-            self.out.pop();
+    /// Flush buffered output and return the sink, or the first write error.
+    pub fn finish(mut self) -> Result<S> {
+        self.flush_buf();
+        if let Some(e) = self.error.take() {
+            return Err(e);
         }
-        self.out
+        self.sink.flush()?;
+        Ok(self.sink)
     }
 }
 
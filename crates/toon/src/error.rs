@@ -4,6 +4,202 @@ use thiserror::Error;
 #[cfg(feature = "std")]
 use std::io;
 
+#[cfg(all(not(feature = "std"), feature = "serde"))]
+use alloc::format;
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+
+/// Location of the text a syntax error refers to.
+///
+/// `offset` and `len` are in bytes; `line` and `column` are 1-based, with the
+/// column counted in characters. A `column` of 0 means only the line is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+}
+
+impl Span {
+    /// A span covering `len` bytes at byte `offset` of `source`.
+    pub fn locate(source: &str, offset: usize, len: usize) -> Self {
+        let offset = floor_char_boundary(source, offset.min(source.len()));
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        Span {
+            offset,
+            line: source[..offset].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            len,
+        }
+    }
+
+    /// A span covering `part`; `None` unless `part` is a slice of `source`.
+    pub fn of(source: &str, part: &str) -> Option<Self> {
+        let offset = (part.as_ptr() as usize).wrapping_sub(source.as_ptr() as usize);
+        (offset <= source.len() && part.len() <= source.len() - offset)
+            .then(|| Self::locate(source, offset, part.len()))
+    }
+
+    /// Narrow a span covering `text` (on a single line) to `part`, a slice of
+    /// `text`; returns `self` unchanged if `part` is not inside `text`.
+    pub(crate) fn within(self, text: &str, part: &str) -> Self {
+        let start = (part.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+        if start > text.len() || part.len() > text.len() - start {
+            return self;
+        }
+        Span {
+            offset: self.offset + start,
+            line: self.line,
+            column: self.column + text[..start].chars().count(),
+            len: part.len(),
+        }
+    }
+
+    /// Only the line number is known.
+    pub fn line(line: usize) -> Self {
+        Span {
+            line,
+            ..Span::default()
+        }
+    }
+
+    /// Render the line `self` points into, with a caret underline:
+    ///
+    /// ```text
+    /// 3 |   2,Bob,"oops
+    ///   |         ^^^^^
+    /// ```
+    ///
+    /// Returns an empty string if `source` has no such line.
+    pub fn snippet(&self, source: &str) -> String {
+        use core::fmt::Write;
+
+        let Some(text) = source.split('\n').nth(self.line.wrapping_sub(1)) else {
+            return String::new();
+        };
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let mut out = String::new();
+        let _ = writeln!(out, "{} | {}", number, text);
+        let _ = write!(out, "{} | ", gutter);
+        if self.column == 0 {
+            out.push_str(&"^".repeat(text.chars().count().max(1)));
+            return out;
+        }
+        // Keep tabs so the caret lines up with the source as displayed
+        let mut chars = text.chars();
+        for c in chars.by_ref().take(self.column - 1) {
+            out.push(if c == '\t' { '\t' } else { ' ' });
+        }
+        let underline = chars
+            .as_str()
+            .get(..self.len.min(chars.as_str().len()))
+            .map_or(0, |s| s.chars().count());
+        out.push_str(&"^".repeat(underline.max(1)));
+        out
+    }
+}
+
+impl core::fmt::Display for Span {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.column == 0 {
+            write!(f, "line {}", self.line)
+        } else {
+            write!(f, "line {}, column {}", self.line, self.column)
+        }
+    }
+}
+
+/// `path: message at span`, omitting whatever is unknown.
+struct DataDisplay<'a> {
+    path: &'a str,
+    message: &'a str,
+    span: &'a Option<Span>,
+}
+
+impl core::fmt::Display for DataDisplay<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        f.write_str(self.message)?;
+        if let Some(span) = self.span {
+            write!(f, " at {}", span)?;
+        }
+        Ok(())
+    }
+}
+
+fn floor_char_boundary(s: &str, mut i: usize) -> usize {
+    while !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+/// Category of an [`Error`], for callers that branch on the kind of failure
+/// rather than its message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Malformed input not covered by a more specific kind
+    Syntax,
+    /// A declared `[N]` length or header field count disagrees with the data
+    LengthMismatch,
+    /// A row or header uses a delimiter other than the one declared
+    DelimiterMismatch,
+    /// Tabs in indentation, uneven indentation, or blank lines inside an array
+    Indentation,
+    /// A quoted string is missing its closing quote
+    UnterminatedString,
+    /// A backslash escape that TOON does not define
+    InvalidEscape,
+    /// The same key appears twice where keys must be unique
+    DuplicateKey,
+    /// Expanding dotted keys would merge an object with a non-object
+    PathExpansionConflict,
+    /// The input is valid TOON but does not fit the requested type
+    TypeMismatch,
+    /// Decoding the encoded text does not give back the encoded value
+    RoundTrip,
+    /// Even the smallest shrunk document is over the byte or token budget
+    BudgetExceeded,
+    /// Reading or writing failed
+    Io,
+    /// Anything else, including custom serde messages
+    Other,
+}
+
+impl ErrorKind {
+    /// A stable kebab-case name such as `length-mismatch`, for use as a rule
+    /// id in machine-readable reports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Syntax => "syntax",
+            ErrorKind::LengthMismatch => "length-mismatch",
+            ErrorKind::DelimiterMismatch => "delimiter-mismatch",
+            ErrorKind::Indentation => "indentation",
+            ErrorKind::UnterminatedString => "unterminated-string",
+            ErrorKind::InvalidEscape => "invalid-escape",
+            ErrorKind::DuplicateKey => "duplicate-key",
+            ErrorKind::PathExpansionConflict => "path-expansion-conflict",
+            ErrorKind::TypeMismatch => "type-mismatch",
+            ErrorKind::RoundTrip => "round-trip",
+            ErrorKind::BudgetExceeded => "budget-exceeded",
+            ErrorKind::Io => "io",
+            ErrorKind::Other => "other",
+        }
+    }
+}
+
+impl core::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[cfg(feature = "std")]
 #[derive(Debug, Error)]
 pub enum Error {
@@ -14,8 +210,26 @@ pub enum Error {
     #[error("serde_json error: {0}")]
     SerdeJson(#[from] serde_json::Error),
 
-    #[error("syntax at line {line}: {message}")]
-    Syntax { line: usize, message: String },
+    /// `line` repeats `span.line`.
+    #[error("syntax at {span}: {message}")]
+    Syntax {
+        kind: ErrorKind,
+        line: usize,
+        message: String,
+        span: Span,
+    },
+
+    /// A categorized failure that is not a syntax error, such as a type
+    /// mismatch. `path` locates the offending value in the document (for
+    /// example `users[3].email`) and is empty at the root; `span` is known
+    /// when the value was decoded from text.
+    #[error("{}", DataDisplay { path, message, span })]
+    Data {
+        kind: ErrorKind,
+        message: String,
+        path: String,
+        span: Option<Span>,
+    },
 
     #[error("{0}")]
     Message(String),
@@ -25,20 +239,304 @@ pub enum Error {
 #[derive(Debug)]
 pub enum Error {
     Syntax {
+        kind: ErrorKind,
         line: usize,
-        message: alloc::string::String,
+        message: String,
+        span: Span,
+    },
+    Data {
+        kind: ErrorKind,
+        message: String,
+        path: String,
+        span: Option<Span>,
     },
-    Message(alloc::string::String),
+    Message(String),
 }
 
 #[cfg(not(feature = "std"))]
 impl core::fmt::Display for Error {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            Error::Syntax { line, message } => write!(f, "syntax at line {}: {}", line, message),
+            Error::Syntax { message, span, .. } => write!(f, "syntax at {}: {}", span, message),
+            Error::Data {
+                message,
+                path,
+                span,
+                ..
+            } => DataDisplay {
+                path,
+                message,
+                span,
+            }
+            .fmt(f),
             Error::Message(m) => f.write_str(m),
         }
     }
 }
 
+#[cfg(not(feature = "std"))]
+impl core::error::Error for Error {}
+
+impl Error {
+    pub fn syntax(kind: ErrorKind, span: Span, message: impl Into<String>) -> Self {
+        Error::Syntax {
+            kind,
+            line: span.line,
+            message: message.into(),
+            span,
+        }
+    }
+
+    /// A positionless error of `kind`; [`ErrorKind::Other`] gives `Error::Message`.
+    pub fn data(kind: ErrorKind, message: impl Into<String>) -> Self {
+        match kind {
+            ErrorKind::Other => Error::Message(message.into()),
+            kind => Error::Data {
+                kind,
+                message: message.into(),
+                path: String::new(),
+                span: None,
+            },
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            #[cfg(feature = "std")]
+            Error::Io(_) => ErrorKind::Io,
+            #[cfg(all(feature = "serde", feature = "json"))]
+            Error::SerdeJson(e) => match e.classify() {
+                serde_json::error::Category::Io => ErrorKind::Io,
+                serde_json::error::Category::Data => ErrorKind::TypeMismatch,
+                _ => ErrorKind::Syntax,
+            },
+            Error::Syntax { kind, .. } | Error::Data { kind, .. } => *kind,
+            Error::Message(_) => ErrorKind::Other,
+        }
+    }
+
+    /// Where the error occurred in the input, if known.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Error::Syntax { span, .. } => Some(*span),
+            Error::Data { span, .. } => *span,
+            _ => None,
+        }
+    }
+
+    /// Document path of the value that failed to deserialize, such as
+    /// `users[3].email`; `None` for errors not tied to a value, and `Some("")`
+    /// for the root.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Error::Data { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    /// Prefix the path of a value error with object key `key`.
+    pub(crate) fn in_key(self, key: &str) -> Self {
+        self.prefix_path(|path, nested| {
+            let mut out = path_key(key);
+            if nested && !path.starts_with('[') {
+                out.push('.');
+            }
+            out.push_str(path);
+            out
+        })
+    }
+
+    #[cfg(feature = "serde")]
+    /// Prefix the path of a value error with array index `index`.
+    pub(crate) fn in_index(self, index: usize) -> Self {
+        self.prefix_path(|path, nested| {
+            let sep = if nested && !path.starts_with('[') {
+                "."
+            } else {
+                ""
+            };
+            format!("[{}]{}{}", index, sep, path)
+        })
+    }
+
+    #[cfg(feature = "serde")]
+    /// Locate a value error at `at`, unless it is already located.
+    pub(crate) fn at(self, at: Span) -> Self {
+        match self.into_value_error() {
+            Error::Data {
+                kind,
+                message,
+                path,
+                span,
+            } => Error::Data {
+                kind,
+                message,
+                path,
+                span: span.or(Some(at)),
+            },
+            e => e,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    fn prefix_path(self, prefix: impl FnOnce(&str, bool) -> String) -> Self {
+        match self.into_value_error() {
+            Error::Data {
+                kind,
+                message,
+                path,
+                span,
+            } => Error::Data {
+                kind,
+                message,
+                path: prefix(&path, !path.is_empty()),
+                span,
+            },
+            e => e,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    /// Custom serde messages are value errors too once they have a location.
+    fn into_value_error(self) -> Self {
+        match self {
+            Error::Message(message) => Error::Data {
+                kind: ErrorKind::Other,
+                message,
+                path: String::new(),
+                span: None,
+            },
+            e => e,
+        }
+    }
+
+    /// The offending line of `source` with a caret under the error, if this is a
+    /// syntax error; `source` must be the decoded input.
+    pub fn snippet(&self, source: &str) -> Option<String> {
+        self.span().map(|span| span.snippet(source))
+    }
+}
+
+/// `key` as a segment of a document path: as is when it is a plain word,
+/// quoted otherwise.
+#[cfg(feature = "serde")]
+pub(crate) fn path_key(key: &str) -> String {
+    let plain = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    if plain {
+        String::from(key)
+    } else {
+        crate::encode::primitives::escape_and_quote(key)
+    }
+}
+
+/// Categorize one of serde's standard errors, keeping serde's wording.
+#[cfg(feature = "serde")]
+fn serde_data(kind: ErrorKind, e: serde::de::value::Error) -> Error {
+    Error::data(kind, e.to_string())
+}
+
+#[cfg(feature = "serde")]
+impl serde::de::Error for Error {
+    fn custom<T: core::fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+
+    fn invalid_type(unexp: serde::de::Unexpected<'_>, exp: &dyn serde::de::Expected) -> Self {
+        serde_data(
+            ErrorKind::TypeMismatch,
+            serde::de::Error::invalid_type(unexp, exp),
+        )
+    }
+
+    fn invalid_value(unexp: serde::de::Unexpected<'_>, exp: &dyn serde::de::Expected) -> Self {
+        serde_data(
+            ErrorKind::TypeMismatch,
+            serde::de::Error::invalid_value(unexp, exp),
+        )
+    }
+
+    fn invalid_length(len: usize, exp: &dyn serde::de::Expected) -> Self {
+        serde_data(
+            ErrorKind::TypeMismatch,
+            serde::de::Error::invalid_length(len, exp),
+        )
+    }
+
+    fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+        serde_data(
+            ErrorKind::TypeMismatch,
+            serde::de::Error::unknown_variant(variant, expected),
+        )
+    }
+
+    fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+        serde_data(
+            ErrorKind::TypeMismatch,
+            serde::de::Error::unknown_field(field, expected),
+        )
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        serde_data(
+            ErrorKind::TypeMismatch,
+            serde::de::Error::missing_field(field),
+        )
+    }
+
+    fn duplicate_field(field: &'static str) -> Self {
+        serde_data(
+            ErrorKind::DuplicateKey,
+            serde::de::Error::duplicate_field(field),
+        )
+    }
+}
+
+/// Implement serde's categorized constructors for a `de::Error` type that is
+/// `From<Error>`, so its [`ErrorKind`] matches what [`Error`] would report.
+#[cfg(feature = "serde")]
+macro_rules! forward_de_error_kinds {
+    () => {
+        fn invalid_type(unexp: serde::de::Unexpected<'_>, exp: &dyn serde::de::Expected) -> Self {
+            <$crate::Error as serde::de::Error>::invalid_type(unexp, exp).into()
+        }
+
+        fn invalid_value(unexp: serde::de::Unexpected<'_>, exp: &dyn serde::de::Expected) -> Self {
+            <$crate::Error as serde::de::Error>::invalid_value(unexp, exp).into()
+        }
+
+        fn invalid_length(len: usize, exp: &dyn serde::de::Expected) -> Self {
+            <$crate::Error as serde::de::Error>::invalid_length(len, exp).into()
+        }
+
+        fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+            <$crate::Error as serde::de::Error>::unknown_variant(variant, expected).into()
+        }
+
+        fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+            <$crate::Error as serde::de::Error>::unknown_field(field, expected).into()
+        }
+
+        fn missing_field(field: &'static str) -> Self {
+            <$crate::Error as serde::de::Error>::missing_field(field).into()
+        }
+
+        fn duplicate_field(field: &'static str) -> Self {
+            <$crate::Error as serde::de::Error>::duplicate_field(field).into()
+        }
+    };
+}
+#[cfg(feature = "serde")]
+pub(crate) use forward_de_error_kinds;
+
+#[cfg(feature = "serde")]
+impl serde::ser::Error for Error {
+    fn custom<T: core::fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
 pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,83 @@
+//! [`fmt`], which rewrites a TOON document in canonical layout
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+use crate::decode::scanner::indent_step;
+use crate::options::{Delimiter, ExpandPaths, KeyFolding, Options};
+use crate::value::Value;
+
+/// Layout [`fmt`] writes documents in.
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    /// Delimiter for inline arrays and table rows
+    pub delimiter: Delimiter,
+    /// Spaces per indentation level
+    pub indent: usize,
+    /// Whether to fold chains of single-key objects into dotted keys
+    pub key_folding: KeyFolding,
+    /// Most segments a folded key may have (None = unlimited)
+    pub flatten_depth: Option<usize>,
+    /// Refuse input that only strict mode rejects
+    pub strict: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions::from(&Options::default())
+    }
+}
+
+impl From<&Options> for FormatOptions {
+    fn from(options: &Options) -> Self {
+        FormatOptions {
+            delimiter: options.delimiter,
+            indent: options.indent,
+            key_folding: options.key_folding,
+            flatten_depth: options.flatten_depth,
+            strict: options.strict,
+        }
+    }
+}
+
+impl FormatOptions {
+    fn options(&self, indent: usize) -> Options {
+        Options {
+            delimiter: self.delimiter,
+            strict: self.strict,
+            indent,
+            key_folding: self.key_folding,
+            flatten_depth: self.flatten_depth,
+            // Dotted keys stay keys; expanding them would change the document
+            expand_paths: ExpandPaths::Off,
+        }
+    }
+}
+
+/// Rewrite `input` the way the encoder would write its value.
+///
+/// The input may use any indentation step; the output uses `options.indent`
+/// spaces per level. Strings are quoted only where
+/// [`needs_quotes`](crate::encode::primitives::needs_quotes) says so, arrays
+/// use `options.delimiter`, and arrays of objects with the same primitive
+/// fields become tables. Key order and values are kept. Like the encoder, the
+/// result has no trailing newline.
+///
+/// ```
+/// let messy = "users[2]:\n    - id: 1\n      name: \"Ada\"\n    - id: 2\n      name: Bob\n";
+/// let tidy = toon_rs::fmt(messy, &Default::default())?;
+/// assert_eq!(tidy, "users[2]{id,name}:\n  1,Ada\n  2,Bob");
+/// # Ok::<(), toon_rs::Error>(())
+/// ```
+pub fn fmt(input: &str, options: &FormatOptions) -> crate::Result<String> {
+    // Read the input with its own indentation
+    let step = indent_step(input).unwrap_or(options.indent);
+    let value: Value = crate::from_str(input, &options.options(step))?;
+    crate::encode_to_string(&value, &options.options(options.indent))
+}
+
+/// Whether `input` is what [`fmt`] makes of it, give or take a final newline.
+pub fn is_formatted(input: &str, options: &FormatOptions) -> crate::Result<bool> {
+    let formatted = fmt(input, options)?;
+    Ok(input.strip_suffix('\n').unwrap_or(input) == formatted)
+}
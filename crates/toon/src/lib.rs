@@ -4,6 +4,9 @@
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
+#[macro_use]
+mod macros;
+
 pub mod encode;
 pub mod error;
 pub(crate) mod number;
@@ -12,22 +15,69 @@ pub mod value;
 
 pub mod decode;
 
+#[cfg(feature = "serde")]
+pub mod budget;
+#[cfg(feature = "serde")]
+pub mod csv;
 #[cfg(feature = "serde")]
 pub mod de;
 #[cfg(feature = "serde")]
+pub mod edit;
+#[cfg(feature = "serde")]
+mod format;
+#[cfg(feature = "serde")]
+mod optimize;
+#[cfg(feature = "serde")]
+pub mod repair;
+#[cfg(feature = "serde")]
 pub mod ser;
+#[cfg(feature = "serde")]
+mod spanned;
+#[cfg(feature = "serde")]
+pub mod stats;
+#[cfg(feature = "serde")]
+mod verify;
 
-pub use crate::error::{Error, Result};
+pub use crate::decode::diagnostic::{Diagnostic, Severity, validate};
+#[cfg(feature = "serde")]
+pub use crate::decode::partial::{Open, OpenKind, Partial, PartialParser};
+pub use crate::error::{Error, ErrorKind, Result, Span};
+#[cfg(feature = "serde")]
+pub use crate::format::{FormatOptions, fmt, is_formatted};
+#[cfg(feature = "serde")]
+pub use crate::optimize::encode_optimized;
 pub use crate::options::{Delimiter, ExpandPaths, KeyFolding, Options};
+#[cfg(feature = "serde")]
+pub use crate::spanned::Spanned;
+pub use crate::value::{Map, Value};
+#[cfg(feature = "serde")]
+pub use crate::value::{from_value, to_value};
+#[cfg(feature = "serde")]
+pub use crate::verify::{encode_verified, verify_roundtrip};
+
+#[cfg(all(feature = "serde", feature = "std"))]
+pub use crate::de::TableReader;
+#[cfg(feature = "serde")]
+pub use crate::ser::TableWriter;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(not(feature = "std"))]
+    pub use alloc::vec;
+    #[cfg(feature = "std")]
+    pub use std::vec;
+}
 
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
 
 #[cfg(feature = "std")]
-use std::io::{Read, Write};
+use std::io::{BufReader, Read, Write};
 
+#[cfg(all(feature = "serde", feature = "std"))]
+use serde::de::DeserializeOwned;
 #[cfg(feature = "serde")]
-use serde::{Serialize, de::DeserializeOwned};
+use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "serde")]
 pub fn encode_to_string<T: Serialize>(value: &T, options: &Options) -> Result<String> {
@@ -36,27 +86,30 @@ pub fn encode_to_string<T: Serialize>(value: &T, options: &Options) -> Result<St
 
 #[cfg(all(feature = "serde", feature = "std"))]
 pub fn encode_to_writer<W: Write, T: Serialize>(
-    mut writer: W,
+    writer: W,
     value: &T,
     options: &Options,
 ) -> Result<()> {
-    let s = encode_to_string(value, options)?;
-    writer.write_all(s.as_bytes())?;
-    Ok(())
+    crate::ser::to_writer_streaming(writer, value, options)
 }
 
-// Decoding helpers require the json (serde_json) feature
+/// Decode `s` into any `T: Deserialize<'de>`.
+///
+/// Keys and strings that need no unescaping are borrowed from `s`, so `&'de str`
+/// and `Cow<'de, str>` fields work without allocating. With
+/// [`ExpandPaths::Safe`] the document is expanded first and strings are owned.
 #[cfg(feature = "serde")]
-pub fn decode_from_str<T: DeserializeOwned + 'static>(s: &str, options: &Options) -> Result<T> {
+pub fn decode_from_str<'de, T: Deserialize<'de>>(s: &'de str, options: &Options) -> Result<T> {
     crate::de::from_str(s, options)
 }
 
+#[cfg(feature = "serde")]
+pub use crate::de::from_str;
+
+/// Decode from a reader incrementally; lines are consumed as values are produced.
+///
+/// The reader is buffered internally; use [`de::from_reader`] for an existing `BufRead`.
 #[cfg(all(feature = "serde", feature = "std"))]
-pub fn decode_from_reader<R: Read, T: DeserializeOwned + 'static>(
-    mut reader: R,
-    options: &Options,
-) -> Result<T> {
-    let mut s = String::new();
-    reader.read_to_string(&mut s)?;
-    decode_from_str(&s, options)
+pub fn decode_from_reader<R: Read, T: DeserializeOwned>(reader: R, options: &Options) -> Result<T> {
+    crate::de::from_reader(BufReader::new(reader), options)
 }
@@ -0,0 +1,124 @@
+/// Build a [`Value`](crate::value::Value) from JSON-like syntax.
+///
+/// Keys are string literals or parenthesised expressions; values are literals,
+/// nested arrays/objects, or any expression convertible with `Value::from`.
+///
+/// ```
+/// use toon_rs::toon;
+///
+/// let name = "Ada";
+/// let v = toon!({
+///     "name": name,
+///     "tags": ["admin", "ops"],
+///     "active": true,
+///     "manager": null,
+/// });
+/// assert_eq!(v["tags"][1], "ops");
+/// assert_eq!(v.to_string(), "name: Ada\ntags[2]: admin,ops\nactive: true\nmanager: null");
+/// ```
+#[macro_export]
+macro_rules! toon {
+    ($($tt:tt)+) => {
+        $crate::toon_internal!($($tt)+)
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! toon_internal {
+    // Array elements, accumulated in `[$($elems,)*]`.
+    (@array [$($elems:expr,)*]) => {
+        $crate::__private::vec![$($elems,)*]
+    };
+    (@array [$($elems:expr),*]) => {
+        $crate::__private::vec![$($elems),*]
+    };
+    (@array [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::toon_internal!(@array [$($elems,)* $crate::toon_internal!(null)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::toon_internal!(@array [$($elems,)* $crate::toon_internal!(true)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::toon_internal!(@array [$($elems,)* $crate::toon_internal!(false)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::toon_internal!(@array [$($elems,)* $crate::toon_internal!([$($array)*])] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] {$($map:tt)*} $($rest:tt)*) => {
+        $crate::toon_internal!(@array [$($elems,)* $crate::toon_internal!({$($map)*})] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::toon_internal!(@array [$($elems,)* $crate::toon_internal!($next),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::toon_internal!(@array [$($elems,)* $crate::toon_internal!($last)])
+    };
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::toon_internal!(@array [$($elems,)*] $($rest)*)
+    };
+
+    // Object entries: `@object $map (key tts) (value tts) rest`.
+    (@object $object:ident () () ()) => {};
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $object.insert(($($key)+).into(), $value);
+        $crate::toon_internal!(@object $object () ($($rest)*) ($($rest)*));
+    };
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $object.insert(($($key)+).into(), $value);
+    };
+    (@object $object:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::toon_internal!(@object $object [$($key)+] ($crate::toon_internal!(null)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: true $($rest:tt)*) $copy:tt) => {
+        $crate::toon_internal!(@object $object [$($key)+] ($crate::toon_internal!(true)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: false $($rest:tt)*) $copy:tt) => {
+        $crate::toon_internal!(@object $object [$($key)+] ($crate::toon_internal!(false)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::toon_internal!(@object $object [$($key)+] ($crate::toon_internal!([$($array)*])) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: {$($map:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::toon_internal!(@object $object [$($key)+] ($crate::toon_internal!({$($map)*})) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::toon_internal!(@object $object [$($key)+] ($crate::toon_internal!($value)) , $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::toon_internal!(@object $object [$($key)+] ($crate::toon_internal!($value)));
+    };
+    (@object $object:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::toon_internal!(@object $object ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    // Entry points.
+    (null) => {
+        $crate::value::Value::Null
+    };
+    (true) => {
+        $crate::value::Value::Bool(true)
+    };
+    (false) => {
+        $crate::value::Value::Bool(false)
+    };
+    ([]) => {
+        $crate::value::Value::Array($crate::__private::vec![])
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::value::Value::Array($crate::toon_internal!(@array [] $($tt)+))
+    };
+    ({}) => {
+        $crate::value::Value::Object($crate::value::Map::new())
+    };
+    ({ $($tt:tt)+ }) => {
+        $crate::value::Value::Object({
+            let mut object = $crate::value::Map::new();
+            $crate::toon_internal!(@object object () ($($tt)+) ($($tt)+));
+            object
+        })
+    };
+    ($other:expr) => {
+        $crate::value::Value::from($other)
+    };
+}
@@ -0,0 +1,227 @@
+//! [`encode_optimized`] and [`Options::optimize_for`], which choose the
+//! layout that costs the fewest tokens
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use serde::Serialize;
+
+use crate::csv::table_fields;
+use crate::edit::Document;
+use crate::encode::primitives;
+use crate::error::{Result, path_key};
+use crate::options::{Delimiter, ExpandPaths, KeyFolding, Options};
+use crate::stats::{Member, Tokenizer};
+use crate::value::Value;
+use crate::verify::verify_roundtrip;
+
+const DELIMITERS: [Delimiter; 3] = [Delimiter::Comma, Delimiter::Tab, Delimiter::Pipe];
+
+impl Options {
+    /// These options with the delimiter and key folding that encode `value`
+    /// in the fewest tokens, as counted by `tokenizer`. Pass `str::len` to
+    /// count bytes instead.
+    ///
+    /// Every delimiter is tried with key folding off and on. A layout is only
+    /// chosen if its text decodes back to `value` (see [`verify_roundtrip`]),
+    /// and with folding on the result expands paths so that it does. Ties go
+    /// to the delimiter and folding of `self`.
+    ///
+    /// ```
+    /// use toon_rs::{Delimiter, Options, toon};
+    ///
+    /// let value = toon!({ "tags": ["a, b", "c, d"] });
+    /// let options = Options::default().optimize_for(&value, &str::len);
+    /// assert_eq!(options.delimiter, Delimiter::Tab);
+    /// assert_eq!(toon_rs::encode_to_string(&value, &options)?, "tags[2\t]: a, b\tc, d");
+    /// # Ok::<(), toon_rs::Error>(())
+    /// ```
+    pub fn optimize_for<T: Tokenizer + ?Sized>(&self, value: &Value, tokenizer: &T) -> Options {
+        let candidates = [self.key_folding, KeyFolding::Off, KeyFolding::Safe]
+            .into_iter()
+            .flat_map(|folding| {
+                [self.delimiter]
+                    .into_iter()
+                    .chain(DELIMITERS)
+                    .map(move |delimiter| (delimiter, folding))
+            });
+        let mut best: Option<(usize, Options)> = None;
+        for (delimiter, key_folding) in candidates {
+            let options = Options {
+                delimiter,
+                key_folding,
+                expand_paths: match key_folding {
+                    KeyFolding::Safe => ExpandPaths::Safe,
+                    KeyFolding::Off => ExpandPaths::Off,
+                },
+                ..self.clone()
+            };
+            let Ok(text) = crate::encode_to_string(value, &options) else {
+                continue;
+            };
+            if verify_roundtrip(value, &text, &options).is_err() {
+                continue;
+            }
+            let cost = tokenizer.count_tokens(&text);
+            if best.as_ref().is_none_or(|(least, _)| cost < *least) {
+                best = Some((cost, options));
+            }
+        }
+        best.map_or_else(|| self.clone(), |(_, options)| options)
+    }
+}
+
+/// Encode `value` in the fewest tokens `tokenizer` counts, and return the text
+/// with the options to decode it with.
+///
+/// The delimiter and key folding of the whole document come from
+/// [`Options::optimize_for`]. Then each array that is a table or inline list
+/// of primitives, at the root or under object keys, is written with whichever
+/// delimiter its header can declare, and a table also as a list of objects,
+/// if that is cheaper. The result is checked to decode back to `value` as
+/// [`encode_verified`](crate::encode_verified) does; if that fails, the
+/// per-array choices are dropped.
+///
+/// ```
+/// use toon_rs::{Options, Value, toon};
+///
+/// // Count bytes: tabs spare the quotes around the notes, but the ids are
+/// // shortest with the default comma
+/// let value = toon!({
+///     "notes": [{ "id": 1, "text": "a, b, c" }, { "id": 2, "text": "d, e" }],
+///     "ids": [1, 2, 3]
+/// });
+/// let (text, options) = toon_rs::encode_optimized(&value, &Options::default(), &str::len)?;
+/// assert_eq!(text, "notes[2\t]{id\ttext}:\n  1\ta, b, c\n  2\td, e\nids[3]: 1,2,3");
+/// assert_eq!(toon_rs::decode_from_str::<Value>(&text, &options)?, value);
+/// # Ok::<(), toon_rs::Error>(())
+/// ```
+pub fn encode_optimized<T: Serialize, K: Tokenizer + ?Sized>(
+    value: &T,
+    options: &Options,
+    tokenizer: &K,
+) -> Result<(String, Options)> {
+    let value = crate::to_value(value)?;
+    let options = options.optimize_for(&value, tokenizer);
+    let text = crate::encode_to_string(&value, &options)?;
+    let tuned = tune_arrays(&text, &options, tokenizer)?;
+    if tuned != text && verify_roundtrip(&value, &tuned, &options).is_ok() {
+        return Ok((tuned, options));
+    }
+    Ok((text, options))
+}
+
+/// `text` with each array rewritten in its cheapest layout.
+fn tune_arrays<K: Tokenizer + ?Sized>(
+    text: &str,
+    options: &Options,
+    tokenizer: &K,
+) -> Result<String> {
+    // Folded keys stay as written, so that paths lead to the lines in `text`
+    let written: Value = crate::from_str(
+        text,
+        &Options {
+            expand_paths: ExpandPaths::Off,
+            ..options.clone()
+        },
+    )?;
+    let options = Options {
+        key_folding: KeyFolding::Off,
+        ..options.clone()
+    };
+    if written.is_array() {
+        return Ok(cheapest(None, &written, &options, tokenizer)?.unwrap_or_else(|| text.into()));
+    }
+    let mut doc = Document::parse(text)?;
+    let mut pending = Vec::new();
+    collect_arrays(&written, "", &mut pending);
+    for (path, key, array) in pending {
+        if let Some(best) = cheapest(Some(key), array, &options, tokenizer)? {
+            doc.replace_field(&path, &best)?;
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// Arrays under object keys, with their paths and keys.
+fn collect_arrays<'v>(value: &'v Value, path: &str, out: &mut Vec<(String, &'v str, &'v Value)>) {
+    let Some(map) = value.as_object() else {
+        return;
+    };
+    for (key, value) in map.iter() {
+        let path = match path {
+            "" => path_key(key),
+            _ => format!("{}.{}", path, path_key(key)),
+        };
+        match value {
+            Value::Array(_) => out.push((path, key, value)),
+            Value::Object(_) => collect_arrays(value, &path, out),
+            _ => {}
+        }
+    }
+}
+
+/// The cheapest way to write `array` under `key`, if it is not how `options`
+/// write it.
+fn cheapest<K: Tokenizer + ?Sized>(
+    key: Option<&str>,
+    array: &Value,
+    options: &Options,
+    tokenizer: &K,
+) -> Result<Option<String>> {
+    let rows = array.as_array().map_or(&[][..], Vec::as_slice);
+    let tabular = table_fields(rows).is_some_and(|f| !f.is_empty());
+    if rows.is_empty() || !(tabular || rows.iter().all(Value::is_primitive)) {
+        return Ok(None);
+    }
+    let current = render(key, array, options)?;
+    let mut least = tokenizer.count_tokens(&current);
+    let mut best = None;
+    for delimiter in DELIMITERS {
+        let options = Options {
+            delimiter,
+            ..options.clone()
+        };
+        let mut layouts = Vec::from([render(key, array, &options)?]);
+        if tabular {
+            layouts.push(render_list(key, rows, &options)?);
+        }
+        for text in layouts {
+            let cost = tokenizer.count_tokens(&text);
+            if cost < least {
+                least = cost;
+                best = Some(text);
+            }
+        }
+    }
+    Ok(best)
+}
+
+/// `array` as the encoder writes it, under `key` if there is one.
+fn render(key: Option<&str>, array: &Value, options: &Options) -> Result<String> {
+    match key {
+        Some(key) => crate::ser::to_string_streaming(&Member(key, array), options),
+        None => crate::ser::to_string_streaming(array, options),
+    }
+}
+
+/// `rows` as list items, which the encoder only writes when they are not a
+/// table.
+fn render_list(key: Option<&str>, rows: &[Value], options: &Options) -> Result<String> {
+    let mut out = format!(
+        "{}{}",
+        key.map(primitives::format_key).unwrap_or_default(),
+        primitives::format_expanded_array_header(rows.len(), options.delimiter)
+    );
+    for row in rows {
+        for line in crate::ser::list_item_lines(row, options)? {
+            out.push('\n');
+            out.push_str(&line);
+        }
+    }
+    Ok(out)
+}
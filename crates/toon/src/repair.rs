@@ -0,0 +1,485 @@
+//! Finding TOON in free text and fixing common mistakes in it
+//!
+//! Language models asked for TOON tend to wrap it in markdown fences and to
+//! get a few things wrong: `[N]` headers that miscount, a delimiter after the
+//! last value, indentation that drifts, and table cells that contain the
+//! delimiter but are not quoted. [`repair`] finds the TOON in a reply, fixes
+//! those mistakes and decodes the result.
+//!
+//! The problems are found by [`validate`] in strict mode, so a fix is only
+//! made where the decoder would reject the text. Each fix changes a single
+//! line and is listed in [`Repaired::fixes`]; lines are never added or
+//! removed. What cannot be fixed is left as it is, and the error from
+//! decoding it is returned.
+//!
+//! ```
+//! use toon_rs::repair::{self, FixKind, RepairOptions};
+//! use toon_rs::toon;
+//!
+//! let reply = "Here you go:\n\n```toon\nusers[3]{id,name}:\n  1,Ada,\n  2,Smith, John\n```\n";
+//! let repaired = repair::repair(reply, &RepairOptions::default())?;
+//! assert_eq!(repaired.text, "users[2]{id,name}:\n  1,Ada\n  2,\"Smith, John\"");
+//! let kinds: Vec<FixKind> = repaired.fixes.iter().map(|f| f.kind).collect();
+//! assert_eq!(kinds, [FixKind::Length, FixKind::TrailingDelimiter, FixKind::QuotedCell]);
+//! assert_eq!(
+//!     repaired.value,
+//!     toon!({ "users": [{ "id": 1, "name": "Ada" }, { "id": 2, "name": "Smith, John" }] })
+//! );
+//! # Ok::<(), toon_rs::Error>(())
+//! ```
+
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::ops::Range;
+
+use crate::decode::diagnostic::validate;
+use crate::decode::parser::{
+    ArrayHeader, Scalar, find_unquoted_bracket, is_array_header_line, parse_array_header,
+    parse_scalar_token, split_delim_aware, split_kv_quote_aware,
+};
+use crate::encode::primitives::escape_and_quote;
+use crate::error::{Error, ErrorKind, Result, Span};
+use crate::options::Options;
+use crate::value::Value;
+
+/// Which mistakes [`repair`] fixes, and how the result is decoded.
+#[derive(Debug, Clone)]
+pub struct RepairOptions {
+    /// Options the text is checked and decoded with; it is always decoded in
+    /// strict mode, whatever `options.strict` says
+    pub options: Options,
+    /// Set the `N` of an array header to the number of elements that follow
+    pub fix_lengths: bool,
+    /// Drop a delimiter that ends a table row or an inline array, when it
+    /// leaves one value too many
+    pub trailing_delimiters: bool,
+    /// Re-indent the text by `options.indent` spaces per level when some lines
+    /// are indented by another amount or with tabs
+    pub fix_indentation: bool,
+    /// Quote adjacent cells of a table row that has too many values, when
+    /// they are strings that were split on the delimiter
+    pub quote_cells: bool,
+}
+
+impl Default for RepairOptions {
+    fn default() -> Self {
+        RepairOptions {
+            options: Options::default(),
+            fix_lengths: true,
+            trailing_delimiters: true,
+            fix_indentation: true,
+            quote_cells: true,
+        }
+    }
+}
+
+/// A kind of mistake [`repair`] fixes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FixKind {
+    /// An array header declared the wrong number of elements
+    Length,
+    /// A row or inline array ended in a delimiter
+    TrailingDelimiter,
+    /// A line was indented by the wrong amount or with tabs
+    Indentation,
+    /// Cells of a table row were one string containing the delimiter
+    QuotedCell,
+}
+
+/// One change made by [`repair`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub kind: FixKind,
+    /// Line of the TOON text that was changed, from 1
+    pub line: usize,
+    /// What was changed, such as `length 3 changed to 2`
+    pub message: String,
+}
+
+/// TOON found in free text, after repair.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repaired {
+    /// Byte range of the TOON text in the input, without any fences
+    pub range: Range<usize>,
+    /// The repaired text
+    pub text: String,
+    /// What was changed, in line order
+    pub fixes: Vec<Fix>,
+    /// The repaired text, decoded
+    pub value: Value,
+}
+
+/// Find the TOON in `input`, fix it and decode it.
+///
+/// Each region [`blocks`] finds is tried in turn, and the first that decodes
+/// after repair is returned. If none does, the error is the one from the first
+/// region; if there are none, the error has kind [`ErrorKind::Syntax`].
+pub fn repair(input: &str, options: &RepairOptions) -> Result<Repaired> {
+    let mut first = None;
+    for range in blocks(input) {
+        match fix(&input[range.clone()], options) {
+            Ok(repaired) => return Ok(Repaired { range, ..repaired }),
+            Err(e) => {
+                first.get_or_insert(e);
+            }
+        }
+    }
+    Err(first.unwrap_or_else(|| Error::data(ErrorKind::Syntax, "no TOON found in the text")))
+}
+
+/// Fix the TOON text `toon`, which is taken to be TOON throughout, and decode
+/// it. Line endings become `\n`.
+pub fn fix(toon: &str, options: &RepairOptions) -> Result<Repaired> {
+    let strict = Options {
+        strict: true,
+        ..options.options.clone()
+    };
+    let mut lines: Vec<String> = toon.lines().map(String::from).collect();
+    let mut fixes = Vec::new();
+    if options.fix_indentation
+        && validate(toon, &strict)
+            .iter()
+            .any(|d| d.kind == ErrorKind::Indentation)
+    {
+        reindent(&mut lines, strict.indent, &mut fixes);
+    }
+    // Every fix changes a line, and no line is changed many times over
+    for _ in 0..=4 * lines.len() {
+        let text = lines.join("\n");
+        let found = validate(&text, &strict).into_iter().find_map(|d| {
+            let at = d.span.line.checked_sub(1)?;
+            match d.kind {
+                ErrorKind::LengthMismatch | ErrorKind::DelimiterMismatch => {
+                    fix_line(&mut lines, at, options)
+                }
+                _ => None,
+            }
+        });
+        match found {
+            Some(fix) => fixes.push(fix),
+            None => break,
+        }
+    }
+    fixes.sort_by_key(|f| f.line);
+    let text = lines.join("\n");
+    let value = crate::from_str(&text, &strict)?;
+    Ok(Repaired {
+        range: 0..toon.len(),
+        text,
+        fixes,
+        value,
+    })
+}
+
+/// Byte ranges of the TOON in `input`, in order, without any fences.
+///
+/// These are the contents of fenced code blocks (```` ``` ```` or `~~~`)
+/// tagged `toon` or not tagged at all; a fence that is never closed runs to
+/// the end of `input`. Only if there are none, they are runs of lines outside
+/// code blocks that start at column 0 with a `key:` or array header and go on
+/// with indented lines or further such lines. A run must have an array
+/// header or more than one line, so that prose such as `Note: ...` is left
+/// out.
+pub fn blocks(input: &str) -> Vec<Range<usize>> {
+    let lines = line_ranges(input);
+    let mut fenced = Vec::new();
+    // Lines outside fenced blocks, tagged or not
+    let mut prose = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &input[lines[i].clone()];
+        let Some((fence, info)) = opening_fence(line) else {
+            prose.push(lines[i].clone());
+            i += 1;
+            continue;
+        };
+        let body = i + 1;
+        let mut end = body;
+        while end < lines.len() && !closes(&input[lines[end].clone()], fence) {
+            end += 1;
+        }
+        if info.is_empty() || info.eq_ignore_ascii_case("toon") {
+            let start = lines.get(body).map_or(input.len(), |r| r.start);
+            let stop = match end {
+                _ if end == body => start,
+                _ => lines[end - 1].end,
+            };
+            fenced.push(start..stop);
+        }
+        i = end + 1;
+    }
+    if !fenced.is_empty() {
+        return fenced;
+    }
+
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < prose.len() {
+        if !starts_toon(&input[prose[i].clone()]) {
+            i += 1;
+            continue;
+        }
+        let first = i;
+        let mut last = i;
+        let mut j = i + 1;
+        while j < prose.len() {
+            let line = &input[prose[j].clone()];
+            if line.trim().is_empty() {
+                j += 1;
+            } else if line.starts_with([' ', '\t']) || starts_toon(line) {
+                last = j;
+                j += 1;
+            } else {
+                break;
+            }
+        }
+        let head = &input[prose[first].clone()];
+        if last > first || is_array_header_line(head) {
+            found.push(prose[first].start..prose[last].end);
+        }
+        i = last + 1;
+    }
+    found
+}
+
+/// Byte ranges of the lines of `input`, without line endings.
+fn line_ranges(input: &str) -> Vec<Range<usize>> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    for line in input.split_inclusive('\n') {
+        let text = line.strip_suffix('\n').unwrap_or(line);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        out.push(start..start + text.len());
+        start += line.len();
+    }
+    out
+}
+
+/// The fence and info string of a line that opens a code block.
+fn opening_fence(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim_start_matches(' ');
+    let mark = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = line.len() - line.trim_start_matches(mark).len();
+    (len >= 3).then(|| (&line[..len], line[len..].trim()))
+}
+
+/// Whether `line` closes a code block opened by `fence`.
+fn closes(line: &str, fence: &str) -> bool {
+    let line = line.trim();
+    let mark = fence.as_bytes()[0] as char;
+    line.len() >= fence.len() && line.chars().all(|c| c == mark)
+}
+
+/// Whether `line` starts a run of bare TOON: an array header, or a `key:`
+/// whose key is quoted or a single word, at column 0.
+fn starts_toon(line: &str) -> bool {
+    if line.starts_with([' ', '\t']) {
+        return false;
+    }
+    if is_array_header_line(line) && parse_array_header(line).is_some() {
+        return true;
+    }
+    let Some((key, _)) = split_kv_quote_aware(line) else {
+        return false;
+    };
+    let word = key.starts_with('"')
+        || (!key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-')));
+    // The key is trimmed, so slice from where it actually ends
+    let end = key.as_ptr() as usize - line.as_ptr() as usize + key.len();
+    let rest = &line[end..];
+    word && (rest.starts_with(": ") || rest.trim_end() == ":")
+}
+
+/// Indent each line by `step` spaces per level, where the levels are the
+/// distinct indentations of the lines it is nested in. A tab counts as
+/// `step` spaces.
+fn reindent(lines: &mut [String], step: usize, fixes: &mut Vec<Fix>) {
+    let mut levels: Vec<usize> = Vec::new();
+    for (i, line) in lines.iter_mut().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let body = line.trim_start_matches([' ', '\t']);
+        let lead = &line[..line.len() - body.len()];
+        let width: usize = lead.chars().map(|c| if c == '\t' { step } else { 1 }).sum();
+        while levels.last().is_some_and(|&l| l > width) {
+            levels.pop();
+        }
+        if levels.last().is_none_or(|&l| l < width) {
+            levels.push(width);
+        }
+        let want = (levels.len() - 1) * step;
+        if lead.len() == want && !lead.contains('\t') {
+            continue;
+        }
+        let message = match lead.contains('\t') {
+            true => format!("tab indentation changed to {} spaces", want),
+            false => format!("indentation of {} changed to {}", lead.len(), want),
+        };
+        *line = format!("{}{}", " ".repeat(want), body);
+        fixes.push(Fix {
+            kind: FixKind::Indentation,
+            line: i + 1,
+            message,
+        });
+    }
+}
+
+/// Fix a count or delimiter problem reported at line `at`, which is either
+/// an array header or a table row.
+fn fix_line(lines: &mut [String], at: usize, options: &RepairOptions) -> Option<Fix> {
+    let (indent, body) = split_indent(&lines[at]);
+    match header(body) {
+        Some(head) => fix_header(lines, at, &head, options),
+        None => {
+            // A row belongs to the nearest line above it that is indented less
+            let owner = (0..at)
+                .rev()
+                .find(|&j| !lines[j].trim().is_empty() && split_indent(&lines[j]).0 < indent)?;
+            let head = header(split_indent(&lines[owner]).1)?;
+            fix_row(lines, at, &head, options)
+        }
+    }
+}
+
+/// Leading spaces of `line`, and the rest of it.
+fn split_indent(line: &str) -> (usize, &str) {
+    let body = line.trim_start_matches(' ');
+    (line.len() - body.len(), body)
+}
+
+/// The array header on a line, which may be a list item.
+fn header(body: &str) -> Option<ArrayHeader> {
+    let body = body.strip_prefix("- ").unwrap_or(body);
+    parse_array_header(body)
+}
+
+fn fix_header(
+    lines: &mut [String],
+    at: usize,
+    head: &ArrayHeader,
+    options: &RepairOptions,
+) -> Option<Fix> {
+    let found = match head
+        .inline_values
+        .as_deref()
+        .filter(|v| !v.trim().is_empty())
+    {
+        Some(values) => {
+            let cells = split_delim_aware(values, head.delimiter);
+            if options.trailing_delimiters && cells.len() > head.length && cells.last() == Some(&"")
+            {
+                return strip_trailing_delimiter(lines, at, head.delimiter);
+            }
+            cells.len()
+        }
+        None => children(lines, at, head.fields.is_some()),
+    };
+    if !options.fix_lengths || found == head.length {
+        return None;
+    }
+    let line = &lines[at];
+    let open = line.len() - line.trim_start_matches([' ', '-']).len();
+    let open = open + find_unquoted_bracket(&line[open..])? + 1;
+    let digits = line[open..].len()
+        - line[open..]
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    lines[at] = format!("{}{}{}", &line[..open], found, &line[open + digits..]);
+    Some(Fix {
+        kind: FixKind::Length,
+        line: at + 1,
+        message: format!("length {} changed to {}", head.length, found),
+    })
+}
+
+/// How many rows (for a table) or list items follow the header at `at`: the
+/// lines at the indentation of the first line nested under it.
+fn children(lines: &[String], at: usize, table: bool) -> usize {
+    let indent = split_indent(&lines[at]).0;
+    let mut level = None;
+    let mut count = 0;
+    for line in &lines[at + 1..] {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (depth, body) = split_indent(line);
+        if depth <= indent {
+            break;
+        }
+        if *level.get_or_insert(depth) == depth && (table || body.starts_with('-')) {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn fix_row(
+    lines: &mut [String],
+    at: usize,
+    head: &ArrayHeader,
+    options: &RepairOptions,
+) -> Option<Fix> {
+    let fields = head.fields.as_ref()?.len();
+    let (indent, row) = split_indent(&lines[at]);
+    let cells = split_delim_aware(row, head.delimiter);
+    if cells.len() <= fields {
+        return None;
+    }
+    if options.trailing_delimiters && cells.last() == Some(&"") {
+        return strip_trailing_delimiter(lines, at, head.delimiter);
+    }
+    if !options.quote_cells {
+        return None;
+    }
+    // The leftmost run of bare strings that makes up the values too many
+    let run = cells.len() - fields + 1;
+    let first = (0..=cells.len() - run).find(|&i| {
+        cells[i..i + run].iter().all(|c| {
+            !c.is_empty()
+                && matches!(parse_scalar_token(c), Ok(Scalar::Str(_)))
+                && !c.starts_with('"')
+        })
+    })?;
+    let start = Span::of(row, cells[first])?.offset;
+    let last = cells[first + run - 1];
+    let end = Span::of(row, last)?.offset + last.len();
+    let merged = &row[start..end];
+    let message = format!(
+        "quoted {}, which contains the delimiter",
+        escape_and_quote(merged)
+    );
+    lines[at] = format!(
+        "{}{}{}{}",
+        " ".repeat(indent),
+        &row[..start],
+        escape_and_quote(merged),
+        &row[end..]
+    );
+    Some(Fix {
+        kind: FixKind::QuotedCell,
+        line: at + 1,
+        message,
+    })
+}
+
+fn strip_trailing_delimiter(lines: &mut [String], at: usize, delimiter: char) -> Option<Fix> {
+    let line = lines[at].trim_end_matches(' ');
+    let kept = line
+        .strip_suffix(delimiter)?
+        .trim_end_matches(' ')
+        .to_string();
+    lines[at] = kept;
+    Some(Fix {
+        kind: FixKind::TrailingDelimiter,
+        line: at + 1,
+        message: "removed the delimiter after the last value".to_string(),
+    })
+}
@@ -1,8 +1,11 @@
 //! Serde encoding helpers for TOON
 
 #[cfg(not(feature = "std"))]
-use alloc::string::String;
+use alloc::{string::String, vec::Vec};
 
+use crate::encode::writer::FmtSink;
+#[cfg(feature = "std")]
+use crate::encode::writer::IoSink;
 use crate::{Result, options::Options};
 
 #[cfg(feature = "serde")]
@@ -10,10 +13,11 @@ use serde::Serialize;
 
 // serde_json Value-based builder is only available with feature "json"
 mod stream;
+mod table;
 #[cfg(all(feature = "serde", feature = "json"))]
 mod value_builder;
-#[cfg(all(feature = "serde", not(feature = "json")))]
-mod value_builder_alloc;
+
+pub use table::TableWriter;
 
 // Non-streaming encoding via serde_json::Value is only available with "json"
 #[cfg(all(feature = "serde", feature = "json"))]
@@ -25,12 +29,12 @@ pub fn to_string<T: Serialize>(value: &T, options: &Options) -> Result<String> {
 // Writer variant requires std
 #[cfg(all(feature = "serde", feature = "json", feature = "std"))]
 pub fn to_writer<W: std::io::Write, T: Serialize>(
-    mut writer: W,
+    writer: W,
     value: &T,
     options: &Options,
 ) -> Result<()> {
-    let s = to_string(value, options)?;
-    std::io::Write::write_all(&mut writer, s.as_bytes())?;
+    let v = value_builder::to_value(value, options);
+    crate::encode::encode_value_to_sink(&v, options, IoSink(writer))?;
     Ok(())
 }
 
@@ -39,13 +43,45 @@ pub fn to_string_streaming<T: Serialize>(value: &T, options: &Options) -> Result
     stream::to_string_streaming(value, options)
 }
 
+/// The lines of `item` as an item of a root list array: `- ` at one level of
+/// indentation, then any deeper lines. Writing them under a `[N]:` header of
+/// your own gives the list the encoder writes when the items are not a table.
+///
+/// ```
+/// let opts = toon_rs::Options::default();
+/// let lines = toon_rs::ser::list_item_lines(&serde_json::json!({"a": 1, "b": [2]}), &opts)?;
+/// assert_eq!(lines, ["  - a: 1", "    b[1]: 2"]);
+/// # Ok::<(), toon_rs::Error>(())
+/// ```
+#[cfg(feature = "serde")]
+pub fn list_item_lines<T: ?Sized + Serialize>(item: &T, options: &Options) -> Result<Vec<String>> {
+    // `[item, []]` is always written as a list, whose last line is `- [0]:`
+    let text = stream::to_string_streaming(&(item, [(); 0]), options)?;
+    let lines: Vec<&str> = text.split('\n').collect();
+    Ok(lines[1..lines.len() - 1]
+        .iter()
+        .map(|l| String::from(*l))
+        .collect())
+}
+
+/// Stream lines into `writer` as they are produced (buffered in small chunks).
 #[cfg(all(feature = "serde", feature = "std"))]
 pub fn to_writer_streaming<W: std::io::Write, T: Serialize>(
-    mut writer: W,
+    writer: W,
+    value: &T,
+    options: &Options,
+) -> Result<()> {
+    stream::to_sink_streaming(value, options, IoSink(writer))?;
+    Ok(())
+}
+
+/// Like [`to_writer_streaming`], for `core::fmt::Write` targets (available without `std`).
+#[cfg(feature = "serde")]
+pub fn to_fmt_writer_streaming<W: core::fmt::Write, T: Serialize>(
+    writer: W,
     value: &T,
     options: &Options,
 ) -> Result<()> {
-    let s = to_string_streaming(value, options)?;
-    std::io::Write::write_all(&mut writer, s.as_bytes())?;
+    stream::to_sink_streaming(value, options, FmtSink(writer))?;
     Ok(())
 }
@@ -4,7 +4,10 @@ use serde::ser::*;
 #[cfg(feature = "json")]
 use serde_json::Value;
 
-use crate::encode::{primitives, writer::LineWriter};
+use crate::encode::{
+    primitives,
+    writer::{LineWriter, Sink},
+};
 use crate::options::Options;
 #[cfg(not(feature = "json"))]
 use crate::value::Value as IValue;
@@ -21,15 +24,37 @@ use std::{format, string::String, vec::Vec};
 
 pub fn to_string_streaming<T: Serialize>(value: &T, options: &Options) -> crate::Result<String> {
     let mut w = LineWriter::new();
+    serialize_into(value, options, &mut w)?;
+    Ok(w.into_string())
+}
+
+/// Serialize into `sink`, handing lines over as they are produced.
+pub fn to_sink_streaming<T: Serialize, S: Sink>(
+    value: &T,
+    options: &Options,
+    sink: S,
+) -> crate::Result<S> {
+    let mut w = LineWriter::with_sink(sink);
+    match serialize_into(value, options, &mut w) {
+        // Encoding stops at a failed write, whose error `finish` gives
+        Err(e) if w.check().is_ok() => Err(e),
+        _ => w.finish(),
+    }
+}
+
+fn serialize_into<T: Serialize, S: Sink>(
+    value: &T,
+    options: &Options,
+    w: &mut LineWriter<S>,
+) -> crate::Result<()> {
     let mut ser = StreamingSerializer {
-        w: &mut w,
+        w,
         opts: options,
         indent: 0,
     };
     value
         .serialize(&mut ser)
-        .map_err(|e| crate::error::Error::Message(e.to_string()))?;
-    Ok(w.into_string())
+        .map_err(|e| crate::error::Error::Message(e.to_string()))
 }
 
 #[derive(Debug)]
@@ -52,14 +77,14 @@ impl serde::ser::Error for SerError {
 
 impl core::error::Error for SerError {}
 
-struct StreamingSerializer<'a> {
-    w: &'a mut LineWriter,
+struct StreamingSerializer<'a, S> {
+    w: &'a mut LineWriter<S>,
     opts: &'a Options,
     indent: usize,
 }
 
-impl<'a> StreamingSerializer<'a> {
-    fn with_indent<'b>(&'b mut self, indent: usize) -> StreamingSerializer<'b> {
+impl<'a, S: Sink> StreamingSerializer<'a, S> {
+    fn with_indent<'b>(&'b mut self, indent: usize) -> StreamingSerializer<'b, S> {
         StreamingSerializer {
             w: self.w,
             opts: self.opts,
@@ -68,33 +93,33 @@ impl<'a> StreamingSerializer<'a> {
     }
 }
 
-impl<'a, 'de> Serializer for &'a mut StreamingSerializer<'de> {
+impl<'a, 'de, S: Sink> Serializer for &'a mut StreamingSerializer<'de, S> {
     type Ok = ();
     type Error = SerError;
 
     #[cfg(feature = "json")]
-    type SerializeSeq = SeqSer<'a, 'de>;
+    type SerializeSeq = SeqSer<'a, 'de, S>;
     #[cfg(not(feature = "json"))]
-    type SerializeSeq = SeqSerAlloc<'a, 'de>;
+    type SerializeSeq = SeqSerAlloc<'a, 'de, S>;
 
     #[cfg(feature = "json")]
-    type SerializeTuple = SeqSer<'a, 'de>;
+    type SerializeTuple = SeqSer<'a, 'de, S>;
     #[cfg(not(feature = "json"))]
-    type SerializeTuple = SeqSerAlloc<'a, 'de>;
+    type SerializeTuple = SeqSerAlloc<'a, 'de, S>;
 
     #[cfg(feature = "json")]
-    type SerializeTupleStruct = SeqSer<'a, 'de>;
+    type SerializeTupleStruct = SeqSer<'a, 'de, S>;
     #[cfg(not(feature = "json"))]
-    type SerializeTupleStruct = SeqSerAlloc<'a, 'de>;
+    type SerializeTupleStruct = SeqSerAlloc<'a, 'de, S>;
 
     #[cfg(feature = "json")]
-    type SerializeTupleVariant = SeqSer<'a, 'de>;
+    type SerializeTupleVariant = SeqSer<'a, 'de, S>;
     #[cfg(not(feature = "json"))]
-    type SerializeTupleVariant = SeqSerAlloc<'a, 'de>;
+    type SerializeTupleVariant = SeqSerAlloc<'a, 'de, S>;
 
-    type SerializeMap = MapSer<'a, 'de>;
-    type SerializeStruct = MapSer<'a, 'de>;
-    type SerializeStructVariant = MapSer<'a, 'de>;
+    type SerializeMap = MapSer<'a, 'de, S>;
+    type SerializeStruct = MapSer<'a, 'de, S>;
+    type SerializeStructVariant = MapSer<'a, 'de, S>;
 
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
         self.w.line(self.indent, primitives::format_bool(v));
@@ -274,13 +299,13 @@ impl<'a, 'de> Serializer for &'a mut StreamingSerializer<'de> {
 }
 
 #[cfg(feature = "json")]
-struct SeqSer<'a, 'de> {
-    parent: &'a mut StreamingSerializer<'de>,
+struct SeqSer<'a, 'de, S> {
+    parent: &'a mut StreamingSerializer<'de, S>,
     items: Vec<Value>,
 }
 
 #[cfg(feature = "json")]
-impl<'a, 'de> SerializeSeq for SeqSer<'a, 'de> {
+impl<'a, 'de, S: Sink> SerializeSeq for SeqSer<'a, 'de, S> {
     type Ok = ();
     type Error = SerError;
 
@@ -303,6 +328,10 @@ impl<'a, 'de> SerializeSeq for SeqSer<'a, 'de> {
 
             // Rows at indent+2
             for item in &self.items {
+                self.parent
+                    .w
+                    .check()
+                    .map_err(|e| SerError::custom(e.to_string()))?;
                 let obj = item.as_object().unwrap();
                 let cells: Vec<String> = keys
                     .iter()
@@ -361,7 +390,7 @@ impl<'a, 'de> SerializeSeq for SeqSer<'a, 'de> {
 }
 
 #[cfg(feature = "json")]
-impl<'a, 'de> SerializeTuple for SeqSer<'a, 'de> {
+impl<'a, 'de, S: Sink> SerializeTuple for SeqSer<'a, 'de, S> {
     type Ok = ();
     type Error = SerError;
 
@@ -374,7 +403,7 @@ impl<'a, 'de> SerializeTuple for SeqSer<'a, 'de> {
 }
 
 #[cfg(feature = "json")]
-impl<'a, 'de> SerializeTupleStruct for SeqSer<'a, 'de> {
+impl<'a, 'de, S: Sink> SerializeTupleStruct for SeqSer<'a, 'de, S> {
     type Ok = ();
     type Error = SerError;
 
@@ -387,7 +416,7 @@ impl<'a, 'de> SerializeTupleStruct for SeqSer<'a, 'de> {
 }
 
 #[cfg(feature = "json")]
-impl<'a, 'de> SerializeTupleVariant for SeqSer<'a, 'de> {
+impl<'a, 'de, S: Sink> SerializeTupleVariant for SeqSer<'a, 'de, S> {
     type Ok = ();
     type Error = SerError;
 
@@ -400,18 +429,18 @@ impl<'a, 'de> SerializeTupleVariant for SeqSer<'a, 'de> {
 }
 
 #[cfg(not(feature = "json"))]
-struct SeqSerAlloc<'a, 'de> {
-    parent: &'a mut StreamingSerializer<'de>,
+struct SeqSerAlloc<'a, 'de, S> {
+    parent: &'a mut StreamingSerializer<'de, S>,
     items: Vec<IValue>,
 }
 
 #[cfg(not(feature = "json"))]
-impl<'a, 'de> SerializeSeq for SeqSerAlloc<'a, 'de> {
+impl<'a, 'de, S: Sink> SerializeSeq for SeqSerAlloc<'a, 'de, S> {
     type Ok = ();
     type Error = SerError;
 
     fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-        let v = crate::ser::value_builder_alloc::to_value(value, self.parent.opts);
+        let v = crate::value::to_value(value).map_err(|e| SerError::custom(e.to_string()))?;
         self.items.push(v);
         Ok(())
     }
@@ -429,6 +458,10 @@ impl<'a, 'de> SerializeSeq for SeqSerAlloc<'a, 'de> {
 
             // Rows at indent+2
             for item in &self.items {
+                self.parent
+                    .w
+                    .check()
+                    .map_err(|e| SerError::custom(e.to_string()))?;
                 let obj = match item {
                     IValue::Object(pairs) => pairs,
                     _ => unreachable!(),
@@ -436,7 +469,7 @@ impl<'a, 'de> SerializeSeq for SeqSerAlloc<'a, 'de> {
                 let cells: Vec<String> = keys
                     .iter()
                     .map(|k| {
-                        let v = obj.iter().find(|(kk, _)| kk == k).map(|(_, v)| v).unwrap();
+                        let v = obj.get(k).unwrap();
                         format_primitive_value_alloc(v, delim)
                     })
                     .collect();
@@ -490,43 +523,43 @@ impl<'a, 'de> SerializeSeq for SeqSerAlloc<'a, 'de> {
 }
 
 #[cfg(not(feature = "json"))]
-impl<'a, 'de> SerializeTuple for SeqSerAlloc<'a, 'de> {
+impl<'a, 'de, S: Sink> SerializeTuple for SeqSerAlloc<'a, 'de, S> {
     type Ok = ();
     type Error = SerError;
     fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
         SerializeSeq::serialize_element(self, value)
     }
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        SerializeSeq::end(self)
     }
 }
 
 #[cfg(not(feature = "json"))]
-impl<'a, 'de> SerializeTupleStruct for SeqSerAlloc<'a, 'de> {
+impl<'a, 'de, S: Sink> SerializeTupleStruct for SeqSerAlloc<'a, 'de, S> {
     type Ok = ();
     type Error = SerError;
     fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
         SerializeSeq::serialize_element(self, value)
     }
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        SerializeSeq::end(self)
     }
 }
 
 #[cfg(not(feature = "json"))]
-impl<'a, 'de> SerializeTupleVariant for SeqSerAlloc<'a, 'de> {
+impl<'a, 'de, S: Sink> SerializeTupleVariant for SeqSerAlloc<'a, 'de, S> {
     type Ok = ();
     type Error = SerError;
     fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
         SerializeSeq::serialize_element(self, value)
     }
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        SerializeSeq::end(self)
     }
 }
 
-struct MapSer<'a, 'de> {
-    parent: &'a mut StreamingSerializer<'de>,
+struct MapSer<'a, 'de, S> {
+    parent: &'a mut StreamingSerializer<'de, S>,
     next_key: Option<String>,
     entry_count: usize,
     /// Buffered entries for key folding collision detection
@@ -534,7 +567,7 @@ struct MapSer<'a, 'de> {
     buffered: Option<Vec<(String, Value)>>,
 }
 
-impl<'a, 'de> SerializeMap for MapSer<'a, 'de> {
+impl<'a, 'de, S: Sink> SerializeMap for MapSer<'a, 'de, S> {
     type Ok = ();
     type Error = SerError;
 
@@ -545,6 +578,10 @@ impl<'a, 'de> SerializeMap for MapSer<'a, 'de> {
     }
 
     fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.parent
+            .w
+            .check()
+            .map_err(|e| SerError::custom(e.to_string()))?;
         let key = self.next_key.take().unwrap_or_default();
 
         // When key folding is enabled, buffer entries for collision detection
@@ -599,7 +636,7 @@ impl<'a, 'de> SerializeMap for MapSer<'a, 'de> {
 
         #[cfg(not(feature = "json"))]
         {
-            let val = crate::ser::value_builder_alloc::to_value(value, self.parent.opts);
+            let val = crate::value::to_value(value).map_err(|e| SerError::custom(e.to_string()))?;
             if let IValue::Array(items) = val {
                 // Use spec-compliant keyed array encoding
                 encode_keyed_array_alloc(
@@ -648,7 +685,7 @@ impl<'a, 'de> SerializeMap for MapSer<'a, 'de> {
     }
 }
 
-impl<'a, 'de> SerializeStruct for MapSer<'a, 'de> {
+impl<'a, 'de, S: Sink> SerializeStruct for MapSer<'a, 'de, S> {
     type Ok = ();
     type Error = SerError;
 
@@ -666,7 +703,7 @@ impl<'a, 'de> SerializeStruct for MapSer<'a, 'de> {
     }
 }
 
-impl<'a, 'de> SerializeStructVariant for MapSer<'a, 'de> {
+impl<'a, 'de, S: Sink> SerializeStructVariant for MapSer<'a, 'de, S> {
     type Ok = ();
     type Error = SerError;
 
@@ -683,7 +720,7 @@ impl<'a, 'de> SerializeStructVariant for MapSer<'a, 'de> {
     }
 }
 
-fn join_with_delim(cells: &[String], dch: char) -> String {
+pub(super) fn join_with_delim(cells: &[String], dch: char) -> String {
     cells.join(&dch.to_string())
 }
 
@@ -698,7 +735,7 @@ fn is_primitive_array_json(items: &[Value]) -> bool {
 }
 
 #[cfg(feature = "json")]
-fn format_primitive_value_json(v: &Value, delim: crate::options::Delimiter) -> String {
+pub(super) fn format_primitive_value_json(v: &Value, delim: crate::options::Delimiter) -> String {
     match v {
         Value::Null => primitives::format_null().to_string(),
         Value::Bool(b) => primitives::format_bool(*b).to_string(),
@@ -709,9 +746,9 @@ fn format_primitive_value_json(v: &Value, delim: crate::options::Delimiter) -> S
 }
 
 #[cfg(feature = "json")]
-fn encode_list_item_json(
+fn encode_list_item_json<S: Sink>(
     item: &Value,
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<(), SerError> {
@@ -953,10 +990,10 @@ fn encode_list_item_json(
 
 /// Encode a keyed array with spec-compliant format: key[N]: v1,v2 or key[N]{fields}:
 #[cfg(feature = "json")]
-fn encode_keyed_array_json(
+fn encode_keyed_array_json<S: Sink>(
     key: &str,
     items: &[Value],
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<(), SerError> {
@@ -1039,7 +1076,7 @@ fn is_primitive_array_alloc(items: &[IValue]) -> bool {
 }
 
 #[cfg(not(feature = "json"))]
-fn format_primitive_value_alloc(v: &IValue, delim: crate::options::Delimiter) -> String {
+pub(super) fn format_primitive_value_alloc(v: &IValue, delim: crate::options::Delimiter) -> String {
     match v {
         IValue::Null => primitives::format_null().to_string(),
         IValue::Bool(b) => primitives::format_bool(*b).to_string(),
@@ -1050,9 +1087,9 @@ fn format_primitive_value_alloc(v: &IValue, delim: crate::options::Delimiter) ->
 }
 
 #[cfg(not(feature = "json"))]
-fn encode_list_item_alloc(
+fn encode_list_item_alloc<S: Sink>(
     item: &IValue,
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<(), SerError> {
@@ -1128,7 +1165,7 @@ fn encode_list_item_alloc(
                         let cells: Vec<String> = keys
                             .iter()
                             .map(|k| {
-                                let v = obj.iter().find(|(kk, _)| kk == k).map(|(_, v)| v).unwrap();
+                                let v = obj.get(k).unwrap();
                                 format_primitive_value_alloc(v, delim)
                             })
                             .collect();
@@ -1298,10 +1335,10 @@ fn encode_list_item_alloc(
 
 /// Encode a keyed array with spec-compliant format (alloc version)
 #[cfg(not(feature = "json"))]
-fn encode_keyed_array_alloc(
+fn encode_keyed_array_alloc<S: Sink>(
     key: &str,
     items: &[IValue],
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<(), SerError> {
@@ -1335,7 +1372,7 @@ fn encode_keyed_array_alloc(
             let cells: Vec<String> = keys
                 .iter()
                 .map(|k| {
-                    let v = obj.iter().find(|(kk, _)| kk == k).map(|(_, v)| v).unwrap();
+                    let v = obj.get(k).unwrap();
                     format_primitive_value_alloc(v, delim)
                 })
                 .collect();
@@ -1392,6 +1429,9 @@ fn is_tabular_array_alloc(arr: &[IValue]) -> Option<Vec<String>> {
             _ => return None,
         };
         let mut kset: Vec<String> = obj.iter().map(|(k, _)| k.clone()).collect();
+        if kset.is_empty() {
+            return None;
+        }
         kset.sort();
         if let Some(ref ks) = keys {
             if *ks != kset {
@@ -1410,9 +1450,9 @@ fn is_tabular_array_alloc(arr: &[IValue]) -> Option<Vec<String>> {
 }
 
 #[cfg(not(feature = "json"))]
-fn encode_internal_value_alloc(
+fn encode_internal_value_alloc<S: Sink>(
     v: &IValue,
-    w: &mut LineWriter,
+    w: &mut LineWriter<S>,
     opts: &Options,
     indent: usize,
 ) -> Result<(), SerError> {
@@ -0,0 +1,239 @@
+//! Row-at-a-time writer for tabular arrays
+//!
+//! The header (`key[N]{fields}:`) is written up front, so rows can be streamed
+//! from a cursor or iterator without collecting them first.
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+
+use serde::Serialize;
+
+use crate::encode::primitives;
+use crate::encode::writer::{LineWriter, Sink};
+use crate::error::{Error, ErrorKind, Result};
+use crate::options::{Delimiter, Options};
+
+#[cfg(not(feature = "json"))]
+use super::stream::format_primitive_value_alloc as format_cell;
+#[cfg(feature = "json")]
+use super::stream::format_primitive_value_json as format_cell;
+use super::stream::join_with_delim;
+
+/// Streaming writer for a single `key[N]{fields}:` table.
+///
+/// The fields must be non-empty and distinct. Each row must serialize to an
+/// object with exactly the declared fields and primitive values; exactly `N`
+/// rows must be written before [`TableWriter::finish`].
+///
+/// ```
+/// # use serde::Serialize;
+/// #[derive(Serialize)]
+/// struct User { id: u32, name: &'static str }
+///
+/// let opts = toon_rs::Options::default();
+/// let mut table = toon_rs::TableWriter::with_sink(String::new(), "users", &["id", "name"], 2, &opts)?;
+/// table.write_rows([User { id: 1, name: "Ada" }, User { id: 2, name: "Bob" }])?;
+/// assert_eq!(table.finish()?, "users[2]{id,name}:\n  1,Ada\n  2,Bob");
+/// # Ok::<(), toon_rs::Error>(())
+/// ```
+pub struct TableWriter<S> {
+    w: LineWriter<S>,
+    fields: Vec<String>,
+    declared: usize,
+    written: usize,
+    delimiter: Delimiter,
+    row_indent: usize,
+    /// For building rows as `serde_json` values
+    #[cfg(feature = "json")]
+    options: Options,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> TableWriter<crate::encode::writer::IoSink<W>> {
+    /// Write the header to `writer`; rows follow via [`TableWriter::write_row`].
+    pub fn new<F: AsRef<str>>(
+        writer: W,
+        key: &str,
+        fields: &[F],
+        len: usize,
+        options: &Options,
+    ) -> Result<Self> {
+        Self::with_sink(
+            crate::encode::writer::IoSink(writer),
+            key,
+            fields,
+            len,
+            options,
+        )
+    }
+}
+
+impl<S: Sink> TableWriter<S> {
+    /// Write the header to an arbitrary [`Sink`]. Fails, writing nothing, if
+    /// `fields` is empty or names a field twice.
+    pub fn with_sink<F: AsRef<str>>(
+        sink: S,
+        key: &str,
+        fields: &[F],
+        len: usize,
+        options: &Options,
+    ) -> Result<Self> {
+        let fields: Vec<String> = fields.iter().map(|f| f.as_ref().into()).collect();
+        if fields.is_empty() {
+            return Err(Error::data(
+                ErrorKind::Other,
+                "a table header needs at least one field",
+            ));
+        }
+        if let Some(field) =
+            (1..fields.len()).find_map(|i| fields[..i].contains(&fields[i]).then_some(&fields[i]))
+        {
+            return Err(Error::data(
+                ErrorKind::DuplicateKey,
+                format!("field `{}` appears twice in the table header", field),
+            ));
+        }
+        let field_cells: Vec<String> = fields.iter().map(|f| primitives::format_key(f)).collect();
+        let mut header = if key.is_empty() {
+            String::new()
+        } else {
+            primitives::format_key(key)
+        };
+        header.push_str(&primitives::format_tabular_header(
+            len,
+            &field_cells,
+            options.delimiter,
+        ));
+        let mut w = LineWriter::with_sink(sink);
+        w.line(0, &header);
+        Ok(Self {
+            w,
+            fields,
+            declared: len,
+            written: 0,
+            delimiter: options.delimiter,
+            row_indent: options.indent,
+            #[cfg(feature = "json")]
+            options: options.clone(),
+        })
+    }
+
+    /// Serialize one row; it must have exactly the header's fields.
+    ///
+    /// Once a write to the sink has failed, rows are refused and
+    /// [`TableWriter::finish`] gives the sink's error.
+    pub fn write_row<T: ?Sized + Serialize>(&mut self, row: &T) -> Result<()> {
+        self.w.check()?;
+        if self.written == self.declared {
+            return Err(Error::data(
+                ErrorKind::LengthMismatch,
+                format!(
+                    "table declares {} rows but more were written",
+                    self.declared
+                ),
+            ));
+        }
+        let cells = self.row_cells(row)?;
+        let dch = primitives::delimiter_char(self.delimiter);
+        self.w.line(self.row_indent, &join_with_delim(&cells, dch));
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Serialize every row from `rows`, stopping at the first error.
+    pub fn write_rows<I>(&mut self, rows: I) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        for row in rows {
+            self.write_row(&row)?;
+        }
+        Ok(())
+    }
+
+    /// Rows written so far.
+    pub fn rows_written(&self) -> usize {
+        self.written
+    }
+
+    /// Check the row count and flush, returning the sink.
+    pub fn finish(self) -> Result<S> {
+        // After a failed write, the sink's error explains any missing rows
+        if self.w.check().is_ok() && self.written != self.declared {
+            return Err(Error::data(
+                ErrorKind::LengthMismatch,
+                format!(
+                    "table declares {} rows but {} were written",
+                    self.declared, self.written
+                ),
+            ));
+        }
+        self.w.finish()
+    }
+
+    #[cfg(feature = "json")]
+    fn row_cells<T: ?Sized + Serialize>(&self, row: &T) -> Result<Vec<String>> {
+        use serde_json::Value;
+
+        let value = super::value_builder::try_to_value(row, &self.options)
+            .map_err(<Error as serde::ser::Error>::custom)?;
+        let Value::Object(obj) = value else {
+            return Err(self.shape_error("is not an object"));
+        };
+        if obj.len() != self.fields.len() {
+            return Err(self.field_count_error(obj.len()));
+        }
+        self.fields
+            .iter()
+            .map(|f| match obj.get(f) {
+                Some(Value::Array(_) | Value::Object(_)) => {
+                    Err(self.shape_error(&format!("field `{}` is not a primitive", f)))
+                }
+                Some(v) => Ok(format_cell(v, self.delimiter)),
+                None => Err(self.shape_error(&format!("is missing field `{}`", f))),
+            })
+            .collect()
+    }
+
+    #[cfg(not(feature = "json"))]
+    fn row_cells<T: ?Sized + Serialize>(&self, row: &T) -> Result<Vec<String>> {
+        use crate::value::Value;
+
+        let Value::Object(obj) = crate::value::to_value(row)? else {
+            return Err(self.shape_error("is not an object"));
+        };
+        if obj.len() != self.fields.len() {
+            return Err(self.field_count_error(obj.len()));
+        }
+        self.fields
+            .iter()
+            .map(|f| match obj.get(f) {
+                Some(Value::Array(_) | Value::Object(_)) => {
+                    Err(self.shape_error(&format!("field `{}` is not a primitive", f)))
+                }
+                Some(v) => Ok(format_cell(v, self.delimiter)),
+                None => Err(self.shape_error(&format!("is missing field `{}`", f))),
+            })
+            .collect()
+    }
+
+    fn shape_error(&self, what: &str) -> Error {
+        self.row_error(ErrorKind::TypeMismatch, what)
+    }
+
+    fn field_count_error(&self, found: usize) -> Error {
+        self.row_error(
+            ErrorKind::LengthMismatch,
+            &format!(
+                "has {} fields but header declares {}",
+                found,
+                self.fields.len()
+            ),
+        )
+    }
+
+    fn row_error(&self, kind: ErrorKind, what: &str) -> Error {
+        Error::data(kind, format!("table row {} {}", self.written, what))
+    }
+}
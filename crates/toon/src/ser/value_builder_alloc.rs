@@ -1,335 +0,0 @@
-#[cfg(not(feature = "std"))]
-use alloc::{
-    format,
-    string::{String, ToString},
-    vec::Vec,
-};
-
-use serde::Serialize;
-use serde::ser::*;
-
-use crate::options::Options;
-use crate::value::{Number, Value};
-
-pub fn to_value<T: Serialize + ?Sized>(value: &T, _options: &Options) -> Value {
-    let mut ser = ValueSerializer;
-    value.serialize(&mut ser).unwrap_or(Value::Null)
-}
-
-struct ValueSerializer;
-
-#[derive(Debug)]
-pub struct BuildError;
-impl core::fmt::Display for BuildError {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.write_str("ser error")
-    }
-}
-impl serde::ser::Error for BuildError {
-    fn custom<T: core::fmt::Display>(_t: T) -> Self {
-        BuildError
-    }
-}
-impl core::error::Error for BuildError {}
-
-impl Serializer for &mut ValueSerializer {
-    type Ok = Value;
-    type Error = BuildError;
-    type SerializeSeq = SeqSerializer;
-    type SerializeTuple = SeqSerializer;
-    type SerializeTupleStruct = SeqSerializer;
-    type SerializeTupleVariant = SeqSerializer;
-    type SerializeMap = MapSerializer;
-    type SerializeStruct = MapSerializer;
-    type SerializeStructVariant = StructVariantSerializer;
-
-    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Bool(v))
-    }
-    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Number(Number::I64(v as i64)))
-    }
-    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Number(Number::I64(v as i64)))
-    }
-    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Number(Number::I64(v as i64)))
-    }
-    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Number(Number::I64(v)))
-    }
-    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Number(Number::U64(v as u64)))
-    }
-    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Number(Number::U64(v as u64)))
-    }
-    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Number(Number::U64(v as u64)))
-    }
-    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Number(Number::U64(v)))
-    }
-    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-        self.serialize_f64(v as f64)
-    }
-    fn serialize_f64(self, f: f64) -> Result<Self::Ok, Self::Error> {
-        if f.is_finite() {
-            Ok(Value::Number(Number::F64(f)))
-        } else {
-            Ok(Value::Null)
-        }
-    }
-    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::String(v.to_string()))
-    }
-    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::String(v.to_string()))
-    }
-    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Array(
-            v.iter()
-                .map(|b| Value::Number(Number::U64(*b as u64)))
-                .collect(),
-        ))
-    }
-    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Null)
-    }
-    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
-        value.serialize(self)
-    }
-    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Null)
-    }
-    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Null)
-    }
-    fn serialize_unit_variant(
-        self,
-        _name: &'static str,
-        _variant_index: u32,
-        variant: &'static str,
-    ) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::String(variant.to_string()))
-    }
-    fn serialize_newtype_struct<T: ?Sized + Serialize>(
-        self,
-        _name: &'static str,
-        value: &T,
-    ) -> Result<Self::Ok, Self::Error> {
-        value.serialize(self)
-    }
-    fn serialize_newtype_variant<T: ?Sized + Serialize>(
-        self,
-        _name: &'static str,
-        _variant_index: u32,
-        variant: &'static str,
-        value: &T,
-    ) -> Result<Self::Ok, Self::Error> {
-        let mut m = Vec::new();
-        let mut inner = ValueSerializer;
-        m.push((variant.to_string(), value.serialize(&mut inner)?));
-        Ok(Value::Object(m))
-    }
-    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        Ok(SeqSerializer {
-            elems: Vec::with_capacity(len.unwrap_or(0)),
-        })
-    }
-    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-        self.serialize_seq(Some(len))
-    }
-    fn serialize_tuple_struct(
-        self,
-        _name: &'static str,
-        len: usize,
-    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        self.serialize_seq(Some(len))
-    }
-    fn serialize_tuple_variant(
-        self,
-        _name: &'static str,
-        _variant_index: u32,
-        _variant: &'static str,
-        _len: usize,
-    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        Ok(SeqSerializer { elems: Vec::new() })
-    }
-    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        Ok(MapSerializer {
-            map: Vec::new(),
-            next_key: None,
-        })
-    }
-    fn serialize_struct(
-        self,
-        _name: &'static str,
-        _len: usize,
-    ) -> Result<Self::SerializeStruct, Self::Error> {
-        Ok(MapSerializer {
-            map: Vec::new(),
-            next_key: None,
-        })
-    }
-    fn serialize_struct_variant(
-        self,
-        _name: &'static str,
-        _variant_index: u32,
-        variant: &'static str,
-        _len: usize,
-    ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        Ok(StructVariantSerializer {
-            map: Vec::new(),
-            name: variant.to_string(),
-        })
-    }
-}
-
-pub struct SeqSerializer {
-    elems: Vec<Value>,
-}
-
-impl SerializeSeq for SeqSerializer {
-    type Ok = Value;
-    type Error = BuildError;
-
-    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-        let mut ser = ValueSerializer;
-        self.elems.push(value.serialize(&mut ser)?);
-        Ok(())
-    }
-
-    fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Array(self.elems))
-    }
-}
-
-impl SerializeTuple for SeqSerializer {
-    type Ok = Value;
-    type Error = BuildError;
-
-    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-        SerializeSeq::serialize_element(self, value)
-    }
-    fn end(self) -> Result<Self::Ok, Self::Error> {
-        SerializeSeq::end(self)
-    }
-}
-
-impl SerializeTupleStruct for SeqSerializer {
-    type Ok = Value;
-    type Error = BuildError;
-
-    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-        SerializeSeq::serialize_element(self, value)
-    }
-    fn end(self) -> Result<Self::Ok, Self::Error> {
-        SerializeSeq::end(self)
-    }
-}
-
-impl SerializeTupleVariant for SeqSerializer {
-    type Ok = Value;
-    type Error = BuildError;
-
-    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-        SerializeSeq::serialize_element(self, value)
-    }
-    fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Array(self.elems))
-    }
-}
-
-pub struct MapSerializer {
-    map: Vec<(String, Value)>,
-    next_key: Option<String>,
-}
-
-impl SerializeMap for MapSerializer {
-    type Ok = Value;
-    type Error = BuildError;
-
-    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
-        let mut ser = ValueSerializer;
-        let v = key.serialize(&mut ser)?;
-        let s = match v {
-            Value::String(s) => s,
-            Value::Number(n) => n.to_string(),
-            Value::Bool(b) => {
-                if b {
-                    "true".into()
-                } else {
-                    "false".into()
-                }
-            }
-            Value::Null => "null".into(),
-            other => format!(
-                "{}",
-                match other {
-                    Value::String(s) => s,
-                    _ => String::new(),
-                }
-            ),
-        };
-        self.next_key = Some(s);
-        Ok(())
-    }
-
-    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
-        let mut ser = ValueSerializer;
-        let v = value.serialize(&mut ser)?;
-        let k = self.next_key.take().unwrap_or_default();
-        self.map.push((k, v));
-        Ok(())
-    }
-
-    fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(Value::Object(self.map))
-    }
-}
-
-impl SerializeStruct for MapSerializer {
-    type Ok = Value;
-    type Error = BuildError;
-
-    fn serialize_field<T: ?Sized + Serialize>(
-        &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error> {
-        SerializeMap::serialize_key(self, &key)?;
-        SerializeMap::serialize_value(self, value)
-    }
-
-    fn end(self) -> Result<Self::Ok, Self::Error> {
-        SerializeMap::end(self)
-    }
-}
-
-pub struct StructVariantSerializer {
-    map: Vec<(String, Value)>,
-    name: String,
-}
-
-impl SerializeStructVariant for StructVariantSerializer {
-    type Ok = Value;
-    type Error = BuildError;
-
-    fn serialize_field<T: ?Sized + Serialize>(
-        &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error> {
-        let mut ser = ValueSerializer;
-        let v = value.serialize(&mut ser)?;
-        self.map.push((key.to_string(), v));
-        Ok(())
-    }
-
-    fn end(self) -> Result<Self::Ok, Self::Error> {
-        let mut outer = Vec::new();
-        outer.push((self.name, Value::Object(self.map)));
-        Ok(Value::Object(outer))
-    }
-}
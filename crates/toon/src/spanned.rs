@@ -0,0 +1,257 @@
+//! [`Spanned`], a value together with where it was found in the input
+//!
+//! Deserializers recognize `Spanned<T>` by a private struct name and hand its
+//! visitor a map holding the value followed by the position of its text. Only
+//! deserializers that know where values come from take part: [`crate::from_str`],
+//! [`crate::decode_from_reader`] and the `de_direct` deserializer. Deserializing
+//! a `Spanned<T>` from a [`crate::Value`] or a foreign format is an error.
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+use serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::Span;
+
+pub(crate) const NAME: &str = "$__toon_private_Spanned";
+pub(crate) const VALUE: &str = "$__toon_private_value";
+pub(crate) const OFFSET: &str = "$__toon_private_offset";
+pub(crate) const LINE: &str = "$__toon_private_line";
+pub(crate) const COLUMN: &str = "$__toon_private_column";
+pub(crate) const LEN: &str = "$__toon_private_len";
+pub(crate) const FIELDS: &[&str] = &[VALUE, OFFSET, LINE, COLUMN, LEN];
+
+/// Whether a `deserialize_struct` call is asking for a [`Spanned`].
+pub(crate) fn is_spanned(name: &str, fields: &[&str]) -> bool {
+    name == NAME && fields == FIELDS
+}
+
+/// A deserialized value and the [`Span`] of the text it came from.
+///
+/// Use it for fields that are checked after decoding, so the error can point
+/// back into the document:
+///
+/// ```
+/// use toon_rs::Spanned;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     timeout: Spanned<u32>,
+/// }
+///
+/// let doc = "name: api\ntimeout: 90000\n";
+/// let config: Config = toon_rs::from_str(doc, &Default::default()).unwrap();
+/// let span = config.timeout.span();
+/// assert_eq!((span.line, span.column), (2, 10));
+/// assert_eq!(&doc[config.timeout.start()..config.timeout.end()], "90000");
+/// ```
+///
+/// Comparison and hashing look at the value only. Serializing writes the value
+/// as if it were not wrapped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Spanned<T> {
+    span: Span,
+    value: T,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(span: Span, value: T) -> Self {
+        Spanned { span, value }
+    }
+
+    /// Where the value was found.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Byte offset of the start of the value.
+    pub fn start(&self) -> usize {
+        self.span.offset
+    }
+
+    /// Byte offset just past the end of the value.
+    pub fn end(&self) -> usize {
+        self.span.offset + self.span.len
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.value
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl Borrow<str> for Spanned<String> {
+    fn borrow(&self) -> &str {
+        &self.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Eq> Eq for Spanned<T> {}
+
+impl<T: PartialOrd> PartialOrd for Spanned<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Ord> Ord for Spanned<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T: Hash> Hash for Spanned<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+impl<T: Serialize> Serialize for Spanned<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Spanned<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SpannedVisitor<T>(core::marker::PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for SpannedVisitor<T> {
+            type Value = Spanned<T>;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("a value with its position in a TOON document")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Spanned<T>, A::Error> {
+                let mut value = None;
+                let mut span = Span::default();
+                while let Some(key) = map.next_key::<&str>()? {
+                    match key {
+                        VALUE => value = Some(map.next_value()?),
+                        OFFSET => span.offset = map.next_value()?,
+                        LINE => span.line = map.next_value()?,
+                        COLUMN => span.column = map.next_value()?,
+                        LEN => span.len = map.next_value()?,
+                        other => return Err(de::Error::unknown_field(other, FIELDS)),
+                    }
+                }
+                let value = value.ok_or_else(|| de::Error::missing_field(VALUE))?;
+                Ok(Spanned { span, value })
+            }
+        }
+
+        deserializer.deserialize_struct(NAME, FIELDS, SpannedVisitor(core::marker::PhantomData))
+    }
+}
+
+/// A position that holds a value and can tell where it lies in the input.
+pub(crate) trait SpannedSource<'de> {
+    type Error: de::Error;
+
+    fn value<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, Self::Error>;
+
+    /// Span of the value; called after [`SpannedSource::value`], so sources
+    /// that only learn where a value ends by reading it can report that.
+    fn span(&mut self) -> Span;
+}
+
+/// The map a [`Spanned`] visitor reads: the value first, then its span.
+pub(crate) struct SpannedAccess<P> {
+    source: P,
+    span: Option<Span>,
+    field: usize,
+}
+
+impl<P> SpannedAccess<P> {
+    pub(crate) fn new(source: P) -> Self {
+        SpannedAccess {
+            source,
+            span: None,
+            field: 0,
+        }
+    }
+}
+
+impl<'de, P: SpannedSource<'de>> MapAccess<'de> for SpannedAccess<P> {
+    type Error = P::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match FIELDS.get(self.field) {
+            Some(&key) => seed
+                .deserialize(de::value::BorrowedStrDeserializer::new(key))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let field = self.field;
+        self.field += 1;
+        if field == 0 {
+            let value = self.source.value(seed)?;
+            self.span = Some(self.source.span());
+            return Ok(value);
+        }
+        let span = self.span.unwrap_or_default();
+        let n = match field {
+            1 => span.offset,
+            2 => span.line,
+            3 => span.column,
+            _ => span.len,
+        };
+        seed.deserialize(IntoDeserializer::<P::Error>::into_deserializer(n as u64))
+    }
+}
+
+/// A source whose span is known before reading the value.
+pub(crate) struct Known<D> {
+    pub(crate) value: Option<D>,
+    pub(crate) span: Span,
+}
+
+impl<'de, D: Deserializer<'de>> SpannedSource<'de> for Known<D> {
+    type Error = D::Error;
+
+    fn value<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, D::Error> {
+        match self.value.take() {
+            Some(d) => seed.deserialize(d),
+            None => Err(de::Error::custom("spanned value read twice")),
+        }
+    }
+
+    fn span(&mut self) -> Span {
+        self.span
+    }
+}
+
+/// Span from the start of `start` up to byte offset `end`.
+pub(crate) fn span_to(start: Span, end: usize) -> Span {
+    Span {
+        len: end.max(start.offset + start.len) - start.offset,
+        ..start
+    }
+}
@@ -0,0 +1,582 @@
+//! serde::Deserialize for [`Value`], and `Value`/`&Value` as deserializers
+//!
+//! `&'de Value` hands strings and keys to visitors as borrowed slices, so a
+//! decoded tree can be viewed as typed data without copying it.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use serde::de::value::{BorrowedStrDeserializer, StrDeserializer, StringDeserializer};
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, Error as _, Expected, IntoDeserializer, MapAccess,
+    SeqAccess, Unexpected, VariantAccess, Visitor,
+};
+
+use super::spanned::{Node, SpannedValue};
+use super::{Map, Number, Value};
+use crate::error::{Error, Result};
+use crate::spanned::{self, Known, SpannedAccess};
+
+impl<'de> serde::Deserialize<'de> for Value {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        use serde::de::{MapAccess, SeqAccess, Visitor};
+
+        struct ValueVisitor;
+
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = Value;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("any TOON value")
+            }
+
+            fn visit_unit<E>(self) -> core::result::Result<Value, E> {
+                Ok(Value::Null)
+            }
+            fn visit_none<E>(self) -> core::result::Result<Value, E> {
+                Ok(Value::Null)
+            }
+            fn visit_some<D: serde::Deserializer<'de>>(
+                self,
+                d: D,
+            ) -> core::result::Result<Value, D::Error> {
+                serde::Deserialize::deserialize(d)
+            }
+            fn visit_bool<E>(self, b: bool) -> core::result::Result<Value, E> {
+                Ok(Value::Bool(b))
+            }
+            fn visit_i64<E>(self, i: i64) -> core::result::Result<Value, E> {
+                Ok(Value::Number(Number::I64(i)))
+            }
+            fn visit_u64<E>(self, u: u64) -> core::result::Result<Value, E> {
+                Ok(Value::Number(Number::U64(u)))
+            }
+            fn visit_f64<E>(self, f: f64) -> core::result::Result<Value, E> {
+                Ok(Value::Number(Number::F64(f)))
+            }
+            fn visit_str<E>(self, s: &str) -> core::result::Result<Value, E> {
+                Ok(Value::String(s.into()))
+            }
+            fn visit_string<E>(self, s: String) -> core::result::Result<Value, E> {
+                Ok(Value::String(s))
+            }
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> core::result::Result<Value, A::Error> {
+                let mut arr = Vec::new();
+                while let Some(v) = seq.next_element()? {
+                    arr.push(v);
+                }
+                Ok(Value::Array(arr))
+            }
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> core::result::Result<Value, A::Error> {
+                let mut obj = Map::new();
+                while let Some((k, v)) = map.next_entry()? {
+                    obj.push(k, v);
+                }
+                Ok(Value::Object(obj))
+            }
+        }
+
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+impl SpannedValue {
+    fn unexpected(&self) -> Unexpected<'_> {
+        match &self.node {
+            Node::Scalar(v) => v.unexpected(),
+            Node::Array(_) => Unexpected::Seq,
+            Node::Object(_) => Unexpected::Map,
+        }
+    }
+}
+
+impl Value {
+    fn unexpected(&self) -> Unexpected<'_> {
+        match self {
+            Value::Null => Unexpected::Unit,
+            Value::Bool(b) => Unexpected::Bool(*b),
+            Value::Number(Number::I64(i)) => Unexpected::Signed(*i),
+            Value::Number(Number::U64(u)) => Unexpected::Unsigned(*u),
+            Value::Number(Number::F64(f)) => Unexpected::Float(*f),
+            Value::String(s) => Unexpected::Str(s),
+            Value::Array(_) => Unexpected::Seq,
+            Value::Object(_) => Unexpected::Map,
+        }
+    }
+}
+
+fn visit_number<'de, V: Visitor<'de>>(n: Number, visitor: V) -> Result<V::Value> {
+    match n {
+        Number::I64(i) => visitor.visit_i64(i),
+        Number::U64(u) => visitor.visit_u64(u),
+        Number::F64(f) => visitor.visit_f64(f),
+    }
+}
+
+/// Visit a sequence or map, failing if the visitor leaves entries unconsumed.
+macro_rules! visit_all {
+    ($de:ident, $visit:ident, $visitor:expr) => {{
+        let mut de = $de;
+        let value = $visitor.$visit(&mut de)?;
+        de.end()?;
+        Ok(value)
+    }};
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Number(n) => visit_number(n, visitor),
+            Value::String(s) => visitor.visit_string(s),
+            Value::Array(arr) => {
+                let de = SeqAccessor::new(arr.into_iter());
+                visit_all!(de, visit_seq, visitor)
+            }
+            Value::Object(map) => {
+                let de = MapAccessor::new(map.into_iter());
+                visit_all!(de, visit_map, visitor)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self {
+            Value::String(variant) => visitor.visit_enum(EnumDeserializer {
+                variant: StringDeserializer::new(variant),
+                value: None::<Value>,
+            }),
+            Value::Object(map) if map.len() == 1 => {
+                let (variant, value) = map.into_iter().next().expect("map has one entry");
+                visitor.visit_enum(EnumDeserializer {
+                    variant: StringDeserializer::new(variant),
+                    value: Some(value),
+                })
+            }
+            other => Err(Error::invalid_type(
+                other.unexpected(),
+                &"a string or an object with a single key",
+            )),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &'de Value {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(*b),
+            Value::Number(n) => visit_number(*n, visitor),
+            Value::String(s) => visitor.visit_borrowed_str(s),
+            Value::Array(arr) => {
+                let de = SeqAccessor::new(arr.iter());
+                visit_all!(de, visit_seq, visitor)
+            }
+            Value::Object(map) => {
+                let de = MapAccessor::new(map.iter());
+                visit_all!(de, visit_map, visitor)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            other => visitor.visit_some(other),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self {
+            Value::String(variant) => visitor.visit_enum(EnumDeserializer {
+                variant: BorrowedStrDeserializer::new(variant),
+                value: None::<&Value>,
+            }),
+            Value::Object(map) if map.len() == 1 => {
+                let (variant, value) = map.iter().next().expect("map has one entry");
+                visitor.visit_enum(EnumDeserializer {
+                    variant: BorrowedStrDeserializer::new(variant),
+                    value: Some(value),
+                })
+            }
+            other => Err(Error::invalid_type(
+                other.unexpected(),
+                &"a string or an object with a single key",
+            )),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// Like `Value`, but errors point at the node's span and [`crate::Spanned`]
+/// fields are filled in.
+impl<'de> de::Deserializer<'de> for SpannedValue {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let span = self.span;
+        let result = match self.node {
+            Node::Scalar(v) => v.deserialize_any(visitor),
+            Node::Array(items) => {
+                let de = SeqAccessor::new(items.into_iter());
+                visit_all!(de, visit_seq, visitor)
+            }
+            Node::Object(entries) => {
+                let de = MapAccessor::new(entries.into_iter());
+                visit_all!(de, visit_map, visitor)
+            }
+        };
+        result.map_err(|e| e.at(span))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.node {
+            Node::Scalar(Value::Null) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        if spanned::is_spanned(name, fields) {
+            let span = self.span;
+            return visitor.visit_map(SpannedAccess::new(Known {
+                value: Some(self),
+                span,
+            }));
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let span = self.span;
+        let result = match self.node {
+            Node::Scalar(v) => v.deserialize_enum(name, variants, visitor),
+            Node::Object(entries) if entries.len() == 1 => {
+                let (variant, value) = entries.into_iter().next().expect("map has one entry");
+                visitor.visit_enum(EnumDeserializer {
+                    variant: StringDeserializer::new(variant),
+                    value: Some(value),
+                })
+            }
+            _ => Err(Error::invalid_type(
+                self.unexpected(),
+                &"a string or an object with a single key",
+            )),
+        };
+        result.map_err(|e| e.at(span))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map
+        identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for &'de Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Array elements, numbered for error paths.
+struct SeqAccessor<I> {
+    iter: I,
+    index: usize,
+}
+
+impl<I: Iterator> SeqAccessor<I> {
+    fn new(iter: I) -> Self {
+        Self { iter, index: 0 }
+    }
+
+    fn end(self) -> Result<()> {
+        match self.iter.count() {
+            0 => Ok(()),
+            rest => Err(Error::invalid_length(
+                self.index + rest,
+                &Consumed(self.index, "sequence"),
+            )),
+        }
+    }
+}
+
+impl<'de, I, D> SeqAccess<'de> for SeqAccessor<I>
+where
+    I: Iterator<Item = D>,
+    D: de::Deserializer<'de, Error = Error>,
+{
+    type Error = Error;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>> {
+        let Some(value) = self.iter.next() else {
+            return Ok(None);
+        };
+        let index = self.index;
+        self.index += 1;
+        seed.deserialize(value)
+            .map(Some)
+            .map_err(|e| e.in_index(index))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        match self.iter.size_hint() {
+            (lo, Some(hi)) if lo == hi => Some(lo),
+            _ => None,
+        }
+    }
+}
+
+/// Object keys as handed to visitors: copied out of an owned map, borrowed
+/// from `&'de Value`. Either way the key is kept for error paths.
+trait Key<'de> {
+    fn as_str(&self) -> &str;
+
+    fn deserialize<S: DeserializeSeed<'de>>(&self, seed: S) -> Result<S::Value>;
+}
+
+impl<'de> Key<'de> for String {
+    fn as_str(&self) -> &str {
+        self
+    }
+
+    fn deserialize<S: DeserializeSeed<'de>>(&self, seed: S) -> Result<S::Value> {
+        seed.deserialize(StrDeserializer::new(self))
+    }
+}
+
+impl<'de> Key<'de> for &'de String {
+    fn as_str(&self) -> &str {
+        self
+    }
+
+    fn deserialize<S: DeserializeSeed<'de>>(&self, seed: S) -> Result<S::Value> {
+        let key: &'de String = self;
+        seed.deserialize(BorrowedStrDeserializer::new(key))
+    }
+}
+
+/// Object entries, keeping the current key for error paths.
+struct MapAccessor<I, K, D> {
+    iter: I,
+    pending: Option<(K, D)>,
+    count: usize,
+}
+
+impl<I: Iterator, K, D> MapAccessor<I, K, D> {
+    fn new(iter: I) -> Self {
+        Self {
+            iter,
+            pending: None,
+            count: 0,
+        }
+    }
+
+    fn end(self) -> Result<()> {
+        match self.iter.count() {
+            0 => Ok(()),
+            rest => Err(Error::invalid_length(
+                self.count + rest,
+                &Consumed(self.count, "map"),
+            )),
+        }
+    }
+}
+
+impl<'de, I, K, D> MapAccess<'de> for MapAccessor<I, K, D>
+where
+    I: Iterator<Item = (K, D)>,
+    K: Key<'de>,
+    D: de::Deserializer<'de, Error = Error>,
+{
+    type Error = Error;
+
+    fn next_key_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>> {
+        let Some((key, value)) = self.iter.next() else {
+            return Ok(None);
+        };
+        self.count += 1;
+        let out = key.deserialize(seed)?;
+        self.pending = Some((key, value));
+        Ok(Some(out))
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value> {
+        let (key, value) = self
+            .pending
+            .take()
+            .ok_or_else(|| Error::custom("map value requested before its key"))?;
+        seed.deserialize(value).map_err(|e| e.in_key(key.as_str()))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        match self.iter.size_hint() {
+            (lo, Some(hi)) if lo == hi => Some(lo),
+            _ => None,
+        }
+    }
+}
+
+/// "N elements in sequence", for length errors.
+struct Consumed(usize, &'static str);
+
+impl Expected for Consumed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.0 == 1 {
+            write!(f, "1 element in {}", self.1)
+        } else {
+            write!(f, "{} elements in {}", self.0, self.1)
+        }
+    }
+}
+
+/// Externally tagged enum: a variant name plus its content, if any.
+struct EnumDeserializer<K, D> {
+    variant: K,
+    value: Option<D>,
+}
+
+impl<'de, K, D> EnumAccess<'de> for EnumDeserializer<K, D>
+where
+    K: de::Deserializer<'de, Error = Error>,
+    D: de::Deserializer<'de, Error = Error>,
+{
+    type Error = Error;
+    type Variant = VariantDeserializer<D>;
+
+    fn variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, Self::Variant)> {
+        let variant = seed.deserialize(self.variant)?;
+        Ok((variant, VariantDeserializer { value: self.value }))
+    }
+}
+
+struct VariantDeserializer<D> {
+    value: Option<D>,
+}
+
+impl<D> VariantDeserializer<D> {
+    fn missing(expected: &str) -> Error {
+        Error::invalid_type(Unexpected::UnitVariant, &expected)
+    }
+}
+
+impl<'de, D: de::Deserializer<'de, Error = Error>> VariantAccess<'de> for VariantDeserializer<D> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        match self.value {
+            Some(value) => de::Deserialize::deserialize(value),
+            None => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value> {
+        match self.value {
+            Some(value) => seed.deserialize(value),
+            None => Err(Self::missing("newtype variant")),
+        }
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        match self.value {
+            Some(value) => value.deserialize_seq(visitor),
+            None => Err(Self::missing("tuple variant")),
+        }
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        match self.value {
+            Some(value) => value.deserialize_map(visitor),
+            None => Err(Self::missing("struct variant")),
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Indexing into arrays and objects
+
+#[cfg(not(feature = "std"))]
+use alloc::string::String;
+
+use core::ops;
+
+use super::{Map, Value};
+
+/// Types that can index into a [`Value`]: `usize` for arrays, `str` for objects.
+///
+/// This trait is sealed; see [`Value::get`] and the `Index` impls on [`Value`].
+pub trait Index: private::Sealed {
+    #[doc(hidden)]
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value>;
+
+    #[doc(hidden)]
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value>;
+
+    /// Index for assignment; `Null` becomes an empty object for string keys.
+    #[doc(hidden)]
+    fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value;
+}
+
+impl Index for usize {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        match v {
+            Value::Array(arr) => arr.get(*self),
+            _ => None,
+        }
+    }
+
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        match v {
+            Value::Array(arr) => arr.get_mut(*self),
+            _ => None,
+        }
+    }
+
+    fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value {
+        match v {
+            Value::Array(arr) => {
+                let len = arr.len();
+                arr.get_mut(*self).unwrap_or_else(|| {
+                    panic!("cannot access index {} of array of length {}", self, len)
+                })
+            }
+            other => panic!("cannot access index {} of {}", self, other.kind()),
+        }
+    }
+}
+
+impl Index for str {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        match v {
+            Value::Object(map) => map.get(self),
+            _ => None,
+        }
+    }
+
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        match v {
+            Value::Object(map) => map.get_mut(self),
+            _ => None,
+        }
+    }
+
+    fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value {
+        if v.is_null() {
+            *v = Value::Object(Map::new());
+        }
+        match v {
+            Value::Object(map) => {
+                if !map.contains_key(self) {
+                    map.insert(self.into(), Value::Null);
+                }
+                map.get_mut(self).expect("key was just inserted")
+            }
+            other => panic!("cannot access key {:?} of {}", self, other.kind()),
+        }
+    }
+}
+
+impl Index for String {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        self.as_str().index_into(v)
+    }
+
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        self.as_str().index_into_mut(v)
+    }
+
+    fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value {
+        self.as_str().index_or_insert(v)
+    }
+}
+
+impl<T: Index + ?Sized> Index for &T {
+    fn index_into<'v>(&self, v: &'v Value) -> Option<&'v Value> {
+        (**self).index_into(v)
+    }
+
+    fn index_into_mut<'v>(&self, v: &'v mut Value) -> Option<&'v mut Value> {
+        (**self).index_into_mut(v)
+    }
+
+    fn index_or_insert<'v>(&self, v: &'v mut Value) -> &'v mut Value {
+        (**self).index_or_insert(v)
+    }
+}
+
+mod private {
+    #[cfg(not(feature = "std"))]
+    use alloc::string::String;
+
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for str {}
+    impl Sealed for String {}
+    impl<T: Sealed + ?Sized> Sealed for &T {}
+}
+
+static NULL: Value = Value::Null;
+
+impl<I: Index> ops::Index<I> for Value {
+    type Output = Value;
+
+    /// Missing keys, out-of-range indices and mismatched types yield `Null`.
+    fn index(&self, index: I) -> &Value {
+        index.index_into(self).unwrap_or(&NULL)
+    }
+}
+
+impl<I: Index> ops::IndexMut<I> for Value {
+    /// Missing keys are inserted as `Null`; indexing past the end of an array panics.
+    fn index_mut(&mut self, index: I) -> &mut Value {
+        index.index_or_insert(self)
+    }
+}
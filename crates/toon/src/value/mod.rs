@@ -0,0 +1,276 @@
+//! In-memory TOON document
+//!
+//! [`Value`] mirrors the JSON data model TOON encodes. It is available on the
+//! alloc-only build, so documents can be built, inspected and (with `serde`)
+//! printed or parsed without serde_json.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::{string::String, vec::Vec};
+
+use crate::number::format_canonical_f64;
+
+#[cfg(feature = "serde")]
+mod de;
+mod from;
+mod index;
+mod map;
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(feature = "serde")]
+pub(crate) mod spanned;
+
+pub use index::Index;
+pub use map::{Iter, IterMut, Map};
+#[cfg(feature = "serde")]
+pub use ser::Serializer;
+
+/// Convert any `T: Serialize` into a [`Value`] without going through text.
+///
+/// Enums are externally tagged (`{"Variant": ...}`); map keys must serialize
+/// as strings, integers, bools or chars.
+#[cfg(feature = "serde")]
+pub fn to_value<T: serde::Serialize + ?Sized>(value: &T) -> crate::Result<Value> {
+    value.serialize(Serializer)
+}
+
+/// Deserialize a `T` out of an owned [`Value`].
+///
+/// To borrow strings from a tree instead, deserialize from `&Value`, which
+/// implements `serde::Deserializer`:
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct User<'a> { name: &'a str }
+///
+/// let doc: toon_rs::Value = "users[2]{name}:\n  Ada\n  Bob".parse()?;
+/// let users: Vec<User> = Vec::deserialize(&doc["users"])?;
+/// assert_eq!(users[1].name, "Bob");
+/// # Ok::<(), toon_rs::Error>(())
+/// ```
+#[cfg(feature = "serde")]
+pub fn from_value<T: serde::de::DeserializeOwned>(value: Value) -> crate::Result<T> {
+    T::deserialize(value)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Number {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+}
+
+impl Number {
+    /// The value as an `i64`, if it is an integer in range.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Number::I64(i) => Some(i),
+            Number::U64(u) => i64::try_from(u).ok(),
+            Number::F64(_) => None,
+        }
+    }
+
+    /// The value as a `u64`, if it is a non-negative integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Number::I64(i) => u64::try_from(i).ok(),
+            Number::U64(u) => Some(u),
+            Number::F64(_) => None,
+        }
+    }
+
+    /// The value as an `f64`; large integers may lose precision.
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Number::I64(i) => i as f64,
+            Number::U64(u) => u as f64,
+            Number::F64(f) => f,
+        }
+    }
+
+    pub fn is_f64(&self) -> bool {
+        matches!(self, Number::F64(_))
+    }
+}
+
+impl PartialEq for Number {
+    /// Integers compare by value regardless of signedness; floats only equal floats.
+    fn eq(&self, other: &Self) -> bool {
+        match (*self, *other) {
+            (Number::F64(a), Number::F64(b)) => a == b,
+            (Number::F64(_), _) | (_, Number::F64(_)) => false,
+            (a, b) => a.as_i64() == b.as_i64() && a.as_u64() == b.as_u64(),
+        }
+    }
+}
+
+impl core::fmt::Display for Number {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Number::I64(i) => write!(f, "{}", i),
+            Number::U64(u) => write!(f, "{}", u),
+            Number::F64(num) => f.write_str(&format_canonical_f64(*num)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Value {
+    #[default]
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<Value>),
+    Object(Map),
+}
+
+impl Value {
+    pub fn is_primitive(&self) -> bool {
+        matches!(
+            self,
+            Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_)
+        )
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Value::Bool(_))
+    }
+
+    pub fn is_number(&self) -> bool {
+        matches!(self, Value::Number(_))
+    }
+
+    pub fn is_string(&self) -> bool {
+        matches!(self, Value::String(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, Value::Array(_))
+    }
+
+    pub fn is_object(&self) -> bool {
+        matches!(self, Value::Object(_))
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            Value::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number().and_then(Number::as_i64)
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number().and_then(Number::as_u64)
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(Number::as_f64)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&Map> {
+        match self {
+            Value::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut Map> {
+        match self {
+            Value::Object(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Look up an object key or array index; `None` if absent or the wrong type.
+    ///
+    /// ```
+    /// let v = toon_rs::toon!({"users": [{"name": "Ada"}]});
+    /// assert_eq!(v.get("users").and_then(|u| u.get(0)).and_then(|u| u.get("name")), Some(&"Ada".into()));
+    /// assert!(v.get("missing").is_none());
+    /// ```
+    pub fn get<I: Index>(&self, index: I) -> Option<&Value> {
+        index.index_into(self)
+    }
+
+    pub fn get_mut<I: Index>(&mut self, index: I) -> Option<&mut Value> {
+        index.index_into_mut(self)
+    }
+
+    /// Replace with `Null`, returning the previous value.
+    pub fn take(&mut self) -> Value {
+        core::mem::take(self)
+    }
+
+    /// Name of the variant, for error messages.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "a boolean",
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Array(_) => "an array",
+            Value::Object(_) => "an object",
+        }
+    }
+}
+
+/// Encodes as TOON with [`Options::default`](crate::Options::default).
+#[cfg(feature = "serde")]
+impl core::fmt::Display for Value {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let s = crate::ser::to_string_streaming(self, &crate::Options::default())
+            .map_err(|_| core::fmt::Error)?;
+        f.write_str(&s)
+    }
+}
+
+/// Decodes TOON text with [`Options::default`](crate::Options::default).
+#[cfg(feature = "serde")]
+impl core::str::FromStr for Value {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        crate::de::from_str(s, &crate::Options::default())
+    }
+}
@@ -0,0 +1,103 @@
+//! A [`Value`] tree that remembers where each node was found
+//!
+//! Path expansion needs the whole document before anything can be handed to
+//! the target type. Decoding into this tree instead of a plain [`Value`] keeps
+//! the spans, so [`crate::Spanned`] fields and error locations survive the
+//! detour.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use super::{Number, Value};
+use crate::error::Span;
+use crate::spanned::Spanned;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct SpannedValue {
+    pub(crate) node: Node,
+    pub(crate) span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Node {
+    Scalar(Value),
+    Array(Vec<SpannedValue>),
+    Object(Vec<(String, SpannedValue)>),
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Node::Scalar(Value::Null)
+    }
+}
+
+impl<'de> Deserialize<'de> for SpannedValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let node = Spanned::<Node>::deserialize(deserializer)?;
+        Ok(SpannedValue {
+            span: node.span(),
+            node: node.into_inner(),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NodeVisitor)
+    }
+}
+
+struct NodeVisitor;
+
+impl<'de> Visitor<'de> for NodeVisitor {
+    type Value = Node;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("any TOON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Node, E> {
+        Ok(Node::Scalar(Value::Null))
+    }
+    fn visit_none<E>(self) -> Result<Node, E> {
+        Ok(Node::Scalar(Value::Null))
+    }
+    fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Node, D::Error> {
+        Node::deserialize(d)
+    }
+    fn visit_bool<E>(self, b: bool) -> Result<Node, E> {
+        Ok(Node::Scalar(Value::Bool(b)))
+    }
+    fn visit_i64<E>(self, i: i64) -> Result<Node, E> {
+        Ok(Node::Scalar(Value::Number(Number::I64(i))))
+    }
+    fn visit_u64<E>(self, u: u64) -> Result<Node, E> {
+        Ok(Node::Scalar(Value::Number(Number::U64(u))))
+    }
+    fn visit_f64<E>(self, f: f64) -> Result<Node, E> {
+        Ok(Node::Scalar(Value::Number(Number::F64(f))))
+    }
+    fn visit_str<E>(self, s: &str) -> Result<Node, E> {
+        Ok(Node::Scalar(Value::String(s.into())))
+    }
+    fn visit_string<E>(self, s: String) -> Result<Node, E> {
+        Ok(Node::Scalar(Value::String(s)))
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Node, A::Error> {
+        let mut items = Vec::new();
+        while let Some(v) = seq.next_element()? {
+            items.push(v);
+        }
+        Ok(Node::Array(items))
+    }
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Node, A::Error> {
+        let mut entries = Vec::new();
+        while let Some(key) = map.next_key::<String>()? {
+            entries.push((key, map.next_value()?));
+        }
+        Ok(Node::Object(entries))
+    }
+}
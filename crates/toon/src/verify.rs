@@ -0,0 +1,133 @@
+//! [`encode_verified`] and [`verify_roundtrip`], which check that decoding
+//! encoded text gives back the value that was encoded
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String};
+
+use serde::Serialize;
+
+use crate::error::{Error, ErrorKind, Result};
+use crate::options::{ExpandPaths, KeyFolding, Options};
+use crate::value::{Number, Value};
+
+/// Encode `value` as [`crate::encode_to_string`] does, then decode the text
+/// and compare the result with `value`; see [`verify_roundtrip`].
+///
+/// ```
+/// use toon_rs::{ErrorKind, Options, toon};
+///
+/// let text = toon_rs::encode_verified(&toon!({ "ids": [1, 2.5] }), &Options::default())?;
+/// assert_eq!(text, "ids[2]: 1,2.5");
+///
+/// // Text that lost the quotes around a numeric-looking string
+/// let err = toon_rs::verify_roundtrip(&toon!({ "ids": ["1"] }), "ids[1]: 1", &Options::default())
+///     .unwrap_err();
+/// assert_eq!((err.kind(), err.path()), (ErrorKind::RoundTrip, Some("ids[0]")));
+/// assert_eq!(err.to_string(), "ids[0]: encoded the string \"1\" but decoded the number 1");
+/// # Ok::<(), toon_rs::Error>(())
+/// ```
+pub fn encode_verified<T: Serialize>(value: &T, options: &Options) -> Result<String> {
+    let text = crate::encode_to_string(value, options)?;
+    verify_roundtrip(&crate::to_value(value)?, &text, options)?;
+    Ok(text)
+}
+
+/// Check that `encoded`, the text `original` was encoded to with `options`,
+/// decodes back to `original`.
+///
+/// Objects must have the same keys (in any order) and arrays the same length.
+/// Numbers compare by value, so `1.0` matches the `1` it is written as, but a
+/// float that changed or a number that became a string does not. Folded keys
+/// are expanded again when `options` folds them.
+///
+/// On a difference, the error has kind [`ErrorKind::RoundTrip`] and the
+/// [path](Error::path) of the first value that differs.
+pub fn verify_roundtrip(original: &Value, encoded: &str, options: &Options) -> Result<()> {
+    let options = Options {
+        strict: true,
+        expand_paths: match options.key_folding {
+            KeyFolding::Safe => ExpandPaths::Safe,
+            KeyFolding::Off => ExpandPaths::Off,
+        },
+        ..options.clone()
+    };
+    let decoded: Value = crate::from_str(encoded, &options).map_err(|e| {
+        Error::data(
+            ErrorKind::RoundTrip,
+            format!("the encoded text does not decode: {}", e),
+        )
+    })?;
+    compare(original, &decoded)
+}
+
+/// The first difference between `expected` and `found`, with its path.
+fn compare(expected: &Value, found: &Value) -> Result<()> {
+    match (expected, found) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, value) in a.iter() {
+                match b.get(key) {
+                    Some(other) => compare(value, other).map_err(|e| e.in_key(key))?,
+                    None => return Err(differ("the key is missing after decoding").in_key(key)),
+                }
+            }
+            match b.keys().find(|k| !a.contains_key(k)) {
+                Some(key) => Err(differ("decoding added this key").in_key(key)),
+                None => Ok(()),
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for (i, (value, other)) in a.iter().zip(b).enumerate() {
+                compare(value, other).map_err(|e| e.in_index(i))?;
+            }
+            if a.len() == b.len() {
+                Ok(())
+            } else {
+                Err(differ(&format!(
+                    "encoded {} items but decoded {}",
+                    a.len(),
+                    b.len()
+                )))
+            }
+        }
+        (Value::Number(a), Value::Number(b)) if same_number(*a, *b) => Ok(()),
+        (a, b) if a.is_primitive() && a == b => Ok(()),
+        (a, b) => Err(differ(&format!(
+            "encoded {} but decoded {}",
+            describe(a),
+            describe(b)
+        ))),
+    }
+}
+
+/// Whether two numbers have the same value, whatever their representation.
+fn same_number(a: Number, b: Number) -> bool {
+    match (a, b) {
+        (Number::F64(x), Number::F64(y)) => x == y,
+        (Number::F64(f), n) | (n, Number::F64(f)) => {
+            // Exact: integral, and equal without rounding the integer to a float
+            let n = n.as_i64().map(i128::from).or(n.as_u64().map(i128::from));
+            f % 1.0 == 0.0 && n.is_some_and(|n| f as i128 == n)
+        }
+        (a, b) => a == b,
+    }
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => String::from("null"),
+        Value::Bool(b) => format!("{}", b),
+        Value::Number(n) => format!("the number {}", n),
+        Value::String(s) => format!("the string {:?}", s),
+        Value::Array(items) => format!("an array of {} items", items.len()),
+        Value::Object(map) => format!("an object with {} keys", map.len()),
+    }
+}
+
+fn differ(message: &str) -> Error {
+    Error::Data {
+        kind: ErrorKind::RoundTrip,
+        message: message.into(),
+        path: String::new(),
+        span: None,
+    }
+}
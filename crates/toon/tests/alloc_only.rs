@@ -63,3 +63,65 @@ fn strict_indent_error_alloc() {
     let msg = err.to_string();
     assert!(msg.contains("indent") || msg.contains("multiple") || msg.contains("syntax"));
 }
+
+#[test]
+fn empty_objects_are_list_items_alloc() -> Result<(), toon_rs::Error> {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Empty {}
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Holder {
+        a: Vec<Empty>,
+    }
+    let h = Holder {
+        a: vec![Empty {}, Empty {}],
+    };
+    let opts = toon_rs::Options::default();
+    let s = toon_rs::ser::to_string_streaming(&h, &opts)?;
+    assert_eq!(s, "a[2]:\n  -\n  -");
+    Ok(())
+}
+
+#[test]
+fn tuples_and_fixed_arrays_alloc() -> Result<(), toon_rs::Error> {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Pair(u32, u32);
+    let opts = toon_rs::Options::default();
+    // At the root, where the sequence itself writes the header
+    assert_eq!(
+        toon_rs::ser::to_string_streaming(&[1, 2, 3], &opts)?,
+        "[3]: 1,2,3"
+    );
+    assert_eq!(
+        toon_rs::ser::to_string_streaming(&(4, "x"), &opts)?,
+        "[2]: 4,x"
+    );
+    assert_eq!(
+        toon_rs::ser::to_string_streaming(&Pair(5, 6), &opts)?,
+        "[2]: 5,6"
+    );
+    let back: Pair = toon_rs::de::from_str("[2]: 5,6", &opts)?;
+    assert_eq!(back, Pair(5, 6));
+    Ok(())
+}
+
+#[test]
+fn values_in_arrays_keep_variants_and_report_errors_alloc() -> Result<(), toon_rs::Error> {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    enum Shape {
+        Pair(u32, u32),
+    }
+    let opts = toon_rs::Options::default();
+    let shapes = vec![Shape::Pair(1, 2)];
+    let s = toon_rs::ser::to_string_streaming(&shapes, &opts)?;
+    let back: Vec<Shape> = toon_rs::de::from_str(&s, &opts)?;
+    assert_eq!(back, shapes);
+
+    // A key that is not a string is an error, not a silent null
+    let maps = vec![std::collections::BTreeMap::from([(vec![1u8], 2u8)])];
+    let err = toon_rs::ser::to_string_streaming(&maps, &opts).unwrap_err();
+    assert!(
+        err.to_string().contains("object key must be a string"),
+        "{err}"
+    );
+    Ok(())
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "serde")]
+use toon_rs::budget::{self, Budget, Sampling};
+use toon_rs::stats::ApproxTokenizer;
+use toon_rs::{ErrorKind, Options, Value, decode_from_str, toon};
+
+fn rows() -> Value {
+    (0..10)
+        .map(|i| toon!({ "i": i, "g": if i < 6 { "a" } else { "b" } }))
+        .collect()
+}
+
+/// Indices kept from `rows()` when the text may have four lines: the table
+/// header and three rows.
+fn kept(sampling: Sampling) -> Vec<usize> {
+    let budget = Budget {
+        max_tokens: Some(4),
+        sampling,
+        ..Budget::default()
+    };
+    let lines = |text: &str| text.lines().count();
+    let (text, report) =
+        budget::encode_within(&rows(), &Options::default(), &budget, &lines).unwrap();
+    assert!(text.starts_with("[3]{"), "{}", text);
+    assert_eq!(
+        (report.sampled[0].path.as_str(), report.sampled[0].len),
+        ("", 10)
+    );
+    report.sampled[0].kept.clone()
+}
+
+#[test]
+fn text_that_fits_is_left_alone() -> Result<(), toon_rs::Error> {
+    let value = toon!({ "a": [1, 2, 3] });
+    let budget = Budget {
+        max_bytes: Some(100),
+        max_tokens: Some(100),
+        ..Budget::default()
+    };
+    let (text, report) =
+        budget::encode_within(&value, &Options::default(), &budget, &ApproxTokenizer)?;
+    assert_eq!(text, "a[3]: 1,2,3");
+    assert!(report.is_empty());
+    Ok(())
+}
+
+#[test]
+fn arrays_are_sampled_as_asked() {
+    assert_eq!(kept(Sampling::HeadTail), [0, 1, 9]);
+    assert_eq!(kept(Sampling::Even), [0, 4, 9]);
+    // Two of the six `a` rows, one of the four `b` rows
+    assert_eq!(kept(Sampling::Stratified("g".into())), [0, 5, 6]);
+}
+
+#[test]
+fn long_strings_are_cut_with_a_marker() -> Result<(), toon_rs::Error> {
+    let bio = "word ".repeat(100);
+    let value = toon!({ "users": [{ "id": 1, "bio": bio.clone() }, { "id": 2, "bio": "short" }] });
+    let budget = Budget {
+        max_bytes: Some(120),
+        marker: "[...]".into(),
+        ..Budget::default()
+    };
+    let (text, report) =
+        budget::encode_within(&value, &Options::default(), &budget, &ApproxTokenizer)?;
+    assert!(text.len() <= 120);
+    assert!(report.sampled.is_empty());
+    let cut = &report.truncated[0];
+    assert_eq!((cut.path.as_str(), cut.len), ("users[0].bio", 500));
+
+    // Still valid TOON, with every `[N]` right
+    let decoded: Value = decode_from_str(&text, &Options::default())?;
+    let kept: String = bio.chars().take(cut.kept).collect();
+    assert_eq!(
+        decoded["users"][0]["bio"],
+        Value::from(format!("{}[...]", kept))
+    );
+    assert_eq!(decoded["users"][1]["bio"], Value::from("short"));
+    Ok(())
+}
+
+#[test]
+fn a_budget_too_small_for_anything_is_an_error() {
+    let budget = Budget {
+        max_bytes: Some(5),
+        ..Budget::default()
+    };
+    let err =
+        budget::encode_within(&rows(), &Options::default(), &budget, &ApproxTokenizer).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::BudgetExceeded);
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "serde")]
+use toon_rs::csv::{self, CsvOptions};
+use toon_rs::{Delimiter, ErrorKind, Options, Value, toon};
+
+#[test]
+fn tabular_arrays_are_written_with_rfc4180_quoting() {
+    let value = toon!({
+        "report": {
+            "rows": [
+                { "id": 1, "note": "say \"hi\"", "ok": true, "score": 1.5 },
+                { "id": 2, "note": "two\nlines", "ok": false, "score": null },
+                { "id": 3, "note": "a,b", "ok": true, "score": -0.0 }
+            ]
+        }
+    });
+    assert_eq!(
+        csv::to_csv(&value, "report.rows", &CsvOptions::default()).unwrap(),
+        "id,note,ok,score\n1,\"say \"\"hi\"\"\",true,1.5\n2,\"two\nlines\",false,\n3,\"a,b\",true,0\n"
+    );
+    assert_eq!(
+        csv::to_csv(&value, "report.rows", &CsvOptions::tsv()).unwrap(),
+        "id\tnote\tok\tscore\n1\t\"say \"\"hi\"\"\"\ttrue\t1.5\n2\t\"two\nlines\"\tfalse\t\n3\ta,b\ttrue\t0\n"
+    );
+    assert_eq!(
+        csv::to_csv(&toon!([{ "a": "" }]), "", &CsvOptions::default()).unwrap(),
+        "a\n\"\"\n"
+    );
+    assert_eq!(
+        csv::to_csv(&toon!({ "t": [] }), "t", &CsvOptions::default()).unwrap(),
+        ""
+    );
+
+    let err = csv::to_csv(&value, "report", &CsvOptions::default()).unwrap_err();
+    assert_eq!(
+        (err.kind(), err.path()),
+        (ErrorKind::TypeMismatch, Some("report"))
+    );
+    let ragged = toon!([{ "a": 1 }, { "b": 2 }]);
+    assert!(csv::to_csv(&ragged, "", &CsvOptions::default()).is_err());
+    let err = csv::to_csv(&value, "report.missing", &CsvOptions::default()).unwrap_err();
+    assert_eq!(err.path(), Some("report.missing"));
+}
+
+#[test]
+fn types_are_inferred_per_column() {
+    let input = "id,zip,active,score,name\r\n1,02134,true,,Ada\r\n\r\n2,10001,false,2.5,\"Lovelace, A\"\r\n3,x,null,1e2,\"\"\r\n";
+    let value = csv::from_csv(input, &CsvOptions::default()).unwrap();
+    assert_eq!(
+        value,
+        toon!([
+            { "id": 1, "zip": "02134", "active": true, "score": null, "name": "Ada" },
+            { "id": 2, "zip": "10001", "active": false, "score": 2.5, "name": "Lovelace, A" },
+            { "id": 3, "zip": "x", "active": null, "score": 100, "name": "" }
+        ])
+    );
+
+    let plain = CsvOptions {
+        infer_types: false,
+        ..CsvOptions::default()
+    };
+    let value = csv::from_csv(input, &plain).unwrap();
+    assert_eq!(value[0]["id"], toon!("1"));
+    assert_eq!(value[0]["score"], toon!(""));
+
+    let pipes = CsvOptions {
+        delimiter: Delimiter::Pipe,
+        ..CsvOptions::default()
+    };
+    assert_eq!(
+        csv::from_csv("a|b\n\"x|y\"|\"multi\nline\"", &pipes).unwrap(),
+        toon!([{ "a": "x|y", "b": "multi\nline" }])
+    );
+    assert_eq!(
+        csv::from_csv("", &CsvOptions::default()).unwrap(),
+        toon!([])
+    );
+}
+
+#[test]
+fn quoted_cells_stay_strings_and_typed_looking_strings_round_trip() {
+    // A quoted cell makes its column strings, whatever it holds
+    let value = csv::from_csv("a,b\n\"1\",2\n3,\"true\"\n", &CsvOptions::default()).unwrap();
+    assert_eq!(
+        value,
+        toon!([{ "a": "1", "b": "2" }, { "a": "3", "b": "true" }])
+    );
+
+    let value = toon!({ "t": [
+        { "a": "123", "b": "true", "c": "null", "d": "", "e": 7 },
+        { "a": "x", "b": "false", "c": "-1.5", "d": "y", "e": null }
+    ] });
+    let text = csv::to_csv(&value, "t", &CsvOptions::default()).unwrap();
+    assert_eq!(
+        text,
+        "a,b,c,d,e\n\"123\",\"true\",\"null\",\"\",7\nx,\"false\",\"-1.5\",y,\n"
+    );
+    assert_eq!(
+        csv::from_csv(&text, &CsvOptions::default()).unwrap(),
+        value["t"]
+    );
+}
+
+#[test]
+fn malformed_csv_is_located() {
+    let err = csv::from_csv("a,b\n1,2\n3\n", &CsvOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::LengthMismatch);
+    assert_eq!(err.span().map(|s| (s.line, s.column)), Some((3, 1)));
+
+    let err = csv::from_csv("a,b\n1,\"2\n", &CsvOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnterminatedString);
+    assert_eq!(err.span().map(|s| (s.line, s.column)), Some((2, 3)));
+
+    let err = csv::from_csv("a,b\n\"1\"x,2\n", &CsvOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Syntax);
+
+    let err = csv::from_csv("a,a\n1,2\n", &CsvOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DuplicateKey);
+}
+
+#[test]
+fn csv_becomes_a_toon_table_and_back() {
+    let input = "id,name,zip\n1,Ada,02134\n2,Bob | Jr,10001\n";
+    let opts = Options {
+        delimiter: Delimiter::Pipe,
+        ..Options::default()
+    };
+    let text = csv::to_toon(input, "users", &CsvOptions::default(), &opts).unwrap();
+    assert_eq!(
+        text,
+        "users[2|]{id|name|zip}:\n  1|Ada|\"02134\"\n  2|\"Bob | Jr\"|\"10001\""
+    );
+    let value: Value = toon_rs::decode_from_str(&text, &opts).unwrap();
+    // The string "10001" is quoted so that it is read back as a string
+    let back = csv::to_csv(&value, "users", &CsvOptions::default()).unwrap();
+    assert_eq!(back, "id,name,zip\n1,Ada,02134\n2,Bob | Jr,\"10001\"\n");
+    assert_eq!(
+        csv::from_csv(&back, &CsvOptions::default()).unwrap(),
+        value["users"]
+    );
+
+    let root = csv::to_toon(
+        "a,b\n1,2\n",
+        "",
+        &CsvOptions::default(),
+        &Options::default(),
+    );
+    assert_eq!(root.unwrap(), "[1]{a,b}:\n  1,2");
+    assert!(csv::to_toon("", "t", &CsvOptions::default(), &Options::default()).is_err());
+}
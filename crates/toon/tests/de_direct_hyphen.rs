@@ -44,10 +44,18 @@ struct WrapB {
     items: Vec<ItemB>,
 }
 
+/// `- ` table rows are not in the spec; only non-strict decoding reads them.
+fn lenient() -> Options {
+    Options {
+        strict: false,
+        ..Options::default()
+    }
+}
+
 #[test]
 fn hyphen_tabular_header_then_sibling() {
     let s = "items[1]:\n  - users[2]{id,name}:\n    - 1,Ada\n    - 2,Bob\n    status: done\n";
-    let v: WrapB = decode_from_str(s, &Options::default()).unwrap();
+    let v: WrapB = decode_from_str(s, &lenient()).unwrap();
     assert_eq!(v.items[0].users.len(), 2);
     assert_eq!(
         v.items[0].users[0],
@@ -57,6 +65,9 @@ fn hyphen_tabular_header_then_sibling() {
         }
     );
     assert_eq!(v.items[0].status, "done");
+
+    let err = decode_from_str::<WrapB>(s, &Options::default()).unwrap_err();
+    assert_eq!(err.kind(), toon_rs::ErrorKind::LengthMismatch);
 }
 
 #[derive(Debug, Deserialize, PartialEq)]
@@ -81,7 +92,7 @@ struct WrapUnicode {
 fn hyphen_tabular_unicode_header() {
     let header = '\u{0085}';
     let s = format!("items[1]:\n  - table[1]{{\"\",{header}}}:\n    - null,null\n");
-    let v: WrapUnicode = decode_from_str(&s, &Options::default()).unwrap();
+    let v: WrapUnicode = decode_from_str(&s, &lenient()).unwrap();
     assert_eq!(v.items.len(), 1);
     assert_eq!(v.items[0].table.len(), 1);
     let row = &v.items[0].table[0];
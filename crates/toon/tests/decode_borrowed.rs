@@ -0,0 +1,82 @@
+#![cfg(feature = "json")]
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::json;
+use toon_rs::{ExpandPaths, Options};
+
+#[derive(Debug, Deserialize)]
+struct Row<'a> {
+    id: u32,
+    name: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct Doc<'a> {
+    title: &'a str,
+    #[serde(borrow)]
+    note: Cow<'a, str>,
+    #[serde(borrow)]
+    escaped: Cow<'a, str>,
+    tags: Vec<&'a str>,
+    #[serde(borrow)]
+    rows: Vec<Row<'a>>,
+    items: Vec<&'a str>,
+}
+
+#[test]
+fn from_str_borrows_unescaped_strings() {
+    let s = "title: Hello world\nnote: \"quoted, no escapes\"\nescaped: \"a\\tb\"\ntags[3]: x,\"y z\",w\nrows[2]{id,name}:\n  1,Ada\n  2,\"Bob\"\nitems[2]:\n  - first\n  - \"second\"\n";
+    let doc: Doc = toon_rs::from_str(s, &Options::default()).unwrap();
+    assert_eq!(doc.title, "Hello world");
+    assert!(matches!(doc.note, Cow::Borrowed("quoted, no escapes")));
+    assert!(matches!(doc.escaped, Cow::Owned(ref e) if e == "a\tb"));
+    assert_eq!(doc.tags, ["x", "y z", "w"]);
+    assert_eq!((doc.rows[0].id, doc.rows[0].name), (1, "Ada"));
+    assert_eq!((doc.rows[1].id, doc.rows[1].name), (2, "Bob"));
+    assert_eq!(doc.items, ["first", "second"]);
+
+    // Borrowed slices point into the input
+    let range = s.as_bytes().as_ptr_range();
+    assert!(range.contains(&doc.title.as_ptr()));
+    assert!(range.contains(&doc.tags[1].as_ptr()));
+    assert!(range.contains(&doc.rows[0].name.as_ptr()));
+}
+
+#[test]
+fn from_str_borrows_keys() {
+    let s = "alpha: 1\n\"beta gamma\": 2\nlist[2]: 3,4\n";
+    let m: BTreeMap<&str, serde_json::Value> =
+        toon_rs::decode_from_str(s, &Options::default()).unwrap();
+    assert_eq!(m["alpha"], json!(1));
+    assert_eq!(m["beta gamma"], json!(2));
+    assert_eq!(m["list"], json!([3, 4]));
+}
+
+#[test]
+fn from_str_escaped_into_borrowed_str_is_an_error() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct S<'a> {
+        s: &'a str,
+    }
+    let err = toon_rs::from_str::<S>("s: \"a\\nb\"\n", &Options::default()).unwrap_err();
+    assert!(err.to_string().contains("borrowed"), "{err}");
+}
+
+#[test]
+fn from_str_owned_targets_still_work() {
+    let opts = Options::default();
+    let v: serde_json::Value = toon_rs::from_str("a:\n  b[2]: 1,x\n", &opts).unwrap();
+    assert_eq!(v, json!({"a": {"b": [1, "x"]}}));
+
+    // Path expansion rebuilds the document, so strings arrive owned
+    let opts = Options {
+        expand_paths: ExpandPaths::Safe,
+        ..Options::default()
+    };
+    let m: BTreeMap<String, BTreeMap<String, Cow<str>>> =
+        toon_rs::from_str("a.b: x\na.c: y\n", &opts).unwrap();
+    assert_eq!(m["a"]["c"], "y");
+}
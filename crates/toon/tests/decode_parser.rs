@@ -22,3 +22,13 @@ fn parse_leading_zero_numeric_token_as_string() {
     let v = parse_to_value(input);
     assert_eq!(v, json!({"value":"05","other":"-012","canon":0.5}));
 }
+
+#[test]
+fn parse_integral_float_beyond_64_bits_stays_float() {
+    let input = "big: 1e300\nneg: -1e20\nfits: 1e3\n";
+    let v = parse_to_value(input);
+    assert_eq!(v, json!({"big": 1e300, "neg": -1e20, "fits": 1000}));
+    let back: serde_json::Value =
+        toon_rs::decode_from_str(input, &toon_rs::Options::default()).unwrap();
+    assert_eq!(back, v);
+}
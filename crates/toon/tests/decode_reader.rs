@@ -0,0 +1,217 @@
+#![cfg(feature = "json")]
+use std::io::{BufReader, Read};
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+use toon_rs::Options;
+
+/// Hands out one byte per `read` call so lines arrive in pieces.
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.0.is_empty() || buf.is_empty() {
+            return Ok(0);
+        }
+        buf[0] = self.0[0];
+        self.0 = &self.0[1..];
+        Ok(1)
+    }
+}
+
+fn from_reader(s: &str, opts: &Options) -> toon_rs::Result<Value> {
+    toon_rs::decode_from_reader(s.as_bytes(), opts)
+}
+
+#[test]
+fn reader_roundtrips_encoder_output() {
+    let opts = Options::default();
+    let cases = [
+        json!({"users": [{"id": 1, "name": "Ada"}, {"id": 2, "name": "c,d"}], "tags": ["x", "y"], "empty": [], "e": {}, "n": null, "f": 1.5, "s": "true"}),
+        json!({"p": {"a": {}}, "q": 1}),
+        json!([{"rows": [{"a": 1}, {"a": 2}], "n": 1}, {"l": [1, [2, 3], {"x": 1, "y": [{"a": 1}]}], "m": {}}, [[1], [{"a": 1}, {"a": 2}]], {"k": {"z": 1}, "j": 2}]),
+        json!([[], [[]], {"a": []}]),
+        json!("hello"),
+        json!(42),
+        json!([]),
+        json!({}),
+    ];
+    for case in cases {
+        let s = toon_rs::encode_to_string(&case, &opts).unwrap();
+        assert_eq!(from_reader(&s, &opts).unwrap(), case, "input:\n{s}");
+        let trickled: Value = toon_rs::decode_from_reader(Trickle(s.as_bytes()), &opts).unwrap();
+        assert_eq!(trickled, case);
+    }
+}
+
+#[test]
+fn reader_matches_decode_from_str() {
+    let opts = Options::default();
+    for s in [
+        "a: 1\nb:\n  c: [x]\n  d[3]: 1,2,3\n",
+        "items[2]{id,name}:\n  1,Ada\n  2,Bob\ncount: 2\n",
+        "[3]:\n  - 1\n  - two\n  - k: v\n    w: 2\n",
+        "list[2]:\n  - [2]: a,b\n  - -\n",
+        "t[2|]{a|b}:\n  1|2\n  3|4\n",
+        "\"quoted key\": \"x:y\"\nn: -0.5\n",
+        "{0}:\n",
+        "",
+    ] {
+        let expected: Value = toon_rs::decode_from_str(s, &opts).unwrap();
+        assert_eq!(from_reader(s, &opts).unwrap(), expected, "input:\n{s}");
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Role {
+    Admin,
+    Guest,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+enum Shape {
+    Circle { r: f64 },
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Row {
+    id: u32,
+    role: Role,
+    note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Doc {
+    rows: Vec<Row>,
+    shape: Shape,
+    pair: (u8, u8),
+    missing: Option<u8>,
+}
+
+#[test]
+fn reader_typed_deserialize() {
+    let s = "rows[2]{id,role,note}:\n  1,admin,hi\n  2,guest,null\nshape:\n  Circle:\n    r: 1.5\npair[2]: 3,4\nextra:\n  ignored[2]: 1,2\n";
+    let rdr = BufReader::new(s.as_bytes());
+    let doc: Doc = toon_rs::de::from_reader(rdr, &Options::default()).unwrap();
+    assert_eq!(
+        doc,
+        Doc {
+            rows: vec![
+                Row {
+                    id: 1,
+                    role: Role::Admin,
+                    note: Some("hi".into())
+                },
+                Row {
+                    id: 2,
+                    role: Role::Guest,
+                    note: None
+                },
+            ],
+            shape: Shape::Circle { r: 1.5 },
+            pair: (3, 4),
+            missing: None,
+        }
+    );
+}
+
+#[test]
+fn reader_checks_lengths_after_partial_visit() {
+    // The tuple visitor stops after two elements; the rest is still validated
+    let s = "pair[3]: 1,2\n";
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct P {
+        pair: (u8, u8),
+    }
+    let err = toon_rs::decode_from_reader::<_, P>(s.as_bytes(), &Options::default()).unwrap_err();
+    assert!(err.to_string().contains("array length mismatch"));
+}
+
+#[test]
+fn reader_strict_errors() {
+    let opts = Options::default();
+    for (s, needle, line) in [
+        ("a:\n   b: 1\n", "multiple of 2", 2),
+        ("a:\n\tb: 1\n", "tab character", 2),
+        (
+            "xs[3]:\n  - 1\n  - 2\n",
+            "header declares 3 elements but found 2",
+            1,
+        ),
+        (
+            "xs[2]: 1,2,3\n",
+            "header declares 2 elements but found 3",
+            1,
+        ),
+        ("t[2]{a,b}:\n  1,2\n", "tabular array has 1 rows", 1),
+        ("t[1]{a,b}:\n  1,2,3\n", "tabular row has 3 values", 2),
+        ("t[1]{a,b}:\n  1|2\n", "delimiter mismatch", 2),
+        ("t[1|]{a,b}:\n  1|2\n", "mismatched delimiter", 1),
+        ("xs[2]:\n  - 1\n\n  - 2\n", "blank line inside array", 3),
+        ("t[2]{a}:\n  1\n\n  2\n", "blank line inside table", 3),
+        ("rows:\n  @, a, a\n  - 1, 2\n", "duplicate header key", 2),
+        ("rows:\n  @, a, b\n  - 1\n", "row cell count", 3),
+        ("a:\n  oops\n", "missing colon", 2),
+        ("a\nb\n", "two primitives", 2),
+        ("s: \"open\n", "unterminated string", 1),
+    ] {
+        match from_reader(s, &opts).unwrap_err() {
+            toon_rs::Error::Syntax {
+                line: l, message, ..
+            } => {
+                assert!(message.contains(needle), "{s:?}: {message}");
+                assert_eq!(l, line, "{s:?}: {message}");
+            }
+            other => panic!("{s:?}: unexpected error {other}"),
+        }
+    }
+}
+
+#[test]
+fn reader_non_strict_is_lenient() {
+    let opts = Options {
+        strict: false,
+        ..Options::default()
+    };
+    let v = from_reader("a:\n   b: 1\nxs[2]:\n  - 1\n\n  - 2\n", &opts).unwrap();
+    assert_eq!(v, json!({"a": {"b": 1}, "xs": [1, 2]}));
+}
+
+#[test]
+fn reader_expand_paths() {
+    let opts = Options {
+        expand_paths: toon_rs::ExpandPaths::Safe,
+        ..Options::default()
+    };
+    let v = from_reader("a.b: 1\na.c: 2\n\"x.y\": 3\n", &opts).unwrap();
+    assert_eq!(v, json!({"a": {"b": 1, "c": 2}, "x.y": 3}));
+}
+
+#[test]
+fn reader_reports_io_errors() {
+    let bytes = b"a: \xff\n";
+    let err = toon_rs::decode_from_reader::<_, Value>(&bytes[..], &Options::default()).unwrap_err();
+    assert!(matches!(err, toon_rs::Error::Io(_)));
+}
+
+#[test]
+fn reader_accepts_hyphen_table_rows_only_when_lenient() {
+    let lenient = Options {
+        strict: false,
+        ..Options::default()
+    };
+    let s = "items[1]:\n  - users[2]{id,name}:\n    - 1,Ada\n    - 2,Bob\n    status: done\n";
+    let expected = json!({"items": [{"users": [{"id": 1, "name": "Ada"}, {"id": 2, "name": "Bob"}], "status": "done"}]});
+    assert_eq!(from_reader(s, &lenient).unwrap(), expected);
+    let v: Value = toon_rs::decode_from_str(s, &lenient).unwrap();
+    assert_eq!(v, expected);
+
+    // The spec has no `- ` table rows, so strict decoding rejects them
+    for s in [s, "t[2]{a,b}:\n  - 1,2\n  - 3,4\n"] {
+        let err = toon_rs::decode_from_str::<Value>(s, &Options::default()).unwrap_err();
+        assert_eq!(err.kind(), toon_rs::ErrorKind::LengthMismatch);
+        assert!(from_reader(s, &Options::default()).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "serde")]
+use toon_rs::edit::Document;
+use toon_rs::{ErrorKind, toon};
+
+const FIXTURE: &str = "\
+name: \"Ada\"
+\"display name\": Ada L.
+
+users[2]{id,email}:
+  1,ada@example.com
+  2,bob@example.com
+tags[3|]: a | b | c
+server:
+  host: localhost
+  port: 8080
+";
+
+fn doc(s: &str) -> Document {
+    s.parse().unwrap()
+}
+
+#[test]
+fn unedited_documents_round_trip_byte_for_byte() {
+    for input in [
+        "",
+        "\n\n",
+        "a: 1",
+        "a: 1\r\nb:\r\n  c: \"x\"   \r\n",
+        "[3]: 1,2,3",
+        "items[2]:\n  - id: 1\n    name: a\n\n  - \"quoted\"\n",
+        "rows[1\t]{a\tb}:\n  1\t2\n",
+    ] {
+        assert_eq!(doc(input).to_string(), input);
+    }
+}
+
+#[test]
+fn set_rewrites_only_the_token() {
+    let input = "name: \"Ada\"\nplain:   x  \nusers[2]{id,email}:\n  1,a@b\n  2,c@d\n";
+    let mut d = doc(input);
+    d.set("name", "Bob").unwrap();
+    d.set("plain", 7).unwrap();
+    d.set("users[1].email", "e, f").unwrap();
+    assert_eq!(
+        d.to_string(),
+        "name: \"Bob\"\nplain:   7  \nusers[2]{id,email}:\n  1,a@b\n  2,\"e, f\"\n"
+    );
+}
+
+#[test]
+fn rows_and_cells_update_the_length_marker() {
+    let mut d = doc("users[2]{id,name}:\n  1, Ada\n  2, Bob\ntags[2|]: a|b\n");
+    d.push("users", toon!({ "name": "Cy", "id": 3 })).unwrap();
+    d.insert("tags[0]", "z").unwrap();
+    assert_eq!(
+        d.to_string(),
+        "users[3]{id,name}:\n  1, Ada\n  2, Bob\n  3, Cy\ntags[3|]: z|a|b\n"
+    );
+
+    d.remove("users[0]").unwrap();
+    d.remove("tags[2]").unwrap();
+    d.remove("tags[0]").unwrap();
+    d.remove("tags[0]").unwrap();
+    assert_eq!(
+        d.to_string(),
+        "users[2]{id,name}:\n  2, Bob\n  3, Cy\ntags[0|]:\n"
+    );
+    d.push("tags", "x|y").unwrap();
+    assert_eq!(d.value().unwrap()["tags"], toon!(["x|y"]));
+    assert!(d.to_string().ends_with("tags[1|]: \"x|y\"\n"));
+}
+
+#[test]
+fn list_items_are_written_like_the_encoder() {
+    let mut d = doc("items[1]:\n  - id: 1\n    name: a\n");
+    d.push("items", toon!({ "id": 2, "tags": ["x"] })).unwrap();
+    d.push("items", "last").unwrap();
+    assert_eq!(
+        d.to_string(),
+        "items[3]:\n  - id: 1\n    name: a\n  - id: 2\n    tags[1]: x\n  - last\n"
+    );
+
+    // Removing the field on the hyphen line moves the next one up
+    d.remove("items[0].id").unwrap();
+    d.remove("items[1]").unwrap();
+    assert_eq!(d.to_string(), "items[2]:\n  - name: a\n  - last\n");
+    assert_eq!(
+        d.value().unwrap(),
+        toon!({ "items": [{ "name": "a" }, "last"] })
+    );
+}
+
+#[test]
+fn fields_keep_the_documents_indentation() {
+    let mut d = doc(FIXTURE);
+    d.insert("server.tls", toon!({ "enabled": true })).unwrap();
+    d.set("server.port", toon!([80, 443])).unwrap();
+    d.remove("\"display name\"").unwrap();
+    assert_eq!(
+        d.to_string(),
+        FIXTURE.replace("\"display name\": Ada L.\n", "").replace(
+            "  port: 8080\n",
+            "  port[2]: 80,443\n  tls:\n    enabled: true\n"
+        )
+    );
+    let value = d.value().unwrap();
+    assert_eq!(value["server"]["tls"]["enabled"], toon!(true));
+    assert_eq!(value["tags"][2], toon!("c"));
+}
+
+#[test]
+fn missing_keys_are_added_by_set() {
+    let mut d = doc("a:\n  b: 1");
+    d.set("a.c", "x").unwrap();
+    d.set("d", toon!({})).unwrap();
+    assert_eq!(d.to_string(), "a:\n  b: 1\n  c: x\nd:");
+
+    let mut d = doc("a:\n    b: 1\n");
+    d.set("a.c", toon!({ "d": 2 })).unwrap();
+    assert_eq!(d.to_string(), "a:\n    b: 1\n    c:\n        d: 2\n");
+}
+
+#[test]
+fn bad_paths_and_shapes_are_errors() {
+    let mut d = doc(FIXTURE);
+    let err = d.set("server.missing.x", 1).unwrap_err();
+    assert_eq!(err.path(), Some("server.missing.x"));
+    assert_eq!(
+        d.insert("name", 1).unwrap_err().kind(),
+        ErrorKind::DuplicateKey
+    );
+    assert_eq!(
+        d.push("tags", toon!([1])).unwrap_err().kind(),
+        ErrorKind::TypeMismatch
+    );
+    assert_eq!(
+        d.push("users", toon!({ "id": 3 })).unwrap_err().kind(),
+        ErrorKind::TypeMismatch
+    );
+    assert!(d.insert("tags[9]", "x").is_err());
+    assert!(d.set("users[", 1).is_err());
+    assert_eq!(d.to_string(), FIXTURE);
+
+    let err = "a: 1\n   b: 2\n".parse::<Document>().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Indentation);
+
+    // An unterminated quote swallows the delimiter, leaving the row short
+    let err = "users[2]{id,name}:\n  1,Ada\n  \"2,Bob\n"
+        .parse::<Document>()
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::LengthMismatch);
+    assert!(err.to_string().contains("line 3"));
+}
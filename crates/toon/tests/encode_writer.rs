@@ -0,0 +1,131 @@
+#![cfg(feature = "json")]
+use std::cell::Cell;
+use std::io::Write;
+
+use serde_json::json;
+use toon_rs::Options;
+
+/// Records every `write` call so tests can see output arriving in chunks.
+#[derive(Default)]
+struct Chunks(Vec<Vec<u8>>);
+
+impl Write for Chunks {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.push(buf.to_vec());
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Broken;
+
+impl Write for Broken {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("disk full"))
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Fails every `write`, counting them.
+#[derive(Default)]
+struct Failing(usize);
+
+impl Write for Failing {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += 1;
+        Err(std::io::Error::other("disk full"))
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A large map that counts the entries taken from it.
+struct Counted<'a>(&'a Cell<usize>);
+
+impl serde::Serialize for Counted<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map((0..5000).map(|i| {
+            self.0.set(self.0.get() + 1);
+            (format!("key{i}"), format!("value number {i}"))
+        }))
+    }
+}
+
+#[test]
+fn writer_output_matches_string_output() {
+    let opts = Options::default();
+    for v in [
+        json!({"a": 1, "b": {"c": [1, 2]}, "rows": [{"x": 1}, {"x": 2}]}),
+        json!([1, {"k": "v"}, [2, 3]]),
+        json!("scalar"),
+        json!({}),
+    ] {
+        let expected = toon_rs::ser::to_string_streaming(&v, &opts).unwrap();
+
+        let mut out = Vec::new();
+        toon_rs::ser::to_writer_streaming(&mut out, &v, &opts).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let mut out = Vec::new();
+        toon_rs::encode_to_writer(&mut out, &v, &opts).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+
+        let mut out = Vec::new();
+        toon_rs::ser::to_writer(&mut out, &v, &opts).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            toon_rs::ser::to_string(&v, &opts).unwrap()
+        );
+
+        let mut s = String::new();
+        toon_rs::ser::to_fmt_writer_streaming(&mut s, &v, &opts).unwrap();
+        assert_eq!(s, expected);
+    }
+}
+
+#[test]
+fn writer_flushes_in_chunks() {
+    let opts = Options::default();
+    let big: serde_json::Map<String, serde_json::Value> = (0..5000)
+        .map(|i| (format!("key{i}"), json!(format!("value number {i}"))))
+        .collect();
+    let mut sink = Chunks::default();
+    toon_rs::ser::to_writer_streaming(&mut sink, &big, &opts).unwrap();
+    assert!(sink.0.len() > 1, "expected several chunks");
+    assert!(sink.0.iter().all(|c| c.len() < 16 * 1024));
+    let joined: Vec<u8> = sink.0.concat();
+    assert_eq!(
+        String::from_utf8(joined).unwrap(),
+        toon_rs::ser::to_string_streaming(&big, &opts).unwrap()
+    );
+}
+
+#[test]
+fn writer_reports_io_errors() {
+    let err = toon_rs::encode_to_writer(Broken, &json!({"a": 1}), &Options::default()).unwrap_err();
+    assert!(matches!(err, toon_rs::Error::Io(_)));
+}
+
+#[test]
+fn writer_stops_at_the_first_failed_write() {
+    let opts = Options::default();
+    let taken = Cell::new(0);
+    let mut sink = Failing::default();
+    let err = toon_rs::ser::to_writer_streaming(&mut sink, &Counted(&taken), &opts).unwrap_err();
+    assert!(matches!(err, toon_rs::Error::Io(_)), "{err}");
+    assert_eq!(sink.0, 1);
+    assert!(taken.get() < 5000, "took {} entries", taken.get());
+
+    let big: serde_json::Map<String, serde_json::Value> = (0..5000)
+        .map(|i| (format!("key{i}"), json!(format!("value number {i}"))))
+        .collect();
+    let mut sink = Failing::default();
+    let err = toon_rs::encode_to_writer(&mut sink, &big, &opts).unwrap_err();
+    assert!(matches!(err, toon_rs::Error::Io(_)), "{err}");
+    assert_eq!(sink.0, 1);
+}
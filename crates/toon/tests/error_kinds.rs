@@ -0,0 +1,119 @@
+#![cfg(feature = "serde")]
+use serde::Deserialize;
+use toon_rs::{Error, ErrorKind, ExpandPaths, Options, Value};
+
+fn kind_of(s: &str) -> ErrorKind {
+    toon_rs::from_str::<Value>(s, &Options::default())
+        .unwrap_err()
+        .kind()
+}
+
+#[test]
+fn streaming_decoder_kinds() {
+    let cases = [
+        ("items[3]: a,b", ErrorKind::LengthMismatch),
+        ("t[2]{a,b}:\n  1,2\n", ErrorKind::LengthMismatch),
+        ("t[1]{a,b}:\n  1,2,3\n", ErrorKind::LengthMismatch),
+        ("t[1|]{a,b}:\n  1|2\n", ErrorKind::DelimiterMismatch),
+        ("t[1]{a,b}:\n  1,2,\n", ErrorKind::LengthMismatch),
+        (
+            "rows:\n  @, a, b\n  - 1, 2,\n",
+            ErrorKind::DelimiterMismatch,
+        ),
+        ("a:\n\tb: 1\n", ErrorKind::Indentation),
+        ("a:\n   b: 1\n", ErrorKind::Indentation),
+        ("a[2]:\n  - 1\n\n  - 2\n", ErrorKind::Indentation),
+        ("a: \"oops\n", ErrorKind::UnterminatedString),
+        ("a: \"bad \\q escape\"\n", ErrorKind::InvalidEscape),
+        ("rows:\n  @, a, a\n  - 1, 2\n", ErrorKind::DuplicateKey),
+        ("a: 1\nb\n", ErrorKind::Syntax),
+    ];
+    for (input, kind) in cases {
+        assert_eq!(kind_of(input), kind, "input: {:?}", input);
+    }
+}
+
+#[test]
+fn batch_parser_matches_streaming_kinds() {
+    for input in [
+        "items[3]: a,b",
+        "a: \"bad \\q\"\n",
+        "rows:\n  @, a, b\n  - 1, 2,\n",
+    ] {
+        let err = toon_rs::decode::parser::parse_to_value_with_strict(input, true).unwrap_err();
+        assert_eq!(err.kind(), kind_of(input), "input: {:?}", input);
+    }
+}
+
+#[test]
+fn type_mismatches_are_categorized() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct User {
+        id: u32,
+    }
+
+    let err = toon_rs::from_str::<User>("id: abc\n", &Options::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+    assert!(matches!(err, Error::Data { .. }));
+    assert!(
+        err.to_string()
+            .starts_with("id: invalid type: string \"abc\"")
+    );
+
+    let err = toon_rs::from_str::<User>("name: x\n", &Options::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+    let err = toon_rs::from_value::<User>(toon_rs::toon!({ "id": -1 })).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+
+    let err = toon_rs::from_str::<User>("id: 1\nid: 2\n", &Options::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DuplicateKey);
+}
+
+#[test]
+fn path_expansion_conflicts_are_categorized() {
+    let opts = Options {
+        expand_paths: ExpandPaths::Safe,
+        ..Options::default()
+    };
+    let err = toon_rs::from_str::<Value>("a: 1\na.b: 2\n", &opts).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::PathExpansionConflict);
+}
+
+#[test]
+fn value_deserializer_error_keeps_kind() {
+    let de = toon_rs::de::Deserializer::from_value(toon_rs::toon!("text"));
+    let err = <bool as Deserialize>::deserialize(de).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+}
+
+#[test]
+fn custom_messages_are_other() {
+    assert_eq!(
+        <Error as serde::de::Error>::custom("nope").kind(),
+        ErrorKind::Other
+    );
+}
+
+#[test]
+fn kinds_have_stable_names() {
+    assert_eq!(ErrorKind::LengthMismatch.as_str(), "length-mismatch");
+    assert_eq!(kind_of("a:\n   b: 1\n").to_string(), "indentation");
+    assert_eq!(
+        kind_of("t[1|]{a,b}:\n  1|2\n").as_str(),
+        "delimiter-mismatch"
+    );
+}
+
+#[cfg(feature = "de_direct")]
+#[test]
+fn direct_deserializer_kinds() {
+    use toon_rs::de::direct::from_str;
+
+    let err = from_str::<Value>("rows:\n  @, a, b\n  - 1\n", &Options::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::LengthMismatch);
+    let err = from_str::<Value>("a:\n\tb: 1\n", &Options::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Indentation);
+    let err = from_str::<bool>("text\n", &Options::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+}
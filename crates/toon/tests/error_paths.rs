@@ -0,0 +1,121 @@
+#![cfg(feature = "serde")]
+use serde::Deserialize;
+use toon_rs::{Error, ErrorKind, Options};
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct User {
+    id: u32,
+    email: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Doc {
+    users: Vec<User>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Meta {
+    tags: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Tagged {
+    meta: Meta,
+}
+
+const TABLE: &str = "users[2]{id,email}:\n  1,ada@example.com\n  2,3\n";
+
+#[test]
+fn tabular_cell_error_names_row_and_field() {
+    let err = toon_rs::from_str::<Doc>(TABLE, &Options::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+    assert_eq!(err.path(), Some("users[1].email"));
+    let span = err.span().unwrap();
+    assert_eq!((span.line, span.column), (3, 5));
+    assert_eq!(
+        err.to_string(),
+        "users[1].email: invalid type: integer `3`, expected a string at line 3, column 5"
+    );
+}
+
+#[test]
+fn inline_array_element_error_names_index() {
+    let err =
+        toon_rs::from_str::<Tagged>("meta:\n  tags[2]: 1,x\n", &Options::default()).unwrap_err();
+    assert_eq!(err.path(), Some("meta.tags[1]"));
+    assert_eq!(err.span().map(|s| (s.line, s.column)), Some((2, 14)));
+}
+
+#[test]
+fn missing_field_points_at_the_item() {
+    let s = "users[1]:\n  - id: 1\n";
+    let err = toon_rs::from_str::<Doc>(s, &Options::default()).unwrap_err();
+    assert_eq!(err.path(), Some("users[0]"));
+    assert!(
+        err.to_string()
+            .starts_with("users[0]: missing field `email`")
+    );
+    assert_eq!(err.span().map(|s| s.line), Some(2));
+}
+
+#[test]
+fn odd_keys_are_quoted_in_paths() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Odd {
+        #[serde(rename = "a key")]
+        a_key: u8,
+    }
+    let err = toon_rs::from_str::<Odd>("\"a key\": x\n", &Options::default()).unwrap_err();
+    assert_eq!(err.path(), Some("\"a key\""));
+}
+
+#[test]
+fn syntax_errors_have_no_path() {
+    let err = toon_rs::from_str::<Doc>("users[2]: 1\n", &Options::default()).unwrap_err();
+    assert!(matches!(err, Error::Syntax { .. }));
+    assert_eq!(err.path(), None);
+}
+
+#[test]
+fn value_errors_have_paths_but_no_span() {
+    let value =
+        toon_rs::toon!({ "users": [{ "id": 1, "email": "a@b" }, { "id": "two", "email": "c@d" }] });
+    let err = toon_rs::from_value::<Doc>(value).unwrap_err();
+    assert_eq!(err.path(), Some("users[1].id"));
+    assert_eq!(err.span(), None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn reader_errors_have_paths() {
+    let err =
+        toon_rs::decode_from_reader::<_, Doc>(TABLE.as_bytes(), &Options::default()).unwrap_err();
+    assert_eq!(err.path(), Some("users[1].email"));
+    assert_eq!(err.span().map(|s| s.line), Some(3));
+}
+
+#[cfg(feature = "de_direct")]
+#[test]
+fn direct_deserializer_reports_paths() {
+    use toon_rs::de::direct::from_str;
+
+    let err = from_str::<Doc>(
+        "users:\n  @, id, email\n  - 1, ada\n  - x, bob\n",
+        &Options::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.path(), Some("users[1].id"));
+    assert_eq!(err.span().map(|s| (s.line, s.column)), Some((4, 5)));
+
+    let err = from_str::<Tagged>("meta:\n  tags[2]: 1,x\n", &Options::default()).unwrap_err();
+    assert_eq!(err.path(), Some("meta.tags[1]"));
+
+    let err = from_str::<Doc>("users[1]:\n  - id: 1\n", &Options::default()).unwrap_err();
+    assert_eq!(err.path(), Some("users[0]"));
+    assert_eq!(err.span().map(|s| s.line), Some(2));
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "serde")]
+use toon_rs::{Error, Options, Span, Value};
+
+#[test]
+fn bad_cell_in_tabular_row_points_at_the_cell() {
+    let s = "users[2]{id,name,email}:\n  1,Ada,ada@example.com\n  2,Bob,\"bob@example\n";
+    let err = toon_rs::from_str::<Value>(s, &Options::default()).unwrap_err();
+    let span = err.span().expect("syntax error");
+    assert_eq!((span.line, span.column), (3, 9));
+    assert_eq!(&s[span.offset..span.offset + span.len], "\"bob@example");
+    assert_eq!(
+        err.to_string(),
+        "syntax at line 3, column 9: unterminated string"
+    );
+    assert_eq!(
+        err.snippet(s).unwrap(),
+        "3 |   2,Bob,\"bob@example\n  |         ^^^^^^^^^^^^"
+    );
+}
+
+#[test]
+fn row_width_error_covers_the_row() {
+    let s = "users[2]{id,name}:\n  1,Ada\n  2,Bob,extra\n";
+    let err = toon_rs::from_str::<Value>(s, &Options::default()).unwrap_err();
+    let span = err.span().unwrap();
+    assert_eq!(
+        (span.line, span.column, span.len),
+        (3, 3, "2,Bob,extra".len())
+    );
+}
+
+#[test]
+fn tab_indentation_points_at_the_tab() {
+    let s = "a:\n\tb: 1\n";
+    let err = toon_rs::from_str::<Value>(s, &Options::default()).unwrap_err();
+    assert_eq!(
+        err.span(),
+        Some(Span {
+            offset: 3,
+            line: 2,
+            column: 1,
+            len: 1
+        })
+    );
+    assert_eq!(err.snippet(s).unwrap(), "2 | \tb: 1\n  | ^");
+}
+
+#[test]
+fn batch_parser_reports_columns() {
+    let s = "x: 1\ny: \"unterminated\n";
+    let err = toon_rs::decode::parser::parse_to_value_with_strict(s, true).unwrap_err();
+    let span = err.span().unwrap();
+    assert_eq!((span.offset, span.line, span.column), (8, 2, 4));
+    assert!(matches!(err, Error::Syntax { line: 2, .. }));
+}
+
+#[test]
+fn span_of_and_locate_count_chars() {
+    let s = "k: é\nname: \"x\n";
+    let part = &s[s.find('"').unwrap()..s.len() - 1];
+    let span = Span::of(s, part).unwrap();
+    assert_eq!((span.line, span.column, span.len), (2, 7, 2));
+    assert_eq!(Span::locate(s, 4, 2).column, 4);
+    assert_eq!(Span::of(s, "elsewhere"), None);
+}
+
+#[test]
+fn snippet_without_column_underlines_the_line() {
+    let span = Span::line(2);
+    assert_eq!(span.to_string(), "line 2");
+    assert_eq!(span.snippet("a: 1\nb\n"), "2 | b\n  | ^");
+    assert_eq!(span.snippet(""), "");
+}
+
+#[cfg(feature = "de_direct")]
+#[test]
+fn direct_deserializer_reports_spans() {
+    let s = "rows:\n  @, a, b\n  - 1\n";
+    let err = toon_rs::de::direct::from_str::<Value>(s, &Options::default()).unwrap_err();
+    let span = err.span().unwrap();
+    assert_eq!((span.line, span.column), (3, 5));
+    let err =
+        toon_rs::de::direct::from_str::<Value>("a:\n   b: 1\n", &Options::default()).unwrap_err();
+    assert_eq!(err.span().map(|s| (s.line, s.len)), Some((2, 3)));
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "json")]
+use toon_rs::{Delimiter, ErrorKind, FormatOptions, KeyFolding, Value};
+
+#[test]
+fn canonicalizes_layout_quoting_and_tables() {
+    let input = "\
+name:   \"api\"
+server:
+    host: \"localhost\"
+    ports[2]: 80 , 443
+users[2]:
+    - id: 1
+      email: \"a@b\"
+    - id: 2
+      email: c@d
+";
+    let out = toon_rs::fmt(input, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        out,
+        "name: api\nserver:\n  host: localhost\n  ports[2]: 80,443\nusers[2]{id,email}:\n  1,a@b\n  2,c@d"
+    );
+    assert!(toon_rs::is_formatted(&out, &FormatOptions::default()).unwrap());
+    assert!(toon_rs::is_formatted(&format!("{}\n", out), &FormatOptions::default()).unwrap());
+    assert!(!toon_rs::is_formatted(input, &FormatOptions::default()).unwrap());
+}
+
+#[test]
+fn keeps_quotes_that_are_needed_and_values() {
+    let input = "a: \"1\"\nb: \"x, y\"\nc: \"\"\nd: 1.50\ne: \"-\"\n\"odd key\": true\n";
+    let out = toon_rs::fmt(input, &FormatOptions::default()).unwrap();
+    assert_eq!(
+        out,
+        "a: \"1\"\nb: \"x, y\"\nc: \"\"\nd: 1.5\ne: \"-\"\n\"odd key\": true"
+    );
+    let before: Value = input.parse().unwrap();
+    let after: Value = out.parse().unwrap();
+    assert_eq!(before, after);
+}
+
+#[test]
+fn applies_the_chosen_layout() {
+    let opts = FormatOptions {
+        delimiter: Delimiter::Pipe,
+        indent: 4,
+        key_folding: KeyFolding::Safe,
+        ..FormatOptions::default()
+    };
+    let input = "a:\n  b:\n    c: 1\nt[2]: \"x|y\",z\n";
+    assert_eq!(
+        toon_rs::fmt(input, &opts).unwrap(),
+        "a.b.c: 1\nt[2|]: \"x|y\"|z"
+    );
+
+    let nested = "a:\n  b: 1\n";
+    let opts = FormatOptions {
+        indent: 4,
+        ..FormatOptions::default()
+    };
+    assert_eq!(toon_rs::fmt(nested, &opts).unwrap(), "a:\n    b: 1");
+}
+
+#[test]
+fn invalid_input_is_an_error() {
+    let err = toon_rs::fmt("t[3]: a,b\n", &FormatOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::LengthMismatch);
+}
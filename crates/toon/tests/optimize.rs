@@ -0,0 +1,62 @@
+#![cfg(feature = "json")]
+use toon_rs::stats::ApproxTokenizer;
+use toon_rs::{
+    Delimiter, ExpandPaths, KeyFolding, Options, Value, decode_from_str, encode_optimized, toon,
+};
+
+#[test]
+fn folding_is_chosen_only_when_it_decodes_back() -> Result<(), toon_rs::Error> {
+    let value = toon!({ "server": { "http": { "port": 8080 } } });
+    let options = Options::default().optimize_for(&value, &ApproxTokenizer);
+    assert_eq!(
+        (options.key_folding, options.expand_paths),
+        (KeyFolding::Safe, ExpandPaths::Safe)
+    );
+    let (text, options) = encode_optimized(&value, &Options::default(), &ApproxTokenizer)?;
+    assert_eq!(text, "server.http.port: 8080");
+    assert_eq!(decode_from_str::<Value>(&text, &options)?, value);
+
+    // Folding `a.b.c` next to a literal `a.b` key would not expand back
+    let value = toon!({ "a": { "b": { "c": 1 } }, "a.b": 2 });
+    let options = Options::default().optimize_for(&value, &str::len);
+    assert_eq!(options.key_folding, KeyFolding::Off);
+    Ok(())
+}
+
+#[test]
+fn arrays_get_their_own_layout() -> Result<(), toon_rs::Error> {
+    let value = toon!({
+        "data": {
+            "rows": [{ "id": 1, "name": "Ada" }, { "id": 2, "name": "Bob" }],
+            "tags": ["x", "y"]
+        }
+    });
+    // A tokenizer that finds braces expensive prefers list items to a table
+    let braces = |text: &str| text.len() + 100 * text.matches('{').count();
+    let (text, options) = encode_optimized(&value, &Options::default(), &braces)?;
+    assert_eq!(
+        text,
+        "data:\n  rows[2]:\n    - id: 1\n      name: Ada\n    - id: 2\n      name: Bob\n  tags[2]: x,y"
+    );
+    assert_eq!(decode_from_str::<Value>(&text, &options)?, value);
+
+    // The same for a root table
+    let rows = toon!([{ "id": 1 }, { "id": 2 }]);
+    let (text, _) = encode_optimized(&rows, &Options::default(), &braces)?;
+    assert_eq!(text, "[2]:\n  - id: 1\n  - id: 2");
+    Ok(())
+}
+
+#[test]
+fn ties_keep_the_given_options() -> Result<(), toon_rs::Error> {
+    let value = toon!({ "ids": [1, 2, 3] });
+    let pipe = Options {
+        delimiter: Delimiter::Pipe,
+        ..Options::default()
+    };
+    let words = |text: &str| text.split_whitespace().count();
+    assert_eq!(pipe.optimize_for(&value, &words).delimiter, Delimiter::Pipe);
+    let (text, _) = encode_optimized(&value, &pipe, &words)?;
+    assert_eq!(text, "ids[3|]: 1|2|3");
+    Ok(())
+}
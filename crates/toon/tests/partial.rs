@@ -0,0 +1,157 @@
+#![cfg(feature = "serde")]
+use toon_rs::{ExpandPaths, Map, Open, OpenKind, Options, PartialParser, Value, toon};
+
+fn open(path: &str, kind: OpenKind) -> Open {
+    Open {
+        path: path.into(),
+        kind,
+    }
+}
+
+#[test]
+fn every_prefix_decodes_and_the_end_matches_finish() -> Result<(), toon_rs::Error> {
+    let value = toon!({
+        "title": "Report, \"final\"",
+        "users": [{ "id": 1, "name": "Ada" }, { "id": 2, "name": "Bob" }],
+        "items": [{ "sku": "A", "tags": ["x", "y"] }, 3],
+        "done": true
+    });
+    let text = toon_rs::encode_to_string(&value, &Options::default())?;
+    let mut parser = PartialParser::new(&Options::default());
+    let mut last = None;
+    for c in text.chars() {
+        last = Some(parser.feed(c.encode_utf8(&mut [0; 4])));
+    }
+    let last = last.unwrap();
+    assert_eq!(last.value, value);
+    assert_eq!(parser.finish()?, value);
+    Ok(())
+}
+
+#[test]
+fn open_arrays_and_strings_are_marked_along_the_last_line() {
+    let mut parser = PartialParser::default();
+    let partial = parser.feed("items[2]:\n  - id: 1\n    tags[3]: a,b");
+    assert_eq!(
+        partial.value,
+        toon!({ "items": [{ "id": 1, "tags": ["a", "b"] }] })
+    );
+    assert_eq!(
+        partial.open,
+        [
+            open(
+                "items",
+                OpenKind::Array {
+                    declared: 2,
+                    received: 1
+                }
+            ),
+            open(
+                "items[0].tags",
+                OpenKind::Array {
+                    declared: 3,
+                    received: 2
+                }
+            ),
+            open("items[0].tags[1]", OpenKind::String),
+        ]
+    );
+
+    // With its newline the line is done, though the array is not
+    let partial = parser.feed(",c\n");
+    assert_eq!(
+        partial.open,
+        [open(
+            "items",
+            OpenKind::Array {
+                declared: 2,
+                received: 1
+            }
+        )]
+    );
+}
+
+#[test]
+fn unfinished_lines_are_read_as_far_as_they_go() {
+    let mut parser = PartialParser::default();
+    // A row leaves out the fields it has not reached
+    let partial = parser.feed("t[2]{a,b,c}:\n  1,x");
+    assert_eq!(partial.value, toon!({ "t": [{ "a": 1, "b": "x" }] }));
+    assert_eq!(partial.open[1], open("t[0].b", OpenKind::String));
+
+    // A half-written escape is left out of a string still in quotes
+    let mut parser = PartialParser::default();
+    let partial = parser.feed("a:\n  note: \"say \\");
+    assert_eq!(partial.value, toon!({ "a": { "note": "say " } }));
+    assert_eq!(partial.open, [open("a.note", OpenKind::String)]);
+
+    // A key still being written is left out
+    let partial = parser.feed("\"\"\nna");
+    assert_eq!(partial.value, toon!({ "a": { "note": "say \"" } }));
+    assert!(partial.open.is_empty());
+}
+
+/// `value` with each object collected as a `Map`, which keeps one entry per key.
+fn collected(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (key, collected(value)))
+                .collect::<Map>(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(collected).collect()),
+        value => value,
+    }
+}
+
+#[test]
+fn finished_lines_read_as_the_batch_parser_reads_them() {
+    for text in [
+        "a: 1\nusers[2]{id,name}:\n  1,Ada\n  2,Bob\nnested:\n  x: 1\n  y[2]: 1,2\nb: true\n",
+        "\n\na: 1\n\nb:\n  c: 1\n\nd: 2\n",
+        "a: 1\nb: 2\na: 3\n",
+        "items[2]:\n  - x: 1\n    y: 2\n  - 3\nz: 1\n",
+        // The parser nests, stops at or never starts root entries here
+        "a:\nb: 1\nc: 2\n",
+        "t[2]{a}:\nb: 1\nc: 2\n",
+        "a: 1\n    x: 2\n  y: 3\nb: 2\nc: 3\n",
+        "  a: 1\nb: 2\nc: 3\n",
+        "[2]:\n  - a: 1\n  - b: 2\n",
+        "x\na: 1\nb: 2\n",
+    ] {
+        let mut parser = PartialParser::default();
+        for (at, c) in text.char_indices() {
+            let partial = parser.feed(c.encode_utf8(&mut [0; 4]));
+            if c == '\n' {
+                let prefix = &text[..=at];
+                let expected =
+                    toon_rs::decode::parser::Parser::from_input_with_strict(prefix, false)
+                        .parse_document();
+                assert_eq!(partial.value, collected(expected), "{prefix:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn folded_keys_are_expanded_when_asked() {
+    let options = Options {
+        expand_paths: ExpandPaths::Safe,
+        ..Options::default()
+    };
+    let mut parser = PartialParser::new(&options);
+    let partial = parser.feed("server.host: local");
+    assert_eq!(partial.value, toon!({ "server": { "host": "local" } }));
+    assert_eq!(partial.open, [open("server.host", OpenKind::String)]);
+    assert_eq!(parser.text(), "server.host: local");
+    assert_eq!(parser.finish().unwrap(), partial.value);
+
+    // Entries read before the one being written still merge with it
+    let mut parser = PartialParser::new(&options);
+    parser.feed("server.host: local\nserver.port: 80\nserver.tls");
+    let partial = parser.feed(": true\n");
+    assert_eq!(
+        partial.value,
+        toon!({ "server": { "host": "local", "port": 80, "tls": true } })
+    );
+}
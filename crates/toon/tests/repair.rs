@@ -0,0 +1,135 @@
+#![cfg(feature = "serde")]
+use toon_rs::repair::{self, Fix, FixKind, RepairOptions};
+use toon_rs::{ErrorKind, toon};
+
+#[test]
+fn fenced_blocks_are_preferred_and_other_languages_skipped() {
+    let reply =
+        "data: prose\nmore: prose\n```json\n{\"a\": 1}\n```\n~~~\nb: 2\n~~~\n```TOON\nc: 3\n";
+    let found: Vec<&str> = repair::blocks(reply)
+        .into_iter()
+        .map(|r| &reply[r])
+        .collect();
+    // An unclosed fence runs to the end of the text
+    assert_eq!(found, ["b: 2", "c: 3"]);
+
+    // Without fences, runs of `key:` lines and what is nested under them
+    let reply = "Sure! Note: one line is not enough.\n\nitems[3]:\n  - a\n  - b\ncount: 2\n\nHope that helps.";
+    let found: Vec<&str> = repair::blocks(reply)
+        .into_iter()
+        .map(|r| &reply[r])
+        .collect();
+    assert_eq!(found, ["items[3]:\n  - a\n  - b\ncount: 2"]);
+
+    // Indented prose with non-ASCII text before its colon is not a key
+    let reply = "  d[0é]: see below
+   café: ouvert
+items[1]:
+  - a";
+    let found: Vec<&str> = repair::blocks(reply)
+        .into_iter()
+        .map(|r| &reply[r])
+        .collect();
+    assert_eq!(found, ["items[1]:\n  - a"]);
+}
+
+#[test]
+fn counts_and_trailing_delimiters_are_fixed() -> Result<(), toon_rs::Error> {
+    let reply = "The result:\nitems[3]:\n  - a\n  - b\ntags[2]: x,y,\nids[2|]: 1|2|3\n\nDone.";
+    let repaired = repair::repair(reply, &RepairOptions::default())?;
+    assert_eq!(
+        &reply[repaired.range.clone()],
+        "items[3]:\n  - a\n  - b\ntags[2]: x,y,\nids[2|]: 1|2|3"
+    );
+    assert_eq!(
+        repaired.text,
+        "items[2]:\n  - a\n  - b\ntags[2]: x,y\nids[3|]: 1|2|3"
+    );
+    assert_eq!(
+        repaired.fixes,
+        [
+            Fix {
+                kind: FixKind::Length,
+                line: 1,
+                message: "length 3 changed to 2".into()
+            },
+            Fix {
+                kind: FixKind::TrailingDelimiter,
+                line: 4,
+                message: "removed the delimiter after the last value".into()
+            },
+            Fix {
+                kind: FixKind::Length,
+                line: 5,
+                message: "length 2 changed to 3".into()
+            },
+        ]
+    );
+    assert_eq!(
+        repaired.value,
+        toon!({ "items": ["a", "b"], "tags": ["x", "y"], "ids": [1, 2, 3] })
+    );
+    Ok(())
+}
+
+#[test]
+fn drifting_indentation_is_evened_out() -> Result<(), toon_rs::Error> {
+    let toon = "user:\n   id: 1\n   tags[2]:\n\t\t- x\n\t\t- y\nok: true";
+    let repaired = repair::fix(toon, &RepairOptions::default())?;
+    assert_eq!(
+        repaired.text,
+        "user:\n  id: 1\n  tags[2]:\n    - x\n    - y\nok: true"
+    );
+    let fixes: Vec<(FixKind, usize)> = repaired.fixes.iter().map(|f| (f.kind, f.line)).collect();
+    assert_eq!(
+        fixes,
+        [
+            (FixKind::Indentation, 2),
+            (FixKind::Indentation, 3),
+            (FixKind::Indentation, 4),
+            (FixKind::Indentation, 5),
+        ]
+    );
+    assert_eq!(
+        repaired.fixes[3].message,
+        "tab indentation changed to 4 spaces"
+    );
+    assert_eq!(
+        repaired.value,
+        toon!({ "user": { "id": 1, "tags": ["x", "y"] }, "ok": true })
+    );
+    Ok(())
+}
+
+#[test]
+fn cells_split_on_the_delimiter_are_quoted_unless_turned_off() -> Result<(), toon_rs::Error> {
+    let toon = "people[2]{id,name,age}:\n  1,Lovelace, Ada,36\n  2,Hopper, Grace Brewster,85";
+    let repaired = repair::fix(toon, &RepairOptions::default())?;
+    assert_eq!(
+        repaired.text,
+        "people[2]{id,name,age}:\n  1,\"Lovelace, Ada\",36\n  2,\"Hopper, Grace Brewster\",85"
+    );
+    assert_eq!(
+        repaired.fixes[0].message,
+        "quoted \"Lovelace, Ada\", which contains the delimiter"
+    );
+    assert_eq!(repaired.value["people"][1]["age"], toon!(85));
+
+    // Nothing is guessed when the extra cells are not all strings
+    let err = repair::fix("t[1]{a,b}:\n  1,2,3", &RepairOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::LengthMismatch);
+
+    let options = RepairOptions {
+        quote_cells: false,
+        ..RepairOptions::default()
+    };
+    let err = repair::fix(toon, &options).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::LengthMismatch);
+    Ok(())
+}
+
+#[test]
+fn text_without_toon_is_an_error() {
+    let err = repair::repair("No data today.", &RepairOptions::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Syntax);
+}
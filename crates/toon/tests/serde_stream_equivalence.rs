@@ -14,3 +14,21 @@ fn streaming_and_value_paths_match() -> Result<(), Box<dyn std::error::Error>> {
     assert_eq!(s1, s2);
     Ok(())
 }
+
+#[test]
+fn list_item_lines_match_the_encoders_list() -> Result<(), Box<dyn std::error::Error>> {
+    let items = json!([1, "a|b", null, [1, 2], {"c": {"d": [3]}}, {}, []]);
+    let opts = toon_rs::Options {
+        delimiter: toon_rs::Delimiter::Pipe,
+        ..toon_rs::Options::default()
+    };
+    let mut out = format!("[{}|]:", items.as_array().unwrap().len());
+    for item in items.as_array().unwrap() {
+        for line in toon_rs::ser::list_item_lines(item, &opts)? {
+            out.push('\n');
+            out.push_str(&line);
+        }
+    }
+    assert_eq!(out, toon_rs::ser::to_string_streaming(&items, &opts)?);
+    Ok(())
+}
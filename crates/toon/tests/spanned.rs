@@ -0,0 +1,159 @@
+#![cfg(feature = "serde")]
+use serde::Deserialize;
+use toon_rs::{ErrorKind, ExpandPaths, Options, Spanned, Value};
+
+#[derive(Debug, Deserialize)]
+struct Server {
+    host: Spanned<String>,
+    port: u16,
+}
+
+#[derive(Debug, Deserialize)]
+struct Config {
+    name: String,
+    timeout: Spanned<u32>,
+    server: Spanned<Server>,
+    tags: Spanned<Vec<String>>,
+    users: Vec<Spanned<User>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    id: Spanned<u32>,
+    name: String,
+}
+
+const DOC: &str = "\
+name: api
+timeout: 90000
+server:
+  host: example.com
+  port: 8080
+tags[2]: a,b
+users[2]{id,name}:
+  1,Ada
+  2,Bob
+";
+
+fn text<T>(s: &Spanned<T>) -> &'static str {
+    &DOC[s.start()..s.end()]
+}
+
+fn check(config: &Config) {
+    assert_eq!(config.name, "api");
+    let span = config.timeout.span();
+    assert_eq!((span.line, span.column), (2, 10));
+    assert_eq!(text(&config.timeout), "90000");
+    assert_eq!(*config.timeout.get_ref(), 90000);
+
+    assert_eq!(text(&config.server), "host: example.com\n  port: 8080");
+    assert_eq!(config.server.span().line, 4);
+    assert_eq!(config.server.get_ref().port, 8080);
+    let host = &config.server.get_ref().host;
+    assert_eq!((host.span().line, host.span().column), (4, 9));
+    assert_eq!(text(host), "example.com");
+
+    assert_eq!(text(&config.tags), "a,b");
+    assert_eq!(config.tags.get_ref(), &["a", "b"]);
+
+    assert_eq!(text(&config.users[1]), "2,Bob");
+    let id = &config.users[1].get_ref().id;
+    assert_eq!((id.span().line, id.span().column, *id.get_ref()), (9, 3, 2));
+    assert_eq!(config.users[1].get_ref().name, "Bob");
+}
+
+#[test]
+fn from_str_records_spans() {
+    let config: Config = toon_rs::from_str(DOC, &Options::default()).unwrap();
+    check(&config);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn reader_records_spans() {
+    let config: Config = toon_rs::decode_from_reader(DOC.as_bytes(), &Options::default()).unwrap();
+    check(&config);
+}
+
+#[test]
+fn spans_survive_path_expansion() {
+    let opts = Options {
+        expand_paths: ExpandPaths::Safe,
+        ..Options::default()
+    };
+    let config: Config = toon_rs::from_str(DOC, &opts).unwrap();
+    check(&config);
+
+    #[derive(Debug, Deserialize)]
+    struct Nested {
+        server: Server,
+    }
+    let doc = "server.host: example.com\nserver.port: 80\n";
+    let nested: Nested = toon_rs::from_str(doc, &opts).unwrap();
+    let host = nested.server.host;
+    assert_eq!(&doc[host.start()..host.end()], "example.com");
+
+    // Errors found after expansion still point into the document
+    let err = toon_rs::from_str::<Nested>("server.host: h\nserver.port: x\n", &opts).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+    assert_eq!(err.path(), Some("server.port"));
+    assert_eq!(err.span().map(|s| (s.line, s.column)), Some((2, 14)));
+}
+
+#[cfg(feature = "de_direct")]
+#[test]
+fn direct_deserializer_records_spans() {
+    // `de_direct` reads tables in the `@<delim>` header form
+    let doc = DOC.replace(
+        "users[2]{id,name}:\n  1,Ada\n  2,Bob\n",
+        "users:\n  @, id, name\n  - 1, Ada\n  - 2, Bob\n",
+    );
+    let config: Config = toon_rs::de::direct::from_str(&doc, &Options::default()).unwrap();
+    let at = |s: core::ops::Range<usize>| doc[s].to_string();
+    assert_eq!(at(config.timeout.start()..config.timeout.end()), "90000");
+    assert_eq!(
+        at(config.server.start()..config.server.end()),
+        "host: example.com\n  port: 8080"
+    );
+    let host = &config.server.get_ref().host;
+    assert_eq!((host.span().line, host.span().column), (4, 9));
+    assert_eq!(at(config.tags.start()..config.tags.end()), "a,b");
+    let user = &config.users[1];
+    assert_eq!(at(user.start()..user.end()), "2, Bob");
+    assert_eq!(user.get_ref().id.span().line, 10);
+}
+
+#[test]
+fn semantic_errors_can_point_at_the_field() {
+    #[derive(Deserialize)]
+    struct Limits {
+        timeout: Spanned<u32>,
+    }
+    let doc = "timeout: 90000\n";
+    let limits: Limits = toon_rs::from_str(doc, &Options::default()).unwrap();
+    let timeout = &limits.timeout;
+    let message = format!(
+        "field `timeout` on line {} is out of range",
+        timeout.span().line
+    );
+    assert_eq!(message, "field `timeout` on line 1 is out of range");
+    assert_eq!(
+        timeout.span().snippet(doc),
+        "1 | timeout: 90000\n  |          ^^^^^"
+    );
+}
+
+#[test]
+fn compares_and_serializes_as_the_inner_value() {
+    let a: Spanned<u32> = toon_rs::from_str("7", &Options::default()).unwrap();
+    let b = Spanned::new(Default::default(), 7u32);
+    assert_eq!(a, b);
+    assert_eq!(a.span().line, 1);
+    assert_eq!(toon_rs::to_value(&a).unwrap(), Value::from(7));
+}
+
+#[test]
+fn value_trees_have_no_spans() {
+    let err = toon_rs::from_value::<Spanned<u32>>(Value::from(7)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TypeMismatch);
+}
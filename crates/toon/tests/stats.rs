@@ -0,0 +1,77 @@
+#![cfg(feature = "serde")]
+use toon_rs::stats::{self, ApproxTokenizer, Comparison, Size, Tokenizer};
+use toon_rs::{Options, toon};
+
+#[test]
+fn approx_tokenizer_counts_words_numbers_and_punctuation() {
+    let count = |text: &str| ApproxTokenizer.count_tokens(text);
+    assert_eq!(count(""), 0);
+    assert_eq!(count("hello world"), 2);
+    assert_eq!(count("internationalization"), 3);
+    assert_eq!(count("1234567"), 3);
+    assert_eq!(count(r#"{"id":1}"#), 5);
+    assert_eq!(count("a:\n  b: 1\n"), 7);
+    assert_eq!(count("a\n\n    b"), 4);
+    assert_eq!(count("日本"), 2);
+}
+
+#[test]
+fn report_lists_subtrees_and_tables_by_path() -> Result<(), toon_rs::Error> {
+    let value = toon!({
+        "data": {
+            "users": [{ "id": 1, "name": "Ada" }, { "id": 2, "name": "Bob" }],
+            "last seen": { "id": 2 }
+        },
+        "orders": [{ "lines": [{ "sku": "A", "qty": 1 }] }, 3],
+        "count": 2
+    });
+    let report = stats::report(&value, &Options::default(), &ApproxTokenizer)?;
+    let subtrees: Vec<&str> = report.subtrees.iter().map(|s| s.path.as_str()).collect();
+    assert_eq!(
+        subtrees,
+        ["data", "data.users", "data.\"last seen\"", "orders"]
+    );
+    let tables: Vec<(&str, usize, Vec<String>)> = report
+        .tables
+        .iter()
+        .map(|t| (t.path.as_str(), t.rows, t.fields.clone()))
+        .collect();
+    assert_eq!(
+        tables,
+        [
+            ("data.users", 2, vec!["id".into(), "name".into()]),
+            ("orders[0].lines", 1, vec!["sku".into(), "qty".into()]),
+        ]
+    );
+
+    // JSON is measured compact, as serde_json writes it
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(report.total.json.bytes, json.len());
+    assert_eq!(
+        report.subtrees[1].size.json.bytes,
+        r#""users":[{"id":1,"name":"Ada"},{"id":2,"name":"Bob"}]"#.len()
+    );
+    assert_eq!(report.tables[0].size, report.subtrees[1].size);
+    assert!(report.tables[0].size.token_savings() > 0.0);
+
+    // A root table has an empty path
+    let report = stats::report(&toon!([{ "a": 1 }]), &Options::default(), &ApproxTokenizer)?;
+    assert!(report.subtrees.is_empty());
+    assert_eq!(report.tables[0].path, "");
+    Ok(())
+}
+
+#[test]
+fn savings_are_a_share_of_the_json_tokens() {
+    let size = |tokens| Size { bytes: 0, tokens };
+    let saved = |json, toon| {
+        Comparison {
+            json: size(json),
+            toon: size(toon),
+        }
+        .token_savings()
+    };
+    assert_eq!(saved(10, 6), 0.4);
+    assert_eq!(saved(10, 15), -0.5);
+    assert_eq!(saved(0, 0), 0.0);
+}
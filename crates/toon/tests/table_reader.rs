@@ -0,0 +1,139 @@
+#![cfg(feature = "json")]
+use std::io::{BufReader, Read};
+
+use serde::Deserialize;
+use toon_rs::{Options, TableReader};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Item {
+    id: u64,
+    name: String,
+    active: bool,
+}
+
+fn item(id: u64, name: &str, active: bool) -> Item {
+    Item {
+        id,
+        name: name.into(),
+        active,
+    }
+}
+
+#[test]
+fn table_reader_yields_typed_rows() {
+    let s = "items[3]{id,name,active}:\n  1,Ada,true\n  2,\"Bob, Jr\",false\n  3,Cy,true\n";
+    let rows = TableReader::<Item, _>::new(s.as_bytes(), &Options::default()).unwrap();
+    assert_eq!(rows.key(), "items");
+    assert_eq!(rows.fields(), ["id", "name", "active"]);
+    assert_eq!(rows.declared_len(), 3);
+    let items: Vec<Item> = rows.collect::<toon_rs::Result<_>>().unwrap();
+    assert_eq!(
+        items,
+        vec![
+            item(1, "Ada", true),
+            item(2, "Bob, Jr", false),
+            item(3, "Cy", true)
+        ]
+    );
+}
+
+#[test]
+fn table_reader_keyless_and_pipe_delimited() {
+    let s = "[2|]{id|name|active}:\n  1|a|true\n  2|b|false\n";
+    let items: Vec<Item> = TableReader::new(s.as_bytes(), &Options::default())
+        .unwrap()
+        .collect::<toon_rs::Result<_>>()
+        .unwrap();
+    assert_eq!(items, vec![item(1, "a", true), item(2, "b", false)]);
+}
+
+#[test]
+fn table_reader_checks_declared_length_in_strict_mode() {
+    let s = "items[3]{id,name,active}:\n  1,Ada,true\n";
+    let mut rows = TableReader::<Item, _>::new(s.as_bytes(), &Options::default()).unwrap();
+    assert!(rows.next().unwrap().is_ok());
+    let err = rows.next().unwrap().unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("tabular array has 1 rows but header declares 3")
+    );
+    assert!(rows.next().is_none());
+
+    let lenient = Options {
+        strict: false,
+        ..Options::default()
+    };
+    let rows = TableReader::<Item, _>::new(s.as_bytes(), &lenient).unwrap();
+    assert_eq!(rows.count(), 1);
+}
+
+#[test]
+fn table_reader_reports_bad_rows_and_headers() {
+    let s = "items[2]{id,name,active}:\n  1,Ada\n";
+    let mut rows = TableReader::<Item, _>::new(s.as_bytes(), &Options::default()).unwrap();
+    let err = rows.next().unwrap().unwrap_err();
+    assert!(err.to_string().contains("line 2"));
+    assert!(rows.next().is_none());
+
+    for s in ["a: 1\n", "items[2]: 1,2\n", ""] {
+        assert!(TableReader::<Item, _>::new(s.as_bytes(), &Options::default()).is_err());
+    }
+}
+
+#[test]
+fn table_reader_rejects_content_after_the_table() {
+    let s = "items[1]{id,name,active}:\n  1,Ada,true\n\ncount: 1\n";
+    let lenient = Options {
+        strict: false,
+        ..Options::default()
+    };
+    for options in [Options::default(), lenient] {
+        let mut rows = TableReader::<Item, _>::new(s.as_bytes(), &options).unwrap();
+        assert!(rows.next().unwrap().is_ok());
+        let err = rows.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), toon_rs::ErrorKind::Syntax);
+        assert!(err.to_string().contains("line 4"));
+        assert!(rows.next().is_none());
+    }
+}
+
+/// Generates `items[N]{...}:` and its rows on the fly, one line at a time.
+struct Generated {
+    rows: u64,
+    next: u64,
+    pending: Vec<u8>,
+}
+
+impl Read for Generated {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pending.is_empty() {
+            if self.next == 0 {
+                self.pending = format!("items[{}]{{id,name,active}}:\n", self.rows).into_bytes();
+            } else if self.next <= self.rows {
+                self.pending = format!("  {},n{},true\n", self.next, self.next).into_bytes();
+            } else {
+                return Ok(0);
+            }
+            self.next += 1;
+        }
+        let n = buf.len().min(self.pending.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(..n);
+        Ok(n)
+    }
+}
+
+#[test]
+fn table_reader_streams_large_tables() {
+    let src = Generated {
+        rows: 100_000,
+        next: 0,
+        pending: Vec::new(),
+    };
+    let rows = TableReader::<Item, _>::new(BufReader::new(src), &Options::default()).unwrap();
+    let mut sum = 0;
+    for row in rows {
+        sum += row.unwrap().id;
+    }
+    assert_eq!(sum, 100_000 * 100_001 / 2);
+}
@@ -0,0 +1,168 @@
+#![cfg(feature = "json")]
+use serde::Serialize;
+use serde_json::json;
+use toon_rs::{Delimiter, ErrorKind, Options, TableWriter};
+
+#[derive(Serialize)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn users(n: u32) -> Vec<User> {
+    (0..n)
+        .map(|id| User {
+            id,
+            name: format!("user, {id}"),
+        })
+        .collect()
+}
+
+#[test]
+fn table_writer_matches_encoder() {
+    let opts = Options::default();
+    let rows = users(50);
+    let expected = toon_rs::ser::to_string_streaming(&json!({ "users": &rows }), &opts).unwrap();
+
+    let mut out = Vec::new();
+    let mut table =
+        TableWriter::new(&mut out, "users", &["id", "name"], rows.len(), &opts).unwrap();
+    for row in &rows {
+        table.write_row(row).unwrap();
+    }
+    assert_eq!(table.rows_written(), 50);
+    table.finish().unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), expected);
+}
+
+#[test]
+fn table_writer_into_string_sink_with_pipes() {
+    let opts = Options {
+        delimiter: Delimiter::Pipe,
+        ..Options::default()
+    };
+    let mut table = TableWriter::with_sink(String::new(), "t", &["a", "b"], 2, &opts).unwrap();
+    table
+        .write_rows([json!({"b": "x|y", "a": 1}), json!({"a": null, "b": true})])
+        .unwrap();
+    let s = table.finish().unwrap();
+    assert_eq!(s, "t[2|]{a|b}:\n  1|\"x|y\"\n  null|true");
+    let back: serde_json::Value = toon_rs::decode_from_str(&s, &opts).unwrap();
+    assert_eq!(
+        back,
+        json!({"t": [{"a": 1, "b": "x|y"}, {"a": null, "b": true}]})
+    );
+}
+
+#[test]
+fn table_writer_checks_row_count() {
+    let opts = Options::default();
+    let table = TableWriter::with_sink(String::new(), "u", &["id", "name"], 1, &opts).unwrap();
+    let err = table.finish().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::LengthMismatch);
+    assert!(
+        err.to_string()
+            .contains("declares 1 rows but 0 were written"),
+        "{err}"
+    );
+
+    let mut table = TableWriter::with_sink(String::new(), "u", &["id", "name"], 1, &opts).unwrap();
+    table.write_rows(users(1)).unwrap();
+    let err = table.write_rows(users(1)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::LengthMismatch);
+    assert!(err.to_string().contains("more were written"), "{err}");
+}
+
+#[test]
+fn table_writer_rejects_bad_rows() {
+    let opts = Options::default();
+    for (row, kind, needle) in [
+        (
+            json!({"a": 1}),
+            ErrorKind::LengthMismatch,
+            "has 1 fields but header declares 2",
+        ),
+        (
+            json!({"a": 1, "c": 2}),
+            ErrorKind::TypeMismatch,
+            "missing field `b`",
+        ),
+        (
+            json!({"a": 1, "b": [1]}),
+            ErrorKind::TypeMismatch,
+            "field `b` is not a primitive",
+        ),
+        (
+            json!({"a": 1, "b": 2, "c": 3}),
+            ErrorKind::LengthMismatch,
+            "has 3 fields",
+        ),
+        (json!([1, 2]), ErrorKind::TypeMismatch, "is not an object"),
+    ] {
+        let mut table = TableWriter::with_sink(String::new(), "t", &["a", "b"], 1, &opts).unwrap();
+        let err = table.write_row(&row).unwrap_err();
+        assert_eq!(err.kind(), kind, "{row}: {err}");
+        assert!(err.to_string().contains(needle), "{row}: {err}");
+        assert_eq!(table.rows_written(), 0);
+    }
+}
+
+#[test]
+fn table_writer_reports_serialize_errors() {
+    struct Broken;
+    impl Serialize for Broken {
+        fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("no rows today"))
+        }
+    }
+
+    let opts = Options::default();
+    let mut table = TableWriter::with_sink(String::new(), "t", &["a"], 1, &opts).unwrap();
+    let err = table.write_row(&Broken).unwrap_err();
+    assert!(err.to_string().contains("no rows today"), "{err}");
+    assert_eq!(table.rows_written(), 0);
+}
+
+#[test]
+fn table_writer_refuses_rows_after_a_failed_write() {
+    struct Broken;
+    impl std::io::Write for Broken {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("disk full"))
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let opts = Options::default();
+    let rows = users(5000);
+    let mut table = TableWriter::new(Broken, "users", &["id", "name"], rows.len(), &opts).unwrap();
+    let err = table.write_rows(&rows).unwrap_err();
+    assert!(table.rows_written() < rows.len());
+    assert!(err.to_string().contains("failed"), "{err}");
+    let err = table.finish().err().unwrap();
+    assert!(matches!(err, toon_rs::Error::Io(_)), "{err}");
+}
+
+#[test]
+fn table_writer_rejects_empty_and_duplicate_fields() {
+    let opts = Options::default();
+    let mut out = Vec::new();
+    let none: &[&str] = &[];
+    let err = TableWriter::new(&mut out, "t", none, 0, &opts)
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("at least one field"), "{err}");
+
+    let err = TableWriter::new(&mut out, "t", &["id", "id"], 0, &opts)
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), toon_rs::ErrorKind::DuplicateKey);
+    assert!(
+        err.to_string().contains("field `id` appears twice"),
+        "{err}"
+    );
+    // Nothing was written before the error
+    assert!(out.is_empty());
+}
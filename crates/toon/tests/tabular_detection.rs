@@ -23,3 +23,11 @@ fn tabular_detection_negative_nested_values() {
     ];
     assert!(is_tabular_array(&arr).is_none());
 }
+
+#[test]
+fn tabular_detection_negative_empty_objects() {
+    let arr = vec![json!({}), json!({})];
+    assert!(is_tabular_array(&arr).is_none());
+    let s = toon_rs::encode_to_string(&json!({ "a": arr }), &toon_rs::Options::default()).unwrap();
+    assert_eq!(s, "a[2]:\n  -\n  -");
+}
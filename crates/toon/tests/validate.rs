@@ -0,0 +1,92 @@
+use toon_rs::{ErrorKind, Options, Severity, validate};
+
+const FIVE_DEFECTS: &str = "\
+users[2]{id,name}:
+  1,Ada
+  2,Bob,extra
+tags[3]: a,b
+meta:
+   owner: ops
+note: \"unterminated
+bad: \"x\\q\"
+";
+
+#[test]
+fn reports_every_problem_in_order() {
+    let found = validate(FIVE_DEFECTS, &Options::default());
+    let summary: Vec<(usize, ErrorKind)> = found.iter().map(|d| (d.span.line, d.kind)).collect();
+    assert_eq!(
+        summary,
+        [
+            (3, ErrorKind::LengthMismatch),
+            (4, ErrorKind::LengthMismatch),
+            (6, ErrorKind::Indentation),
+            (7, ErrorKind::UnterminatedString),
+            (8, ErrorKind::InvalidEscape),
+        ]
+    );
+    assert!(found.iter().all(|d| d.is_error()));
+    assert_eq!(
+        found[0].to_string(),
+        "error at line 3, column 3: tabular row has 3 values but header declares 2 fields"
+    );
+    assert_eq!(
+        found[3].snippet(FIVE_DEFECTS),
+        "7 | note: \"unterminated\n  |       ^^^^^^^^^^^^^"
+    );
+}
+
+#[test]
+fn first_diagnostic_matches_decode_error() {
+    let lenient = Options {
+        strict: false,
+        ..Options::default()
+    };
+    // `- ` table rows are only read outside strict mode
+    let hyphen_rows = "t[2]{a,b}:\n  - 1,2\n  - 3,4\n";
+    for (doc, options) in [
+        (FIVE_DEFECTS, Options::default()),
+        (hyphen_rows, Options::default()),
+        (hyphen_rows, lenient.clone()),
+        ("a:\n   b: 1\nc: \"open\n", lenient),
+    ] {
+        let decoded = toon_rs::decode_from_str::<toon_rs::Value>(doc, &options);
+        let errors: Vec<_> = validate(doc, &options)
+            .into_iter()
+            .filter(|d| d.is_error())
+            .collect();
+        match decoded {
+            Ok(_) => assert!(errors.is_empty(), "{doc:?}: {errors:?}"),
+            Err(err) => {
+                assert_eq!(err.span(), Some(errors[0].span), "{doc:?}");
+                assert_eq!(err.kind(), errors[0].kind, "{doc:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn valid_documents_have_no_diagnostics() {
+    let doc = "users[2]{id,name}:\n  1,Ada\n  2,Bob\ntags[2]: a,b\n";
+    assert!(validate(doc, &Options::default()).is_empty());
+    assert!(validate("", &Options::default()).is_empty());
+}
+
+#[test]
+fn strict_only_problems_are_warnings_in_lenient_mode() {
+    let doc = "a:\n   b: 1\nc: \"open\n";
+    let lenient = Options {
+        strict: false,
+        ..Options::default()
+    };
+    let found = validate(doc, &lenient);
+    let summary: Vec<(usize, Severity)> = found.iter().map(|d| (d.span.line, d.severity)).collect();
+    assert_eq!(summary, [(2, Severity::Warning), (3, Severity::Error)]);
+}
+
+#[test]
+fn parser_keeps_going_after_errors() {
+    let mut p = toon_rs::decode::parser::Parser::from_input_with_strict("a: \"x\nb: \"y\n", true);
+    p.parse_document();
+    assert_eq!(p.errors().len(), 2);
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use toon_rs::value::Number;
+use toon_rs::{Map, Options, Value, toon};
+
+#[test]
+fn macro_builds_nested_values() {
+    let id = 7u32;
+    let v = toon!({
+        "id": id,
+        "name": "Ada",
+        "tags": ["a", "b", null, true, 1.5],
+        "nested": { "empty": [], "obj": {} },
+        ("computed".to_string() + "_key"): -3,
+    });
+    assert_eq!(v["id"], 7);
+    assert_eq!(v["name"], "Ada");
+    assert_eq!(v["tags"].as_array().map(Vec::len), Some(5));
+    assert!(v["tags"][2].is_null());
+    assert_eq!(v["tags"][3], true);
+    assert_eq!(v["tags"][4], 1.5);
+    assert_eq!(v["nested"]["empty"], Value::Array(vec![]));
+    assert_eq!(v["nested"]["obj"], Value::Object(Map::new()));
+    assert_eq!(v["computed_key"].as_i64(), Some(-3));
+    assert_eq!(toon!(null), Value::Null);
+    assert_eq!(toon!([1, [2, 3]])[1][0], 2);
+}
+
+#[test]
+fn accessors_and_indexing() {
+    let mut v = toon!({ "n": 5, "big": 18446744073709551615u64, "s": "x", "arr": [1, 2] });
+    assert_eq!(v["n"].as_u64(), Some(5));
+    assert_eq!(v["n"].as_f64(), Some(5.0));
+    assert_eq!(v["big"].as_i64(), None);
+    assert_eq!(v["s"].as_str(), Some("x"));
+    assert_eq!(v["s"].as_i64(), None);
+    assert!(v.get("missing").is_none());
+    assert!(v["missing"]["deeper"][3].is_null());
+    assert_eq!(v.get("arr").and_then(|a| a.get(1)), Some(&Value::from(2)));
+
+    v["new"]["inner"] = "set".into();
+    v["arr"][0] = toon!({ "k": false });
+    assert_eq!(v["new"]["inner"], "set");
+    assert_eq!(v["arr"][0]["k"], false);
+    assert_eq!(v["s"].take(), "x");
+    assert!(v["s"].is_null());
+    v.as_object_mut().unwrap().remove("big");
+    assert!(!v.as_object().unwrap().contains_key("big"));
+}
+
+#[test]
+#[should_panic(expected = "cannot access index 5 of array of length 2")]
+fn index_mut_past_end_panics() {
+    let mut v = toon!([1, 2]);
+    v[5] = Value::Null;
+}
+
+#[test]
+fn numbers_compare_by_value() {
+    assert_eq!(Number::I64(3), Number::U64(3));
+    assert_ne!(Number::I64(-1), Number::U64(u64::MAX));
+    assert_ne!(Number::F64(3.0), Number::I64(3));
+    assert_eq!(Value::from(f64::NAN), Value::Null);
+}
+
+#[test]
+fn map_keeps_insertion_order() {
+    let mut m: Map = [("b", 1), ("a", 2)].into_iter().collect();
+    assert_eq!(m.insert("b".into(), 3.into()), Some(Value::from(1)));
+    m.insert("c".into(), Value::Null);
+    assert_eq!(
+        m.keys().map(String::as_str).collect::<Vec<_>>(),
+        ["b", "a", "c"]
+    );
+    assert_eq!(m["b"], 3);
+    m.retain(|_, v| !v.is_null());
+    assert_eq!(m.len(), 2);
+}
+
+#[test]
+fn display_and_from_str_roundtrip() {
+    let v = toon!({
+        "users": [{ "id": 1, "name": "Ada" }, { "id": 2, "name": "Bob" }],
+        "count": 2,
+    });
+    let text = v.to_string();
+    assert_eq!(text, "users[2]{id,name}:\n  1,Ada\n  2,Bob\ncount: 2");
+    let back: Value = text.parse().unwrap();
+    assert_eq!(back, v);
+    assert!("a: \"unterminated".parse::<Value>().is_err());
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[test]
+fn value_is_serde_compatible() {
+    let v = toon!({ "id": 1, "name": "Ada" });
+    let text = toon_rs::encode_to_string(&v, &Options::default()).unwrap();
+    let user: User = toon_rs::from_str(&text, &Options::default()).unwrap();
+    assert_eq!(
+        user,
+        User {
+            id: 1,
+            name: "Ada".into()
+        }
+    );
+    let decoded: Value = toon_rs::from_str("id: 1\nname: Ada", &Options::default()).unwrap();
+    assert_eq!(decoded, v);
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "serde")]
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use toon_rs::{ExpandPaths, Options, Value, from_value, to_value, toon};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Point(i32, i32),
+    Rect { w: u32, h: u32 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Scene {
+    name: String,
+    shapes: Vec<Shape>,
+    parent: Option<u32>,
+    tags: BTreeMap<String, bool>,
+}
+
+fn scene() -> Scene {
+    Scene {
+        name: "demo".into(),
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(1.5),
+            Shape::Point(-1, 2),
+            Shape::Rect { w: 3, h: 4 },
+        ],
+        parent: None,
+        tags: BTreeMap::from([("draft".to_string(), true)]),
+    }
+}
+
+#[test]
+fn to_value_builds_externally_tagged_tree() {
+    let v = to_value(&scene()).unwrap();
+    let expected = toon!({
+        "name": "demo",
+        "shapes": ["Empty", {"Circle": 1.5}, {"Point": [-1, 2]}, {"Rect": {"w": 3, "h": 4}}],
+        "parent": null,
+        "tags": {"draft": true},
+    });
+    assert_eq!(v, expected);
+}
+
+#[test]
+fn from_value_roundtrips() {
+    let v = to_value(&scene()).unwrap();
+    let back: Scene = from_value(v).unwrap();
+    assert_eq!(back, scene());
+}
+
+#[test]
+fn borrowed_deserializer_yields_str_slices() {
+    #[derive(Deserialize)]
+    struct Row<'a> {
+        id: u64,
+        name: &'a str,
+        parent: Option<u32>,
+    }
+
+    let doc: Value = "rows[2]{id,name,parent}:\n  1,Ada,null\n  2,Bob,1"
+        .parse()
+        .unwrap();
+    let rows = Vec::<Row>::deserialize(&doc["rows"]).unwrap();
+    assert_eq!(rows[1].name, "Bob");
+    assert_eq!(rows[1].parent, Some(1));
+    assert_eq!(rows[0].id, 1);
+    // The tree is untouched and can be reused
+    assert_eq!(doc["rows"][0]["name"], "Ada");
+}
+
+#[test]
+fn type_errors_are_reported() {
+    let err = from_value::<Scene>(toon!({"name": 5})).unwrap_err();
+    assert!(err.to_string().contains("invalid type"), "{err}");
+    let err = from_value::<(u8, u8)>(toon!([1, 2, 3])).unwrap_err();
+    assert!(err.to_string().contains("invalid length"), "{err}");
+    let err = to_value(&BTreeMap::from([(vec![1], 1)])).unwrap_err();
+    assert!(err.to_string().contains("key must be"), "{err}");
+}
+
+#[test]
+fn expanded_paths_deserialize_options_and_enums() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Doc {
+        a: Inner,
+    }
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Inner {
+        b: Option<u32>,
+        kind: Shape,
+    }
+
+    let opts = Options {
+        expand_paths: ExpandPaths::Safe,
+        ..Options::default()
+    };
+    let doc: Doc = toon_rs::from_str("a.b: 7\na.kind: Empty", &opts).unwrap();
+    assert_eq!(
+        doc,
+        Doc {
+            a: Inner {
+                b: Some(7),
+                kind: Shape::Empty
+            }
+        }
+    );
+}
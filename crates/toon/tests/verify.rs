@@ -0,0 +1,101 @@
+#![cfg(feature = "serde")]
+use toon_rs::{ErrorKind, KeyFolding, Options, Value, encode_verified, toon, verify_roundtrip};
+
+fn mismatch(original: Value, text: &str) -> (Option<String>, String) {
+    let err = verify_roundtrip(&original, text, &Options::default()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::RoundTrip);
+    (err.path().map(String::from), err.to_string())
+}
+
+#[test]
+fn encoded_values_come_back() {
+    let folding = Options {
+        key_folding: KeyFolding::Safe,
+        ..Options::default()
+    };
+    for value in [
+        toon!({}),
+        toon!([]),
+        toon!({ "a": {}, "b": [], "c": [{}], "d": [[]] }),
+        toon!({ "big": u64::MAX, "neg": i64::MIN, "f": 1.0, "tiny": 1e-7, "huge": 1e300 }),
+        toon!({ "s": ["1", "true", "null", "", " x ", "a,b", "-"] }),
+        toon!({ "users": [{ "id": 1, "name": "Ada" }, { "name": "Bob", "id": 2 }] }),
+        toon!({ "a": { "b": { "c": 1 } }, "d": { "e": [1] } }),
+    ] {
+        encode_verified(&value, &Options::default()).unwrap();
+        encode_verified(&value, &folding).unwrap();
+    }
+
+    // A literal dotted key next to a folded one cannot be told apart when
+    // the folds are expanded again
+    let value = toon!({ "a": { "b": { "c": 1 } }, "a.b": 2 });
+    encode_verified(&value, &Options::default()).unwrap();
+    let err = encode_verified(&value, &folding).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::RoundTrip);
+
+    #[derive(serde::Serialize)]
+    struct Row {
+        id: u32,
+        score: f32,
+        tags: Vec<&'static str>,
+    }
+    let rows = [Row {
+        id: 1,
+        score: 0.5,
+        tags: vec![],
+    }];
+    assert_eq!(
+        encode_verified(&rows, &Options::default()).unwrap(),
+        "[1]:\n  - id: 1\n    score: 0.5\n    tags[0]:"
+    );
+}
+
+#[test]
+fn the_first_difference_is_reported_with_its_path() {
+    assert_eq!(
+        mismatch(toon!({ "a": 1, "b": 2 }), "a: 1\n"),
+        (
+            Some("b".into()),
+            "b: the key is missing after decoding".into()
+        )
+    );
+    assert_eq!(
+        mismatch(toon!({ "a": 1 }), "a: 1\nc: 3\n").0.as_deref(),
+        Some("c")
+    );
+    assert_eq!(
+        mismatch(toon!({ "x y": { "n": [1, 2] } }), "\"x y\":\n  n[1]: 1\n").1,
+        "\"x y\".n: encoded 2 items but decoded 1"
+    );
+    assert_eq!(
+        mismatch(
+            toon!({ "rows": [{ "f": 1.5 }, { "f": 2 }] }),
+            "rows[2]{f}:\n  1.5\n  2.5\n"
+        )
+        .1,
+        "rows[1].f: encoded the number 2 but decoded the number 2.5"
+    );
+    assert_eq!(
+        mismatch(toon!([true]), "[1]: \"true\"").1,
+        "[0]: encoded true but decoded the string \"true\""
+    );
+    let (path, message) = mismatch(toon!({ "a": 1 }), "a: \"open\n");
+    assert_eq!(path.as_deref(), Some(""));
+    assert!(message.starts_with("the encoded text does not decode"));
+}
+
+#[test]
+fn numbers_compare_by_value() {
+    // 1.0 is written as 1, and integers of either sign type are the same
+    verify_roundtrip(
+        &toon!({ "f": 1.0, "i": -3, "u": 7 }),
+        "f: 1\ni: -3\nu: 7",
+        &Options::default(),
+    )
+    .unwrap();
+    // but a float that loses precision is not
+    let (path, _) = mismatch(toon!({ "f": 0.1 }), "f: 0.1000001");
+    assert_eq!(path.as_deref(), Some("f"));
+    let (path, _) = mismatch(toon!({ "u": u64::MAX }), "u: 18446744073709551616");
+    assert_eq!(path.as_deref(), Some("u"));
+}